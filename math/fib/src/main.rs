@@ -1,7 +1,10 @@
-use std::{
-    fs::File,
-    io::{Read, Write},
-    os::unix::io::FromRawFd,
+use {
+    nested::type_system::ProgramInterface,
+    std::{
+        fs::File,
+        io::{Read, Write},
+        os::unix::io::FromRawFd,
+    },
 };
 
 fn fib(n: u64) -> u64 {
@@ -19,40 +22,23 @@ fn fib(n: u64) -> u64 {
 }
 
 fn main() {
-    nested::magic_header();
-
-    eprintln!("            Fibonacci Sequence");
-
-    nested::magic_header();
-
-    eprintln!(
-        "
-interface (Sequence ℕ) 0 1"
-    );
+    ProgramInterface::new("fib", "            Fibonacci Sequence")
+        .with_stdin("n", &[
+            "( ℕ )",
+            "( MachineInt )",
+            "( MachineWord )",
+            "( Stream MachineSyllab )",
+        ])
+        .with_stdout("n'th fibonacci number", &[
+            "( ℕ )",
+            "( MachineInt )",
+            "( MachineWord )",
+            "( Stream MachineSyllab )",
+        ])
+        .announce();
 
     let mut f0 = unsafe { File::from_raw_fd(0) };
-    eprintln!(
-        "
->0: n
-  ( ℕ )
-  ( MachineInt )
-  ( MachineWord )
-  ( Stream MachineSyllab )
-"
-    );
-
     let mut f1 = unsafe { File::from_raw_fd(1) };
-    eprintln!(
-        "
-<1: n'th fibonacci number
-  ( ℕ )
-  ( MachineInt )
-  ( MachineWord )
-  ( Stream MachineSyllab )
-"
-    );
-
-    nested::magic_header();
 
     let mut bytes = [0 as u8; 8];
     f0.read_exact(&mut bytes).expect("");
@@ -1,34 +1,27 @@
-use std::{fs::File, io::Read, os::unix::io::FromRawFd};
+use {
+    nested::type_system::ProgramInterface,
+    std::{fs::File, io::Read, os::unix::io::FromRawFd},
+};
 
 fn main() {
-    nested::magic_header();
-    eprintln!("     Human-readably Print MachineInt");
-    nested::magic_header();
+    ProgramInterface::new("int2str", "     Human-readably Print MachineInt")
+        .with_stdin("n", &[
+            "( ℕ )",
+            "( MachineInt )",
+            "( MachineWord )",
+            "( Stream MachineSyllab )",
+        ])
+        .with_stdout("n", &[
+            "( ℕ )",
+            "( PosInt 10 BigEndian )",
+            "( Sequence ( Digit 10 ) )",
+            "( Sequence UTF-8-Char )",
+            "( Stream UTF-8-Char )",
+            "( Stream MachineSyllab )",
+        ])
+        .announce();
 
     let mut f0 = unsafe { File::from_raw_fd(0) };
-    eprintln!(
-        "
->0:
-  ( ℕ )
-  ( MachineInt )
-  ( MachineWord )
-  ( Stream MachineSyllab )
-"
-    );
-
-    eprintln!(
-        "
-<1:
-  ( ℕ )
-  ( PositionalInt 10 BigEndian )
-  ( Sequence ( Digit 10 ) )
-  ( Sequence UTF-8-Char )
-  ( Stream UTF-8-Char )
-  ( Stream MachineSyllab )
-"
-    );
-
-    nested::magic_header();
 
     let mut bytes = [0 as u8; 8];
     f0.read_exact(&mut bytes).expect("");
@@ -0,0 +1,66 @@
+use {
+    nested::type_system::{Context, Pipeline, Stage},
+    std::{
+        io::{Read, Write},
+        path::PathBuf,
+        sync::{Arc, RwLock},
+    },
+};
+
+const CONVERTERS: [&str; 3] = ["str2int", "int2str", "radix_transform"];
+
+/// programs without path are looked up next to this binary
+fn locate(program: &str) -> PathBuf {
+    if program.contains('/') {
+        program.into()
+    } else {
+        std::env::current_exe()
+            .expect("could not locate binary")
+            .with_file_name(program)
+    }
+}
+
+fn main() {
+    let script = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    if script.trim().is_empty() {
+        eprintln!("usage: pipeline 'PROGRAM ARGS.. | PROGRAM ARGS.. | ..'");
+        std::process::exit(1);
+    }
+
+    let ctx = Arc::new(RwLock::new(Context::default()));
+    let mut pipeline = Pipeline::new(ctx);
+
+    for name in CONVERTERS.iter() {
+        let path = locate(name);
+        if path.exists() {
+            pipeline.add_converter(&path).expect("could not query converter");
+        }
+    }
+
+    for stage in script.split('|') {
+        let mut words = stage.split_whitespace();
+        let program = words.next().expect("empty stage");
+        let args: Vec<&str> = words.collect();
+
+        match Stage::new(locate(program), &args) {
+            Ok(stage) => pipeline.push(stage),
+            Err(err) => {
+                eprintln!("{}: {}", program, err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut input = Vec::new();
+    std::io::stdin().read_to_end(&mut input).expect("could not read stdin");
+
+    match pipeline.run(&input) {
+        Ok(output) => {
+            std::io::stdout().write_all(&output).expect("could not write stdout");
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
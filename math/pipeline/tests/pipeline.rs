@@ -0,0 +1,173 @@
+//! Composes the binaries of `math/` with automatic conversions.
+
+use {
+    nested::type_system::{Context, Pipeline, PipelineError, PortDescription, ProgramInterface, Stage},
+    std::{
+        path::PathBuf,
+        process::Command,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// path of a workspace binary, built on demand
+fn bin(name: &str) -> PathBuf {
+    // tests run from target/<profile>/deps/
+    let dir = std::env::current_exe().unwrap()
+        .parent().unwrap()
+        .parent().unwrap()
+        .to_path_buf();
+    let path = dir.join(name);
+
+    if !path.exists() {
+        let cargo = std::env::var("CARGO").unwrap_or("cargo".into());
+        let status = Command::new(cargo)
+            .args(&["build", "-p", name])
+            .status()
+            .unwrap();
+        assert!(status.success(), "could not build {}", name);
+    }
+    path
+}
+
+fn stage(name: &str, args: &[&str]) -> Stage {
+    Stage::new(bin(name), args).unwrap()
+}
+
+fn pipeline() -> Pipeline {
+    let ctx = Arc::new(RwLock::new(Context::default()));
+    let mut pipeline = Pipeline::new(ctx);
+    for name in ["str2int", "int2str", "radix_transform"].iter() {
+        pipeline.add_converter(bin(name)).unwrap();
+    }
+    pipeline
+}
+
+fn stage_names(stages: &[Stage]) -> Vec<String> {
+    stages.iter()
+        .map(|s| {
+            let mut words = vec![ s.interface.name.clone() ];
+            words.extend(s.args.iter().cloned());
+            words.join(" ")
+        })
+        .collect()
+}
+
+#[test]
+fn interfaces_are_machine_readable() {
+    let fib = ProgramInterface::query(&bin("fib")).unwrap();
+    assert_eq!(fib.name, "fib");
+    assert!(fib.args.is_empty());
+    assert_eq!(
+        fib.stdin.unwrap().ladder,
+        vec!["( ℕ )", "( MachineInt )", "( MachineWord )", "( Stream MachineSyllab )"]
+    );
+
+    let str2int = ProgramInterface::query(&bin("str2int")).unwrap();
+    assert_eq!(str2int.args.len(), 1);
+    assert_eq!(str2int.args[0].name, "radix");
+}
+
+#[test]
+fn arguments_are_substituted_per_token() {
+    let ctx = Context::default();
+    let bind = |args: &[(&str, &str)]| -> Vec<(String, String)> {
+        args.iter().map(|(n, v)| (String::from(*n), String::from(*v))).collect()
+    };
+
+    // `$r` must not touch the start of `$radix`
+    let port = PortDescription::new("x", &["( Digit $r $radix )"]);
+    assert_eq!(
+        port.rungs(&ctx, &bind(&[("r", "2"), ("radix", "16")])),
+        PortDescription::new("x", &["( Digit 2 16 )"]).rungs(&ctx, &[])
+    );
+
+    // values may be composite terms
+    let port = PortDescription::new("x", &["( Sequence $item )"]);
+    assert_eq!(
+        port.rungs(&ctx, &bind(&[("item", "( Digit 10 )")])),
+        PortDescription::new("x", &["( Sequence ( Digit 10 ) )"]).rungs(&ctx, &[])
+    );
+}
+
+#[test]
+fn compatible_stages_are_connected_directly() {
+    let mut p = pipeline();
+    p.push(stage("str2int", &["10"]));
+    p.push(stage("fib", &[]));
+    p.push(stage("int2str", &[]));
+
+    assert_eq!(
+        stage_names(&p.resolve().unwrap()),
+        vec!["str2int 10", "fib", "int2str"]
+    );
+    // apart from fib(0) = 0, `fib` is one ahead: fib(n) = F(n+1)
+    assert_eq!(p.run(b"20").unwrap(), b"10946\n");
+}
+
+#[test]
+fn converters_are_inserted() {
+    // int2str writes decimal text, fib reads machine integers
+    let mut p = pipeline();
+    p.push(stage("int2str", &[]));
+    p.push(stage("fib", &[]));
+    p.push(stage("int2str", &[]));
+
+    assert_eq!(
+        stage_names(&p.resolve().unwrap()),
+        vec!["int2str", "str2int 10", "fib", "int2str"]
+    );
+    assert_eq!(p.run(&10u64.to_le_bytes()).unwrap(), b"89\n");
+}
+
+#[test]
+fn identical_ladders_need_no_converter() {
+    let mut p = pipeline();
+    p.push(stage("fib", &[]));
+    p.push(stage("fib", &[]));
+    p.push(stage("int2str", &[]));
+
+    // no conversion needed between two fib stages
+    assert_eq!(stage_names(&p.resolve().unwrap()), vec!["fib", "fib", "int2str"]);
+    // fib(fib(5)) = fib(8)
+    assert_eq!(p.run(&5u64.to_le_bytes()).unwrap(), b"34\n");
+}
+
+#[test]
+fn incompatible_stages_are_rejected() {
+    // nothing produces hexadecimal text
+    let mut p = pipeline();
+    p.push(stage("fib", &[]));
+    p.push(stage("str2int", &["16"]));
+
+    match p.resolve() {
+        Err(PipelineError::NoConversion { stage, .. }) => assert_eq!(stage, 0),
+        other => panic!("expected type error, got {:?}", other.map(|s| stage_names(&s))),
+    }
+}
+
+#[test]
+fn missing_arguments_are_rejected() {
+    let mut p = pipeline();
+    p.push(stage("str2int", &[]));
+
+    match p.resolve() {
+        Err(PipelineError::BadArgs { expected, got, .. }) => assert_eq!((expected, got), (1, 0)),
+        other => panic!("expected argument error, got {:?}", other.map(|s| stage_names(&s))),
+    }
+}
+
+#[test]
+fn failing_stages_are_reported() {
+    // not a decimal number, str2int fails and int2str gets no input
+    let mut p = pipeline();
+    p.push(stage("str2int", &["10"]));
+    p.push(stage("int2str", &[]));
+
+    match p.run(b"xyz") {
+        Err(PipelineError::StageFailed { stage, status }) => {
+            assert_eq!(stage, 0);
+            assert!(!status.success());
+        }
+        other => panic!("expected failing stage, got {:?}", other),
+    }
+}
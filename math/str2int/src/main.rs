@@ -1,48 +1,36 @@
-use std::{
-    fs::File,
-    io::{Read, Write},
-    os::unix::io::FromRawFd,
+use {
+    nested::type_system::ProgramInterface,
+    std::{
+        fs::File,
+        io::{Read, Write},
+        os::unix::io::FromRawFd,
+    },
 };
 
 fn main() {
-    nested::magic_header();
-    eprintln!("       Parse MachineInt from String");
-    nested::magic_header();
-
-    eprintln!(
-        "
-$1: radix
-  ( ℕ )
-  ( PositionalInt 10 BigEndian )
-  ( Sequence ( Digit 10 ) )
-  ( Sequence UTF-8-Char )
-  ( Sequence MachineSyllab )
-"
-    );
-
-    eprintln!(
-        "
->0: n
-  ( ℕ )
-  ( PositionalInt $radix BigEndian )
-  ( Sequence ( Digit $radix ) )
-  ( Sequence UTF-8-Char )
-  ( Stream UTF-8-Char )
-  ( Stream MachineSyllab )
-"
-    );
-
-    eprintln!(
-        "
-<1: n
-  ( ℕ )
-  ( MachineInt )
-  ( MachineWord )
-  ( Stream MachineSyllab )
-"
-    );
-
-    nested::magic_header();
+    ProgramInterface::new("str2int", "       Parse MachineInt from String")
+        .with_arg("radix", &[
+            "( ℕ )",
+            "( PosInt 10 BigEndian )",
+            "( Sequence ( Digit 10 ) )",
+            "( Sequence UTF-8-Char )",
+            "( Sequence MachineSyllab )",
+        ])
+        .with_stdin("n", &[
+            "( ℕ )",
+            "( PosInt $radix BigEndian )",
+            "( Sequence ( Digit $radix ) )",
+            "( Sequence UTF-8-Char )",
+            "( Stream UTF-8-Char )",
+            "( Stream MachineSyllab )",
+        ])
+        .with_stdout("n", &[
+            "( ℕ )",
+            "( MachineInt )",
+            "( MachineWord )",
+            "( Stream MachineSyllab )",
+        ])
+        .announce();
 
     let mut f0 = unsafe { File::from_raw_fd(0) };
     let mut f1 = unsafe { File::from_raw_fd(1) };
@@ -4,15 +4,26 @@ use {
         buffer::{vec::*, index_hashmap::*}
     },
     crate::{
+        type_system::Context,
         terminal::{
-            TerminalView, TerminalStyle, make_label
+            TerminalView, TerminalStyle, Theme, make_label
         }
     },
+    std::sync::{Arc, RwLock},
     cgmath::Point2
 };
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MessageKind {
+    Error,
+    Warning,
+    Todo,
+    Info,
+}
+
 #[derive(Clone)]
 pub struct Message {
+    pub kind: MessageKind,
     pub addr: Vec<usize>,
     pub port: OuterViewPort<dyn TerminalView>
 }
@@ -23,91 +34,50 @@ pub trait Diagnostics {
     }
 }
 
-pub fn make_error(msg: OuterViewPort<dyn TerminalView>) -> Message {
-    let mut mb = IndexBuffer::new();
-    mb.insert_iter(vec![
-        (Point2::new(0, 0),
-         make_label("error: ")
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::bold(true))
-                   .add_style_back(TerminalStyle::fg_color((200,0,0))))
-        ),
-        (Point2::new(1, 0),
-         msg
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::fg_color((180,180,180))))
-        )
-    ]);
-
-    Message {
-        addr: vec![],
-        port: mb.get_port().flatten()
-    }
+pub fn make_error(ctx: &Arc<RwLock<Context>>, msg: OuterViewPort<dyn TerminalView>) -> Message {
+    make_message(ctx, MessageKind::Error, "error: ", |theme| theme.error, msg)
 }
 
-pub fn make_warn(msg: OuterViewPort<dyn TerminalView>) -> Message {
-    let mut mb = IndexBuffer::new();
-    mb.insert_iter(vec![
-        (Point2::new(0, 0),
-         make_label("warning: ")
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::bold(true))
-                   .add_style_back(TerminalStyle::fg_color((200,200,0))))
-        ),
-        (Point2::new(1, 0),
-         msg
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::fg_color((180,180,180))))
-        )
-    ]);
-
-    Message {
-        addr: vec![],
-        port: mb.get_port().flatten()
-    }
+pub fn make_warn(ctx: &Arc<RwLock<Context>>, msg: OuterViewPort<dyn TerminalView>) -> Message {
+    make_message(ctx, MessageKind::Warning, "warning: ", |theme| theme.warning, msg)
 }
 
-pub fn make_todo(msg: OuterViewPort<dyn TerminalView>) -> Message {
-    let mut mb = IndexBuffer::new();
-    mb.insert_iter(vec![
-        (Point2::new(0, 0),
-         make_label("todo: ")
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::bold(true))
-                   .add_style_back(TerminalStyle::fg_color((180,180,250))))
-        ),
-        (Point2::new(1, 0),
-         msg
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::fg_color((180,180,180))))
-        )
-    ]);
+pub fn make_todo(ctx: &Arc<RwLock<Context>>, msg: OuterViewPort<dyn TerminalView>) -> Message {
+    make_message(ctx, MessageKind::Todo, "todo: ", |theme| theme.todo, msg)
+}
 
-    Message {
-        addr: vec![],
-        port: mb.get_port().flatten()
-    }
+pub fn make_info(ctx: &Arc<RwLock<Context>>, msg: OuterViewPort<dyn TerminalView>) -> Message {
+    make_message(ctx, MessageKind::Info, "info: ", |theme| theme.info, msg)
 }
 
-pub fn make_info(msg: OuterViewPort<dyn TerminalView>) -> Message {
+fn make_message(
+    ctx: &Arc<RwLock<Context>>,
+    kind: MessageKind,
+    label: &str,
+    label_style: impl Fn(&Theme) -> TerminalStyle + Send + Sync + 'static,
+    msg: OuterViewPort<dyn TerminalView>
+) -> Message {
+    let theme = ctx.read().unwrap().get_theme();
     let mut mb = IndexBuffer::new();
     mb.insert_iter(vec![
         (Point2::new(0, 0),
-         make_label("info: ")
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::bold(true))
-                   .add_style_back(TerminalStyle::fg_color((180,180,250))))
+         make_label(label)
+         .map_item({
+             let theme = theme.clone();
+             move |_p,a| a
+                 .add_style_back(label_style(&theme.read().unwrap()))
+         })
         ),
         (Point2::new(1, 0),
          msg
-         .map_item(|_p,a| a
-                   .add_style_back(TerminalStyle::fg_color((180,180,180))))
+         .map_item(move |_p,a| a
+                   .add_style_back(theme.read().unwrap().message))
         )
     ]);
 
     Message {
+        kind,
         addr: vec![],
         port: mb.get_port().flatten()
     }
 }
-
@@ -9,12 +9,13 @@ use {
     laddertypes::{TypeTerm},
     crate::{
         type_system::{Context, ReprTree},
-        terminal::{TerminalAtom},
+        terminal::make_label,
         tree::{NestedNode, TreeNavResult},
         commander::{ObjCommander}
     },
     std::sync::Arc,
-    std::sync::RwLock
+    std::sync::RwLock,
+    unicode_width::UnicodeWidthChar,
 };
 
 pub fn init_ctx( ctx: &mut Context ) {
@@ -39,7 +40,11 @@ impl ObjCommander for CharEditor {
             if let Some(cmd_view) = cmd_obj.get_view::<dyn SingletonView<Item = char>>() {
                 let value = cmd_view.get();
 
-                if self.ctx.read().unwrap().meta_chars.contains(&value) {
+                // a zero-width char (e.g. a combining mark)
+                // can not be displayed in a cell on its own
+                if self.ctx.read().unwrap().meta_chars.contains(&value)
+                    || value.width() == Some(0)
+                {
                     TreeNavResult::Exit
                 } else {
                     self.data.set(value);
@@ -85,8 +90,9 @@ impl CharEditor {
         )
             .set_view(data
                       .get_port()
-                      .map(move |c| TerminalAtom::from(if c == '\0' { ' ' } else { c }))
+                      .map(move |c| make_label(&String::from(if c == '\0' { ' ' } else { c })))
                       .to_grid()
+                      .flatten()
             )
             .set_cmd( editor.clone() )
             .set_editor( editor.clone() )
@@ -9,38 +9,17 @@ use {
         },
         projection::projection_helper::*,
     },
+    super::bignum,
     std::sync::{Arc, RwLock},
 };
 
-fn posint_add(
-    radix: usize,
-    a: impl SequenceView<Item = usize>,
-    b: impl SequenceView<Item = usize>,
-) -> Vec<usize> {
-    let mut carry = 0;
-    let mut result = Vec::new();
-
-    for digit_idx in 0..std::cmp::max(a.len().unwrap_or(0), b.len().unwrap_or(0)) {
-        let sum = a.get(&digit_idx).unwrap_or(0) + b.get(&digit_idx).unwrap_or(0) + carry;
-
-        result.push(sum % radix);
-
-        carry = if sum > radix { sum - radix } else { 0 };
-    }
-
-    if carry > 0 {
-        result.push(carry);
-    }
-
-    result
-}
-
 pub struct Add {
     radix: usize,
     a: Arc<dyn SequenceView<Item = usize>>, // PosInt, Little Endian
     b: Arc<dyn SequenceView<Item = usize>>, // PosInt, Little Endian
     c: VecBuffer<usize>,
-    _proj_helper: ProjectionHelper<usize, Self>,
+    cur: Vec<usize>,
+    proj_helper: ProjectionHelper<usize, Self>,
 }
 
 impl Add {
@@ -56,15 +35,25 @@ impl Add {
             a: proj_helper.new_sequence_arg(0, a, |s: &mut Self, _digit_idx| s.update()),
             b: proj_helper.new_sequence_arg(1, b, |s: &mut Self, _digit_idx| s.update()),
             c: VecBuffer::with_port(c),
-            _proj_helper: proj_helper,
+            cur: Vec::new(),
+            proj_helper,
         }));
+
+        {
+            let mut p = add.write().unwrap();
+            p.proj_helper.set_proj(&add);
+            p.update();
+        }
+
         add
     }
 
     fn update(&mut self) {
-        self.c.clear();
-        for digit in posint_add(self.radix, self.a.clone(), self.b.clone()) {
-            self.c.push(digit);
-        }
+        let sum = bignum::add(
+            self.radix,
+            &bignum::to_digits(&self.a),
+            &bignum::to_digits(&self.b),
+        );
+        bignum::set_digits(&mut self.c, &mut self.cur, sum);
     }
 }
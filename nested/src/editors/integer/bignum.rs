@@ -0,0 +1,575 @@
+//! Arbitrary precision arithmetic on little endian digit sequences.
+//!
+//! Digits are `usize` values smaller than the radix, the least
+//! significant digit comes first. Intermediate results stay within
+//! `radix²`, so radices up to `2^32` are supported on 64-bit targets.
+//! All functions panic on radices outside of `2 ..= MAX_RADIX`.
+
+use {
+    r3vi::{
+        view::{
+            InnerViewPort, OuterViewPort,
+            sequence::*,
+            singleton::*,
+        },
+        buffer::{singleton::*, vec::*},
+        projection::projection_helper::*,
+    },
+    std::{
+        cmp::Ordering,
+        sync::{Arc, RwLock},
+    },
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// largest radix whose intermediate results fit into `usize`
+pub const MAX_RADIX: u64 = 1 << (usize::BITS / 2);
+
+fn check_radix(radix: usize) {
+    assert!(
+        radix >= 2 && radix as u64 <= MAX_RADIX,
+        "unsupported radix {}", radix
+    );
+}
+
+/// remove leading zeros (at the end of the vector)
+pub fn normalize(digits: &mut Vec<usize>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+fn normalized(mut digits: Vec<usize>) -> Vec<usize> {
+    normalize(&mut digits);
+    digits
+}
+
+fn significant(digits: &[usize]) -> &[usize] {
+    let mut len = digits.len();
+    while len > 0 && digits[len - 1] == 0 {
+        len -= 1;
+    }
+    &digits[..len]
+}
+
+pub fn is_zero(digits: &[usize]) -> bool {
+    significant(digits).is_empty()
+}
+
+pub fn compare(a: &[usize], b: &[usize]) -> Ordering {
+    let (a, b) = (significant(a), significant(b));
+    a.len().cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+pub fn add(radix: usize, a: &[usize], b: &[usize]) -> Vec<usize> {
+    check_radix(radix);
+    let mut result = Vec::with_capacity(std::cmp::max(a.len(), b.len()) + 1);
+    let mut carry = 0;
+
+    for i in 0 .. std::cmp::max(a.len(), b.len()) {
+        let sum = a.get(i).unwrap_or(&0) + b.get(i).unwrap_or(&0) + carry;
+        result.push(sum % radix);
+        carry = sum / radix;
+    }
+
+    if carry > 0 {
+        result.push(carry);
+    }
+
+    normalized(result)
+}
+
+/// `a - b`, or `None` if the result would be negative
+pub fn sub(radix: usize, a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    check_radix(radix);
+    if compare(a, b) == Ordering::Less {
+        return None;
+    }
+
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for i in 0 .. a.len() {
+        let subtrahend = b.get(i).unwrap_or(&0) + borrow;
+        if a[i] >= subtrahend {
+            result.push(a[i] - subtrahend);
+            borrow = 0;
+        } else {
+            result.push(a[i] + radix - subtrahend);
+            borrow = 1;
+        }
+    }
+
+    Some(normalized(result))
+}
+
+/// `a * d` for a single digit `d`
+pub fn mul_digit(radix: usize, a: &[usize], d: usize) -> Vec<usize> {
+    check_radix(radix);
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+
+    for x in a.iter() {
+        let prod = x * d + carry;
+        result.push(prod % radix);
+        carry = prod / radix;
+    }
+
+    while carry > 0 {
+        result.push(carry % radix);
+        carry /= radix;
+    }
+
+    normalized(result)
+}
+
+pub fn mul(radix: usize, a: &[usize], b: &[usize]) -> Vec<usize> {
+    check_radix(radix);
+    let (a, b) = (significant(a), significant(b));
+    let mut result = vec![0; a.len() + b.len()];
+
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, y) in b.iter().enumerate() {
+            let cur = result[i + j] + x * y + carry;
+            result[i + j] = cur % radix;
+            carry = cur / radix;
+        }
+
+        let mut k = i + b.len();
+        while carry > 0 {
+            let cur = result[k] + carry;
+            result[k] = cur % radix;
+            carry = cur / radix;
+            k += 1;
+        }
+    }
+
+    normalized(result)
+}
+
+/// `(a / b, a % b)`, or `None` for division by zero
+pub fn divmod(radix: usize, a: &[usize], b: &[usize]) -> Option<(Vec<usize>, Vec<usize>)> {
+    check_radix(radix);
+    let (a, b) = (significant(a), significant(b));
+    if b.is_empty() {
+        return None;
+    }
+
+    let mut quot = vec![0; a.len()];
+    let mut rem: Vec<usize> = Vec::new();
+
+    // long division, from the most significant digit
+    for i in (0 .. a.len()).rev() {
+        rem.insert(0, a[i]);
+        normalize(&mut rem);
+
+        // largest q with b * q <= rem
+        let (mut lo, mut hi) = (0, radix - 1);
+        while lo < hi {
+            let mid = lo + (hi - lo + 1) / 2;
+            if compare(&mul_digit(radix, b, mid), &rem) == Ordering::Greater {
+                hi = mid - 1;
+            } else {
+                lo = mid;
+            }
+        }
+
+        quot[i] = lo;
+        rem = sub(radix, &rem, &mul_digit(radix, b, lo)).unwrap();
+    }
+
+    Some((normalized(quot), rem))
+}
+
+/// convert digits of radix `src_radix` into digits of radix `dst_radix`
+pub fn convert_radix(src_radix: usize, digits: &[usize], dst_radix: usize) -> Vec<usize> {
+    check_radix(src_radix);
+    check_radix(dst_radix);
+    let mut result: Vec<usize> = Vec::new();
+
+    // Horner scheme, evaluated in the destination radix
+    for d in significant(digits).iter().rev() {
+        let mut carry = *d;
+        for x in result.iter_mut() {
+            let cur = *x * src_radix + carry;
+            *x = cur % dst_radix;
+            carry = cur / dst_radix;
+        }
+        while carry > 0 {
+            result.push(carry % dst_radix);
+            carry /= dst_radix;
+        }
+    }
+
+    result
+}
+
+/// read all digits of a sequence
+pub fn to_digits(seq: &impl SequenceView<Item = usize>) -> Vec<usize> {
+    (0 .. seq.len().unwrap_or(0))
+        .map(|i| seq.get(&i).unwrap_or(0))
+        .collect()
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Replace the content of `buf`, only touching changed digits.
+pub(super) fn set_digits(buf: &mut VecBuffer<usize>, old: &mut Vec<usize>, new: Vec<usize>) {
+    while old.len() > new.len() {
+        old.pop();
+        buf.remove(old.len());
+    }
+
+    for (i, d) in new.iter().enumerate() {
+        if i < old.len() {
+            if old[i] != *d {
+                old[i] = *d;
+                *buf.get_mut(i) = *d;
+            }
+        } else {
+            old.push(*d);
+            buf.push(*d);
+        }
+    }
+}
+
+/// Recomputes its outputs whenever one of the two operands changes.
+pub struct BignumProjection {
+    a: Arc<dyn SequenceView<Item = usize>>,
+    b: Arc<dyn SequenceView<Item = usize>>,
+    update: Box<dyn FnMut(&[usize], &[usize]) + Send + Sync>,
+    proj_helper: ProjectionHelper<usize, Self>,
+}
+
+impl BignumProjection {
+    fn new(
+        a: OuterViewPort<dyn SequenceView<Item = usize>>,
+        b: OuterViewPort<dyn SequenceView<Item = usize>>,
+        mut proj_helper: ProjectionHelper<usize, Self>,
+        update: Box<dyn FnMut(&[usize], &[usize]) + Send + Sync>,
+    ) -> Arc<RwLock<Self>> {
+        let proj = Arc::new(RwLock::new(BignumProjection {
+            a: proj_helper.new_sequence_arg(0, a, |s: &mut Self, _digit_idx| s.update()),
+            b: proj_helper.new_sequence_arg(1, b, |s: &mut Self, _digit_idx| s.update()),
+            update,
+            proj_helper,
+        }));
+
+        {
+            let mut p = proj.write().unwrap();
+            p.proj_helper.set_proj(&proj);
+            p.update();
+        }
+
+        proj
+    }
+
+    fn update(&mut self) {
+        let a = to_digits(&self.a);
+        let b = to_digits(&self.b);
+        (self.update)(&a, &b);
+    }
+
+    /// projection `c = op(a, b)`
+    pub fn binary_op(
+        a: OuterViewPort<dyn SequenceView<Item = usize>>,
+        b: OuterViewPort<dyn SequenceView<Item = usize>>,
+        c: InnerViewPort<RwLock<Vec<usize>>>,
+        op: impl Fn(&[usize], &[usize]) -> Vec<usize> + Send + Sync + 'static,
+    ) -> Arc<RwLock<Self>> {
+        let proj_helper = ProjectionHelper::new(c.0.update_hooks.clone());
+        let mut c = VecBuffer::with_port(c);
+        let mut cur = Vec::new();
+
+        BignumProjection::new(a, b, proj_helper, Box::new(move |a, b| {
+            set_digits(&mut c, &mut cur, op(a, b));
+        }))
+    }
+
+    /// `c = a + b`
+    pub fn add(
+        radix: usize,
+        a: OuterViewPort<dyn SequenceView<Item = usize>>,
+        b: OuterViewPort<dyn SequenceView<Item = usize>>,
+        c: InnerViewPort<RwLock<Vec<usize>>>,
+    ) -> Arc<RwLock<Self>> {
+        check_radix(radix);
+        BignumProjection::binary_op(a, b, c, move |a, b| add(radix, a, b))
+    }
+
+    /// `c = a - b`, `negative` is set and `c` empty while `a < b`
+    pub fn sub(
+        radix: usize,
+        a: OuterViewPort<dyn SequenceView<Item = usize>>,
+        b: OuterViewPort<dyn SequenceView<Item = usize>>,
+        c: InnerViewPort<RwLock<Vec<usize>>>,
+        negative: InnerViewPort<dyn SingletonView<Item = bool>>,
+    ) -> Arc<RwLock<Self>> {
+        check_radix(radix);
+        let proj_helper = ProjectionHelper::new(c.0.update_hooks.clone());
+        negative.0.add_update_hook(Arc::new(a.0.clone()));
+        negative.0.add_update_hook(Arc::new(b.0.clone()));
+
+        let mut c = VecBuffer::with_port(c);
+        let mut negative = SingletonBuffer::with_port(false, negative);
+        let mut cur = Vec::new();
+
+        BignumProjection::new(a, b, proj_helper, Box::new(move |a, b| {
+            let diff = sub(radix, a, b);
+            if negative.get() != diff.is_none() {
+                negative.set(diff.is_none());
+            }
+            set_digits(&mut c, &mut cur, diff.unwrap_or(vec![]));
+        }))
+    }
+
+    /// `c = a * b`
+    pub fn mul(
+        radix: usize,
+        a: OuterViewPort<dyn SequenceView<Item = usize>>,
+        b: OuterViewPort<dyn SequenceView<Item = usize>>,
+        c: InnerViewPort<RwLock<Vec<usize>>>,
+    ) -> Arc<RwLock<Self>> {
+        check_radix(radix);
+        BignumProjection::binary_op(a, b, c, move |a, b| mul(radix, a, b))
+    }
+
+    /// `quot = a / b` and `rem = a % b`, both empty while `b = 0`
+    pub fn divmod(
+        radix: usize,
+        a: OuterViewPort<dyn SequenceView<Item = usize>>,
+        b: OuterViewPort<dyn SequenceView<Item = usize>>,
+        quot: InnerViewPort<RwLock<Vec<usize>>>,
+        rem: InnerViewPort<RwLock<Vec<usize>>>,
+    ) -> Arc<RwLock<Self>> {
+        check_radix(radix);
+        let proj_helper = ProjectionHelper::new(quot.0.update_hooks.clone());
+        rem.0.add_update_hook(Arc::new(a.0.clone()));
+        rem.0.add_update_hook(Arc::new(b.0.clone()));
+
+        let mut quot = VecBuffer::with_port(quot);
+        let mut rem = VecBuffer::with_port(rem);
+        let (mut cur_quot, mut cur_rem) = (Vec::new(), Vec::new());
+
+        BignumProjection::new(a, b, proj_helper, Box::new(move |a, b| {
+            let (q, r) = divmod(radix, a, b).unwrap_or((vec![], vec![]));
+            set_digits(&mut quot, &mut cur_quot, q);
+            set_digits(&mut rem, &mut cur_rem, r);
+        }))
+    }
+
+    /// `ord = a.cmp(b)`
+    pub fn compare(
+        a: OuterViewPort<dyn SequenceView<Item = usize>>,
+        b: OuterViewPort<dyn SequenceView<Item = usize>>,
+        ord: InnerViewPort<dyn SingletonView<Item = Ordering>>,
+    ) -> Arc<RwLock<Self>> {
+        let proj_helper = ProjectionHelper::new(ord.0.update_hooks.clone());
+        let mut ord = SingletonBuffer::with_port(Ordering::Equal, ord);
+
+        BignumProjection::new(a, b, proj_helper, Box::new(move |a, b| {
+            let new = compare(a, b);
+            if ord.get() != new {
+                ord.set(new);
+            }
+        }))
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        r3vi::view::ViewPort,
+    };
+
+    /// little endian digits of `n`
+    fn digits(radix: usize, mut n: u64) -> Vec<usize> {
+        let mut digits = Vec::new();
+        while n > 0 {
+            digits.push((n % radix as u64) as usize);
+            n /= radix as u64;
+        }
+        digits
+    }
+
+    #[test]
+    fn compare_ignores_leading_zeros() {
+        assert_eq!(compare(&[], &[0, 0]), Ordering::Equal);
+        assert_eq!(compare(&[1, 2, 0], &[1, 2]), Ordering::Equal);
+        assert_eq!(compare(&[9, 9], &[0, 0, 1]), Ordering::Less);
+        assert_eq!(compare(&[0, 3], &[9, 2]), Ordering::Greater);
+        assert_eq!(compare(&[5], &[]), Ordering::Greater);
+    }
+
+    const RADICES: [usize; 5] = [2, 7, 10, 16, 1 << 32];
+
+    #[test]
+    fn add_matches_machine_addition() {
+        for radix in RADICES.iter() {
+            let cases = [(0u64, 0u64), (0, 5), (1, 1), (99, 1), (123456789, 987654321), (u64::MAX / 2, u64::MAX / 2 + 1)];
+            for (a, b) in cases.iter() {
+                assert_eq!(
+                    add(*radix, &digits(*radix, *a), &digits(*radix, *b)),
+                    digits(*radix, a + b),
+                    "{} + {} in radix {}", a, b, radix
+                );
+            }
+        }
+        // carry into a new digit, operands with leading zeros
+        assert_eq!(add(10, &[9, 9], &[1]), vec![0, 0, 1]);
+        assert_eq!(add(10, &[1, 0, 0], &[2, 0]), vec![3]);
+    }
+
+    #[test]
+    fn sub_matches_machine_subtraction() {
+        for radix in RADICES.iter() {
+            let cases = [(0u64, 0u64), (5, 0), (5, 5), (100, 1), (987654321, 123456789), (u64::MAX, u64::MAX - 1)];
+            for (a, b) in cases.iter() {
+                assert_eq!(
+                    sub(*radix, &digits(*radix, *a), &digits(*radix, *b)),
+                    Some(digits(*radix, a - b)),
+                    "{} - {} in radix {}", a, b, radix
+                );
+            }
+        }
+        // borrow through all digits
+        assert_eq!(sub(10, &[0, 0, 0, 1], &[1]), Some(vec![9, 9, 9]));
+    }
+
+    #[test]
+    fn sub_of_larger_number_is_none() {
+        assert_eq!(sub(10, &[], &[1]), None);
+        assert_eq!(sub(10, &[9, 9], &[0, 0, 1]), None);
+        // leading zeros do not make a number larger
+        assert_eq!(sub(10, &[5], &[5, 0, 0]), Some(vec![]));
+    }
+
+    #[test]
+    fn mul_matches_machine_multiplication() {
+        for radix in RADICES.iter() {
+            let cases = [(0u64, 7u64), (1, 1), (9, 9), (255, 257), (123456789, 987654321), (u32::MAX as u64, u32::MAX as u64)];
+            for (a, b) in cases.iter() {
+                assert_eq!(
+                    mul(*radix, &digits(*radix, *a), &digits(*radix, *b)),
+                    digits(*radix, a * b),
+                    "{} * {} in radix {}", a, b, radix
+                );
+                assert_eq!(mul_digit(*radix, &digits(*radix, *a), *radix - 1), digits(*radix, a * (*radix as u64 - 1)));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported radix 1")]
+    fn radix_one_is_rejected() {
+        mul_digit(1, &[0, 0, 1], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported radix 0")]
+    fn radix_zero_is_rejected() {
+        convert_radix(10, &[1], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported radix")]
+    fn too_large_radix_is_rejected() {
+        add(MAX_RADIX as usize + 1, &[1], &[1]);
+    }
+
+    #[test]
+    fn sub_projection_flags_negative_results() {
+        let mut a = VecBuffer::with_data(vec![3]);
+        let b = VecBuffer::with_data(vec![5]);
+        let c = ViewPort::new();
+        let negative = ViewPort::new();
+        let _proj = BignumProjection::sub(
+            10,
+            a.get_port().to_sequence(),
+            b.get_port().to_sequence(),
+            c.inner(),
+            negative.inner()
+        );
+        let c = c.outer().to_sequence().get_view().unwrap();
+        let negative = negative.outer().get_view().unwrap();
+
+        assert!(negative.get());
+        assert_eq!(to_digits(&c), Vec::<usize>::new());
+
+        a.push(1);
+        assert!(!negative.get());
+        assert_eq!(to_digits(&c), vec![8]);
+    }
+
+    #[test]
+    fn divmod_by_zero() {
+        assert_eq!(divmod(10, &[1, 2], &[]), None);
+        assert_eq!(divmod(10, &[1, 2], &[0, 0]), None);
+    }
+
+    #[test]
+    fn divmod_of_zero() {
+        assert_eq!(divmod(10, &[], &[7]), Some((vec![], vec![])));
+        assert_eq!(divmod(10, &[0, 0], &[3, 1]), Some((vec![], vec![])));
+    }
+
+    #[test]
+    fn divmod_by_single_digit() {
+        // 1234 = 7 * 176 + 2
+        assert_eq!(divmod(10, &[4, 3, 2, 1], &[7]), Some((vec![6, 7, 1], vec![2])));
+        // 0xff = 0x10 * 0xf + 0xf, divisor with leading zero
+        assert_eq!(divmod(16, &[15, 15], &[0, 1, 0]), Some((vec![15], vec![15])));
+        assert_eq!(divmod(2, &[1, 0, 1], &[1]), Some((vec![1, 0, 1], vec![])));
+    }
+
+    #[test]
+    fn divmod_with_borrow_chain() {
+        // 1000000 = 999 * 1001 + 1, the remainder borrows through all digits
+        assert_eq!(
+            divmod(10, &[0, 0, 0, 0, 0, 0, 1], &[9, 9, 9]),
+            Some((vec![1, 0, 0, 1], vec![1]))
+        );
+        // dividend smaller than divisor
+        assert_eq!(divmod(10, &[9, 9], &[0, 0, 1]), Some((vec![], vec![9, 9])));
+    }
+
+    #[test]
+    fn divmod_matches_machine_division() {
+        for radix in [2, 7, 10, 16, 1 << 20].iter() {
+            let cases = [
+                (0u64, 1u64), (1, 1), (99, 100), (123456789, 1),
+                (123456789, 9876), (u64::MAX, 3), (u64::MAX, u32::MAX as u64)
+            ];
+            for (a, b) in cases.iter() {
+                assert_eq!(
+                    divmod(*radix, &digits(*radix, *a), &digits(*radix, *b)),
+                    Some((digits(*radix, a / b), digits(*radix, a % b))),
+                    "{} / {} in radix {}", a, b, radix
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn convert_radix_of_zero() {
+        assert_eq!(convert_radix(10, &[], 2), Vec::<usize>::new());
+        assert_eq!(convert_radix(10, &[0, 0], 16), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn convert_radix_round_trip() {
+        for n in [1u64, 9, 10, 255, 256, 65535, 1234567890123, u64::MAX].iter() {
+            for (src, dst) in [(10, 2), (2, 10), (16, 10), (10, 36), (3, 1 << 32), (1 << 32, 7)].iter() {
+                let converted = convert_radix(*src, &digits(*src, *n), *dst);
+                assert_eq!(converted, digits(*dst, *n), "{} from radix {} to {}", n, src, dst);
+                assert_eq!(convert_radix(*dst, &converted, *src), digits(*src, *n));
+            }
+        }
+    }
+}
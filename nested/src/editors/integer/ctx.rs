@@ -15,34 +15,33 @@ use {
     std::sync::{Arc, RwLock}
 };
 
+/// `radix` of `<T radix>`, `None` unless `2 <= radix <= u32::MAX`
+pub fn radix_arg(ty: &TypeTerm) -> Option<u32> {
+    match ty {
+        TypeTerm::App(args) if args.len() > 1 => match args[1] {
+            TypeTerm::Num(radix) if radix >= 2 && radix <= u32::MAX as i64 => Some(radix as u32),
+            _ => None
+        },
+        _ => None
+    }
+}
+
 pub fn init_ctx(ctx: &mut Context) {
     ctx.add_typename("MachineInt".into());
-    ctx.add_typename("u32".into());
-    ctx.add_typename("u64".into());
+    for name in MachineIntType::NAMES.iter() {
+        ctx.add_typename(name);
+    }
     ctx.add_typename("LittleEndian".into());
     ctx.add_typename("BigEndian".into());
+    ctx.add_typename("Endianness".into());
+    ctx.add_typename("Radix".into());
+    ctx.add_typename("Digits".into());
 
     ctx.add_node_ctor(
         "Digit", Arc::new(
             |ctx: Arc<RwLock<Context>>, ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
-                match ty {
-                    TypeTerm::App(args) => {
-                        if args.len() > 1 {
-                            match args[1] {
-                                TypeTerm::Num(radix) => {
-                                    let node = DigitEditor::new(ctx.clone(), radix as u32).into_node(depth);
-                                    Some(
-                                        node
-                                    )
-                                },
-                                _ => None
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    _ => None
-                }
+                let radix = radix_arg(&ty)?;
+                Some(DigitEditor::new(ctx.clone(), radix).into_node(depth))
             }
         )
     );
@@ -56,76 +55,59 @@ pub fn init_ctx(ctx: &mut Context) {
         Arc::new(
             |mut node, dst_type| {
                 // todo: check src_type parameter to be ( Digit radix )
+                radix_arg(&dst_type)?;
 
-                match dst_type {
-                    TypeTerm::App(args) => {
-                        if args.len() > 1 {
-                            match args[1] {
-                                TypeTerm::Num(_radix) => {
-                                    PTYListController::for_node(
-                                        &mut node,
-                                        Some(','),
-                                        None,
-                                    );
+                PTYListController::for_node(
+                    &mut node,
+                    Some(','),
+                    None,
+                );
 
-                                    PTYListStyle::for_node(
-                                        &mut node,
-                                        ("0d", "", "")
-                                    );
+                PTYListStyle::for_node(
+                    &mut node,
+                    ("0d", "", "")
+                );
 
-                                    Some(node)
-                                },
-                                _ => None
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    _ => None
-                }
+                Some(node)
             }
         )
     );
 
+    ctx.add_typename("PosIntCmd".into());
     ctx.add_node_ctor(
         "PosInt", Arc::new(
             |ctx0: Arc<RwLock<Context>>, dst_typ: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
-                match dst_typ.clone() {
-                    TypeTerm::App(args) => {
-                        if args.len() > 1 {
-                            match args[1] {
-                                TypeTerm::Num(radix) => {
-                                    let ctx = ctx0.read().unwrap();
-                                    let mut node = Context::make_node(
-                                        &ctx0,
-                                        TypeTerm::App(vec![
-                                            TypeTerm::TypeID(ctx.get_typeid("List").unwrap()),
-                                            TypeTerm::TypeID(
-                                                ctx.get_typeid("Digit").unwrap()
-                                            )
-                                                .num_arg(radix)
-                                                .clone()
-                                                .into()
-                                        ]),
-                                        depth.map(|d| d+1)
-                                    ).unwrap();
+                let radix = radix_arg(&dst_typ)?;
+                let endianness = match &dst_typ {
+                    TypeTerm::App(args) => args.get(2)
+                        .and_then(|e| Endianness::from_type_term(&ctx0.read().unwrap(), e)),
+                    _ => None
+                }.unwrap_or(Endianness::BigEndian);
 
-                                    node = node.morph(dst_typ);
+                let node = PosIntEditor::new(ctx0.clone(), radix, endianness).into_node();
+                node.depth.0.set_view(depth.get_view());
 
-                                    Some(node)
-                                }
-                                _ => None
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    _ => None
-                }
+                Some(node)
             }
         )
     );
     
+    for name in MachineIntType::NAMES.iter() {
+        ctx.add_node_ctor(
+            name, Arc::new(
+                |ctx: Arc<RwLock<Context>>, ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+                    let name = match ty {
+                        TypeTerm::TypeID(id) => ctx.read().unwrap().get_typename(&id)?,
+                        _ => return None
+                    };
+                    let node = MachineIntEditor::new(ctx.clone(), MachineIntType::from_name(&name)?).into_node();
+                    node.depth.0.set_view(depth.get_view());
+                    Some(node)
+                }
+            )
+        );
+    }
+
     ctx.add_typename("Date".into());
     ctx.add_typename("ISO-8601".into());
     ctx.add_typename("TimeSince".into());
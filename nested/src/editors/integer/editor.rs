@@ -1,8 +1,9 @@
 use {
     r3vi::{
         view::{
-            OuterViewPort,
+            ViewPort, OuterViewPort,
             singleton::*,
+            sequence::*,
         },
         buffer::{
             singleton::*,
@@ -13,26 +14,101 @@ use {
     laddertypes::{TypeTerm},
     crate::{
         type_system::{Context, ReprTree},
-        editors::list::{ListCmd, PTYListController, PTYListStyle},
+        editors::list::{ListCmd, ListCursorMode, ListEditor, PTYListController, PTYListStyle},
         terminal::{
-            TerminalAtom, TerminalStyle, make_label
+            TerminalEvent, TerminalView, TerminalProjections, make_label
         },
+        editors::integer::{bignum, radix_arg, RadixProjection},
         diagnostics::{Message},
-        tree::{NestedNode, TreeNav, TreeNavResult, TreeCursor},
+        utils::combine::{collect_singletons, zip_singletons},
+        tree::{NestedNode, TreeNav, TreeNavResult, TreeCursor, HitTest},
         commander::ObjCommander
     },
     std::sync::Arc,
     std::sync::RwLock,
     std::iter::FromIterator,
+    termion::event::{Event, Key},
     cgmath::{Point2}
 };
 
 //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
 
+/// How a digit is written: radices up to 36 use a single char,
+/// larger ones write every digit as a small number of fixed width,
+/// in hexadecimal for powers of 16 (e.g. bytes) or else in decimal.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct DigitFormat {
+    pub radix: u32,
+    pub sub_radix: u32,
+    pub width: usize,
+}
+
+impl DigitFormat {
+    pub fn new(radix: u32) -> Self {
+        if radix <= 36 {
+            DigitFormat { radix, sub_radix: radix, width: 1 }
+        } else {
+            let sub_radix =
+                if radix.is_power_of_two() && radix.trailing_zeros() % 4 == 0 {
+                    16
+                } else {
+                    10
+                };
+
+            let mut width = 0;
+            let mut max = radix - 1;
+            while max > 0 {
+                max /= sub_radix;
+                width += 1;
+            }
+
+            DigitFormat { radix, sub_radix, width }
+        }
+    }
+
+    pub fn is_multi_char(&self) -> bool {
+        self.width > 1
+    }
+
+    pub fn is_digit_char(&self, c: char) -> bool {
+        c.to_digit(self.sub_radix).is_some()
+    }
+
+    /// value of a written digit, `None` if empty, malformed or too large
+    pub fn parse(&self, text: &str) -> Option<u32> {
+        if text.is_empty() {
+            return None;
+        }
+
+        let mut value: u64 = 0;
+        for c in text.chars() {
+            value = value * self.sub_radix as u64 + c.to_digit(self.sub_radix)? as u64;
+        }
+
+        if value < self.radix as u64 {
+            Some(value as u32)
+        } else {
+            None
+        }
+    }
+
+    /// zero padded to the full width
+    pub fn format(&self, mut value: u32) -> String {
+        let mut chars = Vec::with_capacity(self.width);
+        for _ in 0 .. self.width {
+            chars.push(char::from_digit(value % self.sub_radix, self.sub_radix).expect("invalid digit"));
+            value /= self.sub_radix;
+        }
+        chars.into_iter().rev().collect()
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
 pub struct DigitEditor {
     ctx: Arc<RwLock<Context>>,
-    radix: u32,
-    data: SingletonBuffer<Option<char>>,
+    format: DigitFormat,
+    data: SingletonBuffer<String>,
     msg: VecBuffer<Message>,
 }
 
@@ -45,29 +121,44 @@ impl ObjCommander for DigitEditor {
             if let Some(cmd_view) = cmd_obj.get_view::<dyn SingletonView<Item = char>>() {
                 let c = cmd_view.get();
 
-                self.msg.clear();
-
                 if self.ctx.read().unwrap().meta_chars.contains(&c) {
                     eprintln!("digitedit: meta char");
                     return TreeNavResult::Exit;
+                }
+
+                // single chars are replaced, longer digits are extended
+                let mut text = if self.format.is_multi_char() {
+                    if self.is_complete() {
+                        return TreeNavResult::Exit;
+                    }
+                    self.data.get()
+                } else {
+                    String::new()
+                };
+                text.push(c);
 
-                } else if c.to_digit(self.radix).is_none() {
+                self.msg.clear();
+
+                if !self.format.is_digit_char(c) {
                     /* in case the character c is not in the range of digit-chars,
                        add a message to the diagnostics view
                      */
 
+                    let theme = self.ctx.read().unwrap().get_theme();
                     let message = IndexBuffer::from_iter(vec![
                         (Point2::new(1, 0), make_label("invalid digit '")),
                         (Point2::new(2, 0), make_label(&format!("{}", c))
-                         .map_item(|_p,a| a.add_style_back(TerminalStyle::fg_color((140,140,250))))),
+                         .map_item(move |_p,a| a.add_style_back(theme.read().unwrap().message_highlight))),
                         (Point2::new(3, 0), make_label("'"))
                     ]);
 
-                    self.msg.push(crate::diagnostics::make_error(message.get_port().flatten()));
-                    self.data.set(Some(c));
-                } else {
-                    self.data.set(Some(c));
+                    self.msg.push(crate::diagnostics::make_error(&self.ctx, message.get_port().flatten()));
+                } else if self.format.parse(&text).is_none() {
+                    let message = make_label(&format!("digit exceeds radix {}", self.format.radix));
+                    self.msg.push(crate::diagnostics::make_error(&self.ctx, message));
                 }
+
+                self.data.set(text);
             }
         }
 
@@ -79,8 +170,8 @@ impl DigitEditor {
     pub fn new(ctx: Arc<RwLock<Context>>, radix: u32) -> Self {
         DigitEditor {
             ctx,
-            radix,
-            data: SingletonBuffer::new(None),
+            format: DigitFormat::new(radix),
+            data: SingletonBuffer::new(String::new()),
             msg: VecBuffer::new(),
         }
     }
@@ -89,34 +180,45 @@ impl DigitEditor {
         let data = self.get_data();        
         let editor = Arc::new(RwLock::new(self));
         let ed = editor.write().unwrap();
-        let r = ed.radix;
+        let format = ed.format;
+        let theme = ed.ctx.read().unwrap().get_theme();
 
         NestedNode::new(ed.ctx.clone(), data, depth)
             .set_cmd(editor.clone())
+            .set_editor(editor.clone())
             .set_view(
                 ed.data
                     .get_port()
-                    .map(move |c| {
-                        TerminalAtom::new(
-                            c.unwrap_or('?'),
-                            if c.unwrap_or('?').to_digit(r).is_some() {
-                                TerminalStyle::fg_color((90, 160, 90))
-                            } else {
-                                //TerminalStyle::bg_color((90, 10, 10))
-                                TerminalStyle::fg_color((200, 40, 40))
-                            },
-                        )
+                    .map(move |text| {
+                        let theme = theme.read().unwrap();
+                        if text.is_empty() {
+                            make_label("?").with_style(theme.invalid)
+                        } else {
+                            make_label(&text).with_style(
+                                if format.parse(&text).is_some() {
+                                    theme.number
+                                } else {
+                                    theme.invalid
+                                }
+                            )
+                        }
                     })
                     .to_grid()
+                    .flatten()
             )
             .set_diag(
                 ed.msg.get_port().to_sequence()
             )
     }
 
+    /// no more chars fit into this digit
+    pub fn is_complete(&self) -> bool {
+        self.data.get().chars().count() >= self.format.width
+    }
+
     pub fn get_data_port(&self) -> OuterViewPort<dyn SingletonView<Item = Option<u32>>> {
-        let radix = self.radix;
-        self.data.get_port().map(move |c| c?.to_digit(radix))
+        let format = self.format;
+        self.data.get_port().map(move |text| format.parse(&text))
     }
 
     pub fn get_type(&self) -> TypeTerm {
@@ -135,15 +237,436 @@ impl DigitEditor {
 }
 
 
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// order of the digits in a `<PosInt radix Endianness>`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Endianness {
+    /// least significant digit first
+    LittleEndian,
+
+    /// most significant digit first
+    BigEndian,
+}
+
+impl Endianness {
+    pub fn from_type_term(ctx: &Context, t: &TypeTerm) -> Option<Self> {
+        match t {
+            TypeTerm::TypeID(id) => {
+                if Some(id.clone()) == ctx.get_typeid("LittleEndian") {
+                    Some(Endianness::LittleEndian)
+                } else if Some(id.clone()) == ctx.get_typeid("BigEndian") {
+                    Some(Endianness::BigEndian)
+                } else {
+                    None
+                }
+            }
+            _ => None
+        }
+    }
+
+    pub fn get_type(&self, ctx: &Context) -> TypeTerm {
+        TypeTerm::TypeID(ctx.get_typeid(match self {
+            Endianness::LittleEndian => "LittleEndian",
+            Endianness::BigEndian => "BigEndian",
+        }).unwrap())
+    }
+
+    pub fn flip(self) -> Self {
+        match self {
+            Endianness::LittleEndian => Endianness::BigEndian,
+            Endianness::BigEndian => Endianness::LittleEndian,
+        }
+    }
+
+    /// list style: the radix prefix of big endian numbers
+    /// is mirrored into a suffix for little endian ones,
+    /// digits of more than one char are separated by spaces
+    fn style(&self, radix: u32) -> (String, String, String) {
+        let prefix = match radix {
+            2 => "0b",
+            8 => "0o",
+            10 => "0d",
+            16 => "0x",
+            _ => ""
+        };
+
+        let sep = if DigitFormat::new(radix).is_multi_char() { " " } else { "" };
+
+        match self {
+            Endianness::BigEndian => (prefix.into(), sep.into(), "".into()),
+            Endianness::LittleEndian => ("".into(), sep.into(), prefix.chars().rev().collect())
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PosIntCmd {
+    /// reverse the digits, keeping the value
+    ToggleEndianness,
+
+    /// rewrite the digits in another radix, keeping the value
+    SetRadix(u32),
+}
+
+impl PosIntCmd {
+    pub fn into_repr_tree(self, ctx: &Arc<RwLock<Context>>) -> Arc<RwLock<ReprTree>> {
+        let buf = r3vi::buffer::singleton::SingletonBuffer::new(self);
+        ReprTree::new_leaf(
+            Context::parse(ctx, "PosIntCmd"),
+            buf.get_port().into()
+        )
+    }
+}
+
+/// radix selected by the letter of a radix prefix like `0x`
+fn prefix_radix(c: char) -> Option<u32> {
+    match c {
+        'b' => Some(2),
+        'o' => Some(8),
+        'd' => Some(10),
+        'x' => Some(16),
+        _ => None
+    }
+}
+
+/// digit values in list order, `None` if some digit is invalid
+fn list_digits(list: &ListEditor) -> Option<Vec<usize>> {
+    let digits = list.get_data_port().get_view()?;
+    digits.iter()
+        .map(|node| {
+            let digit = node.get_edit::<DigitEditor>()?;
+            let value = digit.read().unwrap().get_data_port().get_view()?.get();
+            value.map(|v| v as usize)
+        })
+        .collect()
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Handles `PosIntCmd` and forwards everything else to the list controller.
+/// Does not hold the node itself, since the node holds its commander.
+struct PosIntController {
+    ctx: Arc<RwLock<Context>>,
+    radix: SingletonBuffer<u32>,
+    endianness: SingletonBuffer<Endianness>,
+    show_prefix: bool,
+
+    list: Arc<RwLock<ListEditor>>,
+    list_cmd: Arc<RwLock<dyn ObjCommander + Send + Sync>>,
+
+    data: Arc<RwLock<ReprTree>>,
+    layout: SingletonBuffer<OuterViewPort<dyn TerminalView>>,
+    hit: SingletonBuffer<Option<Arc<RwLock<dyn HitTest + Send + Sync>>>>,
+
+    /// digit before the insert cursor which still takes chars
+    open_digit: Option<Arc<RwLock<NestedNode>>>,
+}
+
+impl PosIntController {
+    /// display radix prefix and digits in the order of the current endianness
+    fn update_layout(&mut self) {
+        let (mut prefix, sep, mut suffix) = self.endianness.get().style(self.radix.get());
+        if !self.show_prefix {
+            prefix.clear();
+            suffix.clear();
+        }
+        let (view, hit) = PTYListStyle::new((prefix.as_str(), sep.as_str(), suffix.as_str()))
+            .layout_editor(&self.list.read().unwrap());
+
+        self.layout.set(view);
+        self.hit.set(Some(Arc::new(RwLock::new(hit))));
+    }
+
+    fn get_list_digits(&self) -> Option<Vec<usize>> {
+        list_digits(&self.list.read().unwrap())
+    }
+
+    /// digit values, least significant first
+    fn get_digits(&self) -> Option<Vec<usize>> {
+        let mut digits = self.get_list_digits()?;
+        if self.endianness.get() == Endianness::BigEndian {
+            digits.reverse();
+        }
+        Some(digits)
+    }
+
+    /// list index of the digit with significance `pos`,
+    /// or of the insert position below it
+    fn list_idx(&self, len: usize, pos: usize, insert: bool) -> isize {
+        match (self.endianness.get(), insert) {
+            (Endianness::LittleEndian, _) => pos as isize,
+            (Endianness::BigEndian, true) => len as isize - pos as isize,
+            (Endianness::BigEndian, false) => len as isize - 1 - pos as isize,
+        }
+    }
+
+    fn toggle_endianness(&mut self) {
+        let endianness = self.endianness.get().flip();
+
+        let mut list = self.list.write().unwrap();
+        let mut cur = list.get_cursor();
+
+        let len = list.data.len();
+        let items: Vec<_> = (0 .. len).map(|i| list.data.get(i)).collect();
+        list.data.clear();
+        for item in items.into_iter().rev() {
+            list.data.push(item);
+        }
+
+        // keep the cursor at the same digit
+        if let Some(idx) = cur.tree_addr.first().cloned() {
+            cur.tree_addr[0] = if cur.tree_addr.len() == 1 && cur.leaf_mode == ListCursorMode::Insert {
+                len as isize - idx
+            } else {
+                len as isize - 1 - idx
+            };
+            list.goto(cur);
+        }
+        drop(list);
+
+        self.endianness.set(endianness);
+        self.update_layout();
+    }
+
+    /// Convert the digits into `radix` through a `RadixProjection`.
+    /// The cursor stays at a digit of about the same significance.
+    fn set_radix(&mut self, radix: u32) {
+        let old_radix = self.radix.get();
+        if radix == old_radix || radix < 2 {
+            return;
+        }
+
+        // invalid digits have no value to convert
+        let old_digits = match self.get_digits() {
+            Some(digits) => digits,
+            None => return
+        };
+
+        let src_port = ViewPort::new();
+        let dst_port = ViewPort::new();
+        let mut src = VecBuffer::<usize>::with_port(src_port.inner());
+        let _proj = RadixProjection::new(
+            old_radix as usize,
+            radix as usize,
+            src_port.outer().to_sequence(),
+            dst_port.inner()
+        );
+        for d in old_digits.iter() {
+            src.push(*d);
+        }
+        let new_digits = dst_port.outer().to_sequence().get_view()
+            .map(|v| bignum::to_digits(&v))
+            .unwrap_or(vec![]);
+
+        // significance of the cursor, counted from the least significant digit
+        let cur = self.list.read().unwrap().get_cursor();
+        let cur_pos = cur.tree_addr.first().map(|idx| {
+            let insert = cur.tree_addr.len() == 1 && cur.leaf_mode == ListCursorMode::Insert;
+            let pos = match (self.endianness.get(), insert) {
+                (Endianness::LittleEndian, _) => *idx,
+                (Endianness::BigEndian, true) => old_digits.len() as isize - idx,
+                (Endianness::BigEndian, false) => old_digits.len() as isize - 1 - idx,
+            };
+            let scale = (old_radix as f64).ln() / (radix as f64).ln();
+            (insert, (pos.max(0) as f64 * scale).round() as usize)
+        });
+
+        self.radix.set(radix);
+
+        // replace the digit editors
+        let digit_type = Context::parse(&self.ctx, format!("<Digit {}>", radix).as_str());
+        let mut list = self.list.write().unwrap();
+        let old_list_type = list.get_seq_type();
+        list.set_item_type(digit_type.clone());
+        list.data.clear();
+        list.goto(TreeCursor::home());
+
+        let mut list_digits = new_digits.clone();
+        if self.endianness.get() == Endianness::BigEndian {
+            list_digits.reverse();
+        }
+        for d in list_digits {
+            let mut digit = Context::make_node(
+                &self.ctx,
+                digit_type.clone(),
+                r3vi::buffer::singleton::SingletonBuffer::new(0).get_port()
+            ).unwrap();
+            for c in DigitFormat::new(radix).format(d as u32).chars() {
+                digit.send_cmd_obj(ReprTree::from_char(&self.ctx, c));
+            }
+            list.insert(Arc::new(RwLock::new(digit)));
+        }
+
+        match cur_pos {
+            Some((insert, pos)) => {
+                let len = new_digits.len();
+                let insert = insert || len == 0;
+                let pos = std::cmp::min(pos, if insert { len } else { len - 1 });
+                list.goto(TreeCursor {
+                    leaf_mode: if insert { ListCursorMode::Insert } else { ListCursorMode::Select },
+                    tree_addr: vec![ self.list_idx(len, pos, insert) ]
+                });
+            }
+            None => {
+                list.goto(TreeCursor::none());
+            }
+        }
+
+        let list_data = list.get_data();
+        drop(list);
+
+        // the root keeps its type and other branches,
+        // only the digit list is filed under its new type
+        let mut data = self.data.write().unwrap();
+        data.remove_branch(&old_list_type);
+        data.insert_branch(list_data);
+        drop(data);
+
+        self.update_layout();
+    }
+
+    /// Digits of more than one char: chars typed behind a digit extend it
+    /// until it is complete or closed with a space, only then a new digit is started.
+    fn handle_multi_char(&mut self, c: char, cmd_obj: Arc<RwLock<ReprTree>>) -> TreeNavResult {
+        let cur = self.list.read().unwrap().get_cursor();
+        let insert_idx = match (cur.leaf_mode, cur.tree_addr.as_slice()) {
+            (ListCursorMode::Insert, [idx]) => Some(*idx),
+            _ => None
+        };
+
+        let open_digit = self.open_digit.take();
+        if insert_idx.is_none() {
+            return self.list_cmd.write().unwrap().send_cmd_obj(cmd_obj);
+        }
+        let idx = insert_idx.unwrap();
+
+        if c == ' ' {
+            return TreeNavResult::Continue;
+        }
+
+        let prev = if idx > 0 {
+            Some(self.list.read().unwrap().data.get(idx as usize - 1))
+        } else {
+            None
+        };
+
+        let digit = match (open_digit, prev) {
+            (Some(open), Some(prev)) if Arc::ptr_eq(&open, &prev) => {
+                open.write().unwrap().send_cmd_obj(cmd_obj);
+                open
+            }
+            _ => {
+                let res = self.list_cmd.write().unwrap().send_cmd_obj(cmd_obj);
+                if res == TreeNavResult::Exit {
+                    return res;
+                }
+
+                // the new digit was inserted before the cursor
+                let list = self.list.read().unwrap();
+                match list.get_cursor().tree_addr.as_slice() {
+                    [idx] if *idx > 0 => list.data.get(*idx as usize - 1),
+                    _ => return res
+                }
+            }
+        };
+
+        let complete = digit.read().unwrap()
+            .get_edit::<DigitEditor>()
+            .map(|d| d.read().unwrap().is_complete())
+            .unwrap_or(true);
+
+        if !complete {
+            self.open_digit = Some(digit);
+        }
+
+        TreeNavResult::Continue
+    }
+
+    /// Typing the letter of a radix prefix right after a single `0` switches the radix.
+    /// Letters which are digits of the current radix stay digits.
+    /// Without a visible prefix the radix is fixed.
+    fn handle_prefix_char(&mut self, c: char) -> bool {
+        if !self.show_prefix || DigitFormat::new(self.radix.get()).is_digit_char(c) {
+            return false;
+        }
+
+        if let Some(radix) = prefix_radix(c) {
+            let cur = self.list.read().unwrap().get_cursor();
+            if self.get_list_digits() == Some(vec![0])
+                && cur.leaf_mode == ListCursorMode::Insert
+                && cur.tree_addr == vec![1]
+            {
+                let mut list = self.list.write().unwrap();
+                list.data.clear();
+                list.goto(TreeCursor::home());
+                drop(list);
+
+                self.set_radix(radix);
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl ObjCommander for PosIntController {
+    fn send_cmd_obj(&mut self, cmd_obj: Arc<RwLock<ReprTree>>) -> TreeNavResult {
+        let cmd_repr = cmd_obj.read().unwrap();
+
+        if let Some(cmd) = cmd_repr.get_view::<dyn SingletonView<Item = PosIntCmd>>() {
+            drop(cmd_repr);
+            match cmd.get() {
+                PosIntCmd::ToggleEndianness => self.toggle_endianness(),
+                PosIntCmd::SetRadix(radix) => self.set_radix(radix)
+            }
+            TreeNavResult::Continue
+        }
+
+        else if let Some(event) = cmd_repr.get_view::<dyn SingletonView<Item = TerminalEvent>>() {
+            drop(cmd_repr);
+            match event.get() {
+                TerminalEvent::Input(Event::Key(Key::Ctrl('e'))) => {
+                    self.toggle_endianness();
+                    TreeNavResult::Continue
+                }
+                _ => self.list_cmd.write().unwrap().send_cmd_obj(cmd_obj)
+            }
+        }
+
+        else if let Some(c) = cmd_repr.get_view::<dyn SingletonView<Item = char>>() {
+            drop(cmd_repr);
+            if self.handle_prefix_char(c.get()) {
+                TreeNavResult::Continue
+            } else if DigitFormat::new(self.radix.get()).is_multi_char() {
+                self.handle_multi_char(c.get(), cmd_obj)
+            } else {
+                self.list_cmd.write().unwrap().send_cmd_obj(cmd_obj)
+            }
+        }
+
+        else {
+            drop(cmd_repr);
+            self.list_cmd.write().unwrap().send_cmd_obj(cmd_obj)
+        }
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// The node keeps the type it was created with, so that
+/// wrapping nodes find it under the same key. Its current
+/// radix and endianness are exposed in the `Radix` and
+/// `Endianness` branches, the digit values, least
+/// significant first, in the `Digits` branch.
 pub struct PosIntEditor {
-    radix: u32,
     digits: NestedNode,
-
-    // todo: endianness
+    ctrl: Arc<RwLock<PosIntController>>,
 }
 
 impl PosIntEditor {
-    pub fn new(ctx: Arc<RwLock<Context>>, radix: u32) -> Self {
+    pub fn new(ctx: Arc<RwLock<Context>>, radix: u32, endianness: Endianness) -> Self {
         let mut node = Context::make_node(
             &ctx,
             Context::parse(&ctx, format!("<List <Digit {}>>", radix).as_str()),
@@ -153,87 +676,220 @@ impl PosIntEditor {
         // Set Type
         node.data = ReprTree::ascend(
             &node.data.clone(),
-            TypeTerm::App(vec![
-                TypeTerm::TypeID(ctx.read().unwrap().get_typeid("PosInt").unwrap()),
-                TypeTerm::Num(radix as i64).into(),
-                TypeTerm::TypeID(ctx.read().unwrap().get_typeid("BigEndian").unwrap())
-            ]
-        ));
+            PosIntEditor::get_type(&ctx, radix, endianness)
+        );
 
-        PTYListController::for_node( &mut node, Some(' '), None );
-        PTYListStyle::for_node( &mut node,
-            (
-                match radix {
-                    2 => "0b".into(),
-                    8 => "0o".into(),
-                    10 => "0d".into(),
-                    16 => "0x".into(),
-                    _ => "".into()
-                },
-                "".into(),
-                "".into()
-            )
+        let radix = SingletonBuffer::new(radix);
+        node.data.write().unwrap().insert_leaf(
+            vec![ Context::parse(&ctx, "Radix") ].into_iter(),
+            radix.get_port().into()
+        );
+        let endianness = SingletonBuffer::new(endianness);
+        node.data.write().unwrap().insert_leaf(
+            vec![ Context::parse(&ctx, "Endianness") ].into_iter(),
+            endianness.get_port().into()
         );
 
-        PosIntEditor {
+        // digit values in list order, invalid ones are `None`
+        let list = node.get_edit::<ListEditor>().unwrap();
+        let digit_values = collect_singletons(
+            list.read().unwrap().get_data_port().map(|digit| {
+                match digit.get_edit::<DigitEditor>() {
+                    Some(digit) => digit.read().unwrap().get_data_port(),
+                    None => SingletonBuffer::new(None).get_port()
+                }
+            })
+        );
+        let digits = zip_singletons(
+            zip_singletons(radix.get_port(), endianness.get_port(), |r, e| (r, e)),
+            digit_values,
+            |(radix, endianness), values| {
+                let mut digits = values.into_iter()
+                    .map(|v| v.map(|v| v as usize))
+                    .collect::<Option<Vec<usize>>>()?;
+                if endianness == Endianness::BigEndian {
+                    digits.reverse();
+                }
+                Some((radix, digits))
+            }
+        );
+        node.data.write().unwrap().insert_leaf(
+            vec![ Context::parse(&ctx, "Digits") ].into_iter(),
+            digits.into()
+        );
+
+        PTYListController::for_node( &mut node, Some(' '), None );
+
+        let ctrl = Arc::new(RwLock::new(PosIntController {
+            ctx: ctx.clone(),
             radix,
-            digits: node
+            endianness,
+            show_prefix: true,
+            list,
+            list_cmd: node.cmd.get().unwrap(),
+            data: node.data.clone(),
+            layout: SingletonBuffer::new(ViewPort::new().into_outer()),
+            hit: node.hit.clone(),
+            open_digit: None,
+        }));
+
+        let layout = {
+            let mut c = ctrl.write().unwrap();
+            c.update_layout();
+            c.layout.get_port()
+        };
+        node.view = Some(layout.to_grid().flatten());
+        node.cmd.set(Some(ctrl.clone()));
+
+        PosIntEditor {
+            digits: node,
+            ctrl
         }
     }
 
-    pub fn from_u64(ctx: Arc<RwLock<Context>>, radix: u32, value: u64) -> Self {
-        let mut edit = PosIntEditor::new(ctx, radix);
+    pub fn from_u64(ctx: Arc<RwLock<Context>>, radix: u32, endianness: Endianness, value: u64) -> Self {
+        let mut edit = PosIntEditor::new(ctx, radix, endianness);
         edit.set_value_u64( value );
         edit
     }
 
-    pub fn set_value_u64(&mut self, mut value: u64) {
+    /// `<PosInt radix endianness>`
+    pub fn get_type(ctx: &Arc<RwLock<Context>>, radix: u32, endianness: Endianness) -> TypeTerm {
+        let ctx = ctx.read().unwrap();
+        TypeTerm::App(vec![
+            TypeTerm::TypeID(ctx.get_typeid("PosInt").unwrap()),
+            TypeTerm::Num(radix as i64).into(),
+            endianness.get_type(&ctx)
+        ])
+    }
+
+    pub fn get_radix(&self) -> u32 {
+        self.ctrl.read().unwrap().radix.get()
+    }
+
+    pub fn set_radix(&mut self, radix: u32) {
+        self.digits.send_cmd_obj(PosIntCmd::SetRadix(radix).into_repr_tree(&self.digits.ctx));
+    }
+
+    pub fn get_endianness(&self) -> Endianness {
+        self.ctrl.read().unwrap().endianness.get()
+    }
+
+    pub fn toggle_endianness(&mut self) {
+        self.digits.send_cmd_obj(PosIntCmd::ToggleEndianness.into_repr_tree(&self.digits.ctx));
+    }
+
+    /// show or hide the radix prefix, e.g. where the radix is implied.
+    /// A hidden prefix can not be typed to switch the radix either
+    pub fn set_show_prefix(&mut self, show: bool) {
+        let mut ctrl = self.ctrl.write().unwrap();
+        ctrl.show_prefix = show;
+        ctrl.update_layout();
+    }
+
+    /// zero is written as a single digit
+    pub fn set_value_u64(&mut self, value: u64) {
+        self.set_digits(&u64_to_digits(self.get_radix(), value));
+    }
+
+    /// replace all digits, least significant first
+    pub fn set_digits(&mut self, digits: &[usize]) {
         self.digits.send_cmd_obj(ListCmd::Clear.into_repr_tree(&self.digits.ctx));
 
-        while value > 0 {
-            let digit_val = (value % self.radix as u64) as u32;
-            value /= self.radix as u64;
+        let format = DigitFormat::new(self.get_radix());
+        let endianness = self.get_endianness();
+        self.digits.goto(TreeCursor::home());
 
-            // if BigEndian
-            self.digits.goto(TreeCursor::home());
+        for d in digits.iter() {
+            // big endian digits are prepended,
+            // little endian ones appended
+            if endianness == Endianness::BigEndian {
+                self.digits.goto(TreeCursor::home());
+            }
 
-            self.digits.send_cmd_obj(ReprTree::from_char(&self.digits.ctx, char::from_digit(digit_val, self.radix).expect("invalid digit"))); 
+            for c in format.format(*d as u32).chars() {
+                self.digits.send_cmd_obj(ReprTree::from_char(&self.digits.ctx, c));
+            }
         }
         self.digits.goto(TreeCursor::none());
     }
 
-    pub fn into_node(self) -> NestedNode {
-        self.digits
+    /// digit values, least significant first.
+    /// `None` if some digit is invalid
+    pub fn get_digits(&self) -> Option<Vec<usize>> {
+        self.ctrl.read().unwrap().get_digits()
     }
 
-/*
-    pub fn get_data_port(&self) -> OuterViewPort<dyn SequenceView<Item = u32>> {
-        let radix = self.radix;
-        self.digits
-            .get_data_port()
-            .filter_map(move |digit_editor| {
-                digit_editor.read().unwrap().data.get()?.to_digit(radix)
-            })
+    /// `None` if some digit is invalid or the value exceeds `u64`
+    pub fn get_value_u64(&self) -> Option<u64> {
+        digits_to_u64(self.get_radix(), &self.get_digits()?)
     }
 
-    pub fn get_value(&self) -> u32 {
-        let mut value = 0;
-        let mut weight = 1;
-        for digit_value in self
-            .get_data_port()
-            .get_view()
-            .unwrap()
-            .iter()
-            .collect::<Vec<_>>()
-            .into_iter()
-            .rev()
-        {
-            value += digit_value * weight;
-            weight *= self.radix;
+    /// radix and digits of a `<PosInt radix endianness>` node,
+    /// least significant first. `None` if some digit is invalid
+    pub fn get_node_digits(node: &NestedNode) -> Option<(u32, Vec<usize>)> {
+        match PosIntEditor::get_node_digits_port(node) {
+            Some(port) => port.get_view()?.get(),
+
+            // not made by a `PosIntEditor`, the type tells the format
+            None => {
+                let typ = node.data.read().unwrap().get_type().clone();
+                let radix = radix_arg(&typ)?;
+                let ctx = node.ctx.read().unwrap();
+                let endianness = match typ {
+                    TypeTerm::App(args) if args[0] == TypeTerm::TypeID(ctx.get_typeid("PosInt")?) => args.get(2)
+                        .and_then(|e| Endianness::from_type_term(&ctx, e))
+                        .unwrap_or(Endianness::BigEndian),
+                    _ => return None
+                };
+
+                let mut digits = list_digits(&node.get_edit::<ListEditor>()?.read().unwrap())?;
+                if endianness == Endianness::BigEndian {
+                    digits.reverse();
+                }
+                Some((radix, digits))
+            }
         }
+    }
+
+    /// projection of `get_node_digits`, following all edits
+    /// of the digits, `None` for nodes without a `Digits` branch
+    pub fn get_node_digits_port(node: &NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<(u32, Vec<usize>)>>>> {
+        let digits_type = Context::parse(&node.ctx, "Digits");
+        let digits = node.data.read().unwrap().descend(digits_type)?;
+        let digits = digits.read().unwrap();
+        digits.get_port()
+    }
 
-        value
+    pub fn get_node(&self) -> NestedNode {
+        self.digits.clone()
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.digits
+    }
 }
-*/
+
+/// value of little endian digits, `None` if it exceeds `u64`
+pub fn digits_to_u64(radix: u32, digits: &[usize]) -> Option<u64> {
+    digits.iter()
+        .rev()
+        .try_fold(0u64, |value, d| {
+            value.checked_mul(radix as u64)?.checked_add(*d as u64)
+        })
 }
 
+
+/// little endian digits of `value`, zero is a single digit
+pub fn u64_to_digits(radix: u32, mut value: u64) -> Vec<usize> {
+    let radix = radix as u64;
+    let mut digits = Vec::new();
+    loop {
+        digits.push((value % radix) as usize);
+        value /= radix;
+        if value == 0 {
+            break;
+        }
+    }
+    digits
+}
@@ -0,0 +1,441 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, singleton::*, sequence::*},
+        buffer::{singleton::*, vec::*, index_hashmap::*}
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, ReprTree},
+        editors::{
+            integer::{PosIntEditor, Endianness},
+            number::IntEditor
+        },
+        terminal::{TerminalEvent, TerminalView, TerminalProjections, make_label},
+        diagnostics::{Message},
+        tree::{NestedNode, TreeNavResult},
+        commander::ObjCommander
+    },
+    std::sync::{Arc, RwLock},
+    termion::event::{Event, Key},
+    cgmath::Point2
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Width and signedness of a machine integer type like `u8` or `i32`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MachineIntType {
+    pub bits: u32,
+    pub signed: bool,
+}
+
+impl MachineIntType {
+    pub const NAMES: [&'static str; 8] = ["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let signed = match name.chars().next()? {
+            'u' => false,
+            'i' => true,
+            _ => return None
+        };
+        match name[1..].parse::<u32>().ok()? {
+            bits @ (8 | 16 | 32 | 64) => Some(MachineIntType { bits, signed }),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}{}", if self.signed { "i" } else { "u" }, self.bits)
+    }
+
+    pub fn min(&self) -> i128 {
+        if self.signed { -(1i128 << (self.bits - 1)) } else { 0 }
+    }
+
+    pub fn max(&self) -> i128 {
+        if self.signed { (1i128 << (self.bits - 1)) - 1 } else { (1i128 << self.bits) - 1 }
+    }
+
+    /// value of little endian `digits`, `None` if it is out of range
+    pub fn value_of(&self, negative: bool, radix: u32, digits: &[usize]) -> Option<i128> {
+        let value = digits.iter().rev().try_fold(0i128, |value, digit|
+            value.checked_mul(radix as i128)?.checked_add(*digit as i128)
+        )?;
+        let value = if negative { -value } else { value };
+        if value >= self.min() && value <= self.max() { Some(value) } else { None }
+    }
+
+    /// two's complement bit pattern of `value`, truncated to `bits`
+    pub fn to_bits(&self, value: i128) -> u64 {
+        let bits = value as u64;
+        if self.bits == 64 { bits } else { bits & ((1u64 << self.bits) - 1) }
+    }
+
+    /// binary digits of `bits`, most significant first, in groups of four
+    pub fn format_bits(&self, bits: u64) -> String {
+        (0..self.bits / 4).rev()
+            .map(|nibble| format!("{:04b}", (bits >> (4 * nibble)) & 0xf))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn get_type(&self, ctx: &Arc<RwLock<Context>>) -> TypeTerm {
+        TypeTerm::TypeID(ctx.read().unwrap().get_typeid(&self.name()).unwrap())
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Fixed width integer, edited as `<PosInt 10 BigEndian>`
+/// or `<Int 10>` for signed types.
+/// The `MachineInt` branch holds the bit pattern, `None` while some
+/// digit is invalid or the value is out of range. Values out of range
+/// produce an error message, the bit view keeps the last valid value.
+/// `Ctrl-b` toggles a view of the binary digits.
+pub struct MachineIntEditor {
+    ctx: Arc<RwLock<Context>>,
+    typ: MachineIntType,
+    inner: NestedNode,
+
+    /// sign, radix and little endian digits of `inner`
+    digits: OuterViewPort<dyn SingletonView<Item = Option<(bool, u32, Vec<usize>)>>>,
+
+    bits: SingletonBuffer<u64>,
+    show_bits: SingletonBuffer<bool>,
+    msg: VecBuffer<Message>,
+}
+
+impl MachineIntEditor {
+    pub fn new(ctx: Arc<RwLock<Context>>, typ: MachineIntType) -> Self {
+        let inner =
+            if typ.signed {
+                IntEditor::new(ctx.clone(), 10).into_node()
+            } else {
+                PosIntEditor::new(ctx.clone(), 10, Endianness::BigEndian).into_node()
+            };
+
+        let digits =
+            if typ.signed {
+                IntEditor::get_node_digits_port(&inner).unwrap()
+            } else {
+                PosIntEditor::get_node_digits_port(&inner).unwrap()
+                    .map(|digits| {
+                        let (radix, digits) = digits?;
+                        Some((false, radix, digits))
+                    })
+            };
+
+        MachineIntEditor {
+            ctx,
+            typ,
+            inner,
+            digits,
+            bits: SingletonBuffer::new(0),
+            show_bits: SingletonBuffer::new(false),
+            msg: VecBuffer::new(),
+        }
+    }
+
+    pub fn get_machine_type(&self) -> MachineIntType {
+        self.typ
+    }
+
+    /// value if all digits are valid and it is in range
+    pub fn get_value(&self) -> Option<i128> {
+        let (negative, radix, digits) = self.digits.get_view()?.get()?;
+        self.typ.value_of(negative, radix, &digits)
+    }
+
+    /// projection of `get_value`, following all edits
+    pub fn get_value_port(&self) -> OuterViewPort<dyn SingletonView<Item = Option<i128>>> {
+        let typ = self.typ;
+        self.digits.map(move |digits| {
+            let (negative, radix, digits) = digits?;
+            typ.value_of(negative, radix, &digits)
+        })
+    }
+
+    /// two's complement bit pattern of the last valid value
+    pub fn get_bits(&self) -> u64 {
+        self.bits.get()
+    }
+
+    pub fn toggle_bit_view(&mut self) {
+        self.show_bits.set(!self.show_bits.get());
+    }
+
+    pub fn get_bit_view(&self) -> OuterViewPort<dyn TerminalView> {
+        let typ = self.typ;
+        let theme = self.ctx.read().unwrap().get_theme();
+        self.bits.get_port()
+            .map(move |bits|
+                make_label(&typ.format_bits(bits))
+                    .with_style(theme.read().unwrap().number)
+            )
+            .to_grid()
+            .flatten()
+    }
+
+    fn update(&mut self) {
+        self.msg.clear();
+
+        let digits = self.digits.get_view().and_then(|digits| digits.get());
+        match digits.map(|(negative, radix, digits)| self.typ.value_of(negative, radix, &digits)) {
+            Some(Some(value)) => {
+                self.bits.set(self.typ.to_bits(value));
+            }
+            Some(None) => {
+                self.msg.push(crate::diagnostics::make_error(
+                    &self.ctx,
+                    make_label(&format!(
+                        "value out of range for {} ({}..={})",
+                        self.typ.name(), self.typ.min(), self.typ.max()
+                    ))
+                ));
+            }
+            // invalid digits are reported by the digit editors
+            None => {}
+        }
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        let ctx = self.ctx.clone();
+        let inner = self.inner.clone();
+        let machine_type = self.typ;
+        let bits = self.get_value_port()
+            .map(move |value| value.map(|value| machine_type.to_bits(value)));
+        let show_bits = self.show_bits.clone();
+        let bit_view = self.get_bit_view();
+        let msg = self.msg.clone();
+        let typ = self.typ.get_type(&ctx);
+        let editor = Arc::new(RwLock::new(self));
+
+        let mut grid = IndexBuffer::new();
+        grid.insert_iter(vec![
+            (Point2::new(0, 0), inner.get_view()),
+            (Point2::new(1, 0), show_bits.get_port()
+                .map(|show| make_label(if show { " = 0b" } else { "" }))
+                .to_grid()
+                .flatten()),
+            (Point2::new(2, 0), show_bits.get_port()
+                .map(move |show| if show { bit_view.clone() } else { make_label("") })
+                .to_grid()
+                .flatten()),
+        ]);
+
+        // navigation and hit test are those of the digits,
+        // which are drawn first
+        let mut node = inner.clone();
+        node.data = ReprTree::ascend(&inner.data, typ);
+        node.data.write().unwrap().insert_leaf(
+            vec![ Context::parse(&ctx, "MachineInt") ].into_iter(),
+            bits.into()
+        );
+        node.view = Some(grid.get_port().flatten());
+        node.diag = Some(
+            VecBuffer::with_data(vec![
+                inner.get_diag(),
+                msg.get_port().to_sequence()
+            ])
+                .get_port()
+                .to_sequence()
+                .flatten()
+        );
+        node.editor = SingletonBuffer::new(None);
+        node.cmd = SingletonBuffer::new(None);
+
+        node.set_editor(editor.clone())
+            .set_cmd(editor)
+    }
+}
+
+impl ObjCommander for MachineIntEditor {
+    fn send_cmd_obj(&mut self, cmd_obj: Arc<RwLock<ReprTree>>) -> TreeNavResult {
+        let co = cmd_obj.read().unwrap();
+        if let Some(event) = co.get_view::<dyn SingletonView<Item = TerminalEvent>>() {
+            if event.get() == TerminalEvent::Input(Event::Key(Key::Ctrl('b'))) {
+                self.toggle_bit_view();
+                return TreeNavResult::Continue;
+            }
+        }
+        drop(co);
+
+        let result = self.inner.send_cmd_obj(cmd_obj);
+        self.update();
+        result
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            editors::{integer::PosIntCmd, list::ListCmd},
+            tree::{TreeCursor, TreeNav},
+        },
+    };
+
+    fn typ(name: &str) -> MachineIntType {
+        MachineIntType::from_name(name).unwrap()
+    }
+
+    #[test]
+    fn names() {
+        for name in MachineIntType::NAMES.iter() {
+            assert_eq!(typ(name).name(), *name);
+        }
+        assert_eq!(typ("u64"), MachineIntType { bits: 64, signed: false });
+        assert_eq!(typ("i8"), MachineIntType { bits: 8, signed: true });
+
+        for name in ["", "u", "i7", "u128", "f32", "x8", "u-8"].iter() {
+            assert_eq!(MachineIntType::from_name(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        assert_eq!((typ("i8").min(), typ("i8").max()), (-128, 127));
+        assert_eq!((typ("u8").min(), typ("u8").max()), (0, 255));
+        assert_eq!((typ("u64").min(), typ("u64").max()), (0, u64::MAX as i128));
+        assert_eq!((typ("i64").min(), typ("i64").max()), (i64::MIN as i128, i64::MAX as i128));
+    }
+
+    #[test]
+    fn twos_complement() {
+        assert_eq!(typ("i8").to_bits(-1), 0xff);
+        assert_eq!(typ("i8").to_bits(-128), 0x80);
+        assert_eq!(typ("i16").to_bits(-2), 0xfffe);
+        assert_eq!(typ("i64").to_bits(-1), u64::MAX);
+        assert_eq!(typ("u64").to_bits(u64::MAX as i128), u64::MAX);
+        assert_eq!(typ("u8").to_bits(5), 5);
+    }
+
+    #[test]
+    fn bit_format() {
+        assert_eq!(typ("u8").format_bits(0xa5), "1010 0101");
+        assert_eq!(typ("i16").format_bits(typ("i16").to_bits(-1)), "1111 1111 1111 1111");
+        assert_eq!(typ("u64").format_bits(1).len(), 64 + 15);
+    }
+
+    fn type_chars(node: &mut NestedNode, chars: &str) {
+        for c in chars.chars() {
+            node.send_cmd_obj(ReprTree::from_char(&node.ctx, c));
+        }
+    }
+
+    /// value, last valid bits, number of messages and `MachineInt` bits
+    fn state(node: &NestedNode) -> (Option<i128>, u64, usize, Option<u64>) {
+        let machine_int = node.data.read().unwrap()
+            .descend(Context::parse(&node.ctx, "MachineInt")).unwrap()
+            .read().unwrap()
+            .get_view::<dyn SingletonView<Item = Option<u64>>>().unwrap()
+            .get();
+
+        let edit = node.get_edit::<MachineIntEditor>().unwrap();
+        let edit = edit.read().unwrap();
+        (edit.get_value(), edit.get_bits(), edit.msg.len(), machine_int)
+    }
+
+    #[test]
+    fn out_of_range_keeps_last_bits() {
+        let ctx = Arc::new(RwLock::new(Context::default()));
+        let mut node = MachineIntEditor::new(ctx.clone(), typ("u8")).into_node();
+        node.goto(TreeCursor::home());
+
+        type_chars(&mut node, "25");
+        assert_eq!(state(&node), (Some(25), 25, 0, Some(25)));
+
+        type_chars(&mut node, "6");
+        assert_eq!(state(&node), (None, 25, 1, None));
+
+        node.send_cmd_obj(ListCmd::Clear.into_repr_tree(&ctx));
+        node.goto(TreeCursor::home());
+        type_chars(&mut node, "0");
+        assert_eq!(state(&node), (Some(0), 0, 0, Some(0)));
+    }
+
+    #[test]
+    fn negative_values() {
+        let ctx = Arc::new(RwLock::new(Context::default()));
+        let mut node = MachineIntEditor::new(ctx.clone(), typ("i8")).into_node();
+        node.goto(TreeCursor::home());
+
+        type_chars(&mut node, "-1");
+        assert_eq!(state(&node), (Some(-1), 0xff, 0, Some(0xff)));
+
+        type_chars(&mut node, "29");
+        assert_eq!(state(&node), (None, 0xff, 1, None));
+
+        // clearing the digits keeps the sign
+        node.send_cmd_obj(ListCmd::Clear.into_repr_tree(&ctx));
+        node.goto(TreeCursor::home());
+        type_chars(&mut node, "128");
+        assert_eq!(state(&node), (Some(-128), 0x80, 0, Some(0x80)));
+    }
+
+    fn key_event(ctx: &Arc<RwLock<Context>>, key: Key) -> Arc<RwLock<ReprTree>> {
+        ReprTree::new_leaf(
+            Context::parse(ctx, "TerminalEvent"),
+            SingletonBuffer::new(TerminalEvent::Input(Event::Key(key))).get_port().into()
+        )
+    }
+
+    #[test]
+    fn endianness_toggle_keeps_the_inner_type() {
+        let ctx = Arc::new(RwLock::new(Context::default()));
+        let mut node = MachineIntEditor::new(ctx.clone(), typ("u8")).into_node();
+        node.goto(TreeCursor::home());
+        for c in "12".chars() {
+            node.send_cmd_obj(ReprTree::from_char(&ctx, c));
+        }
+        node.send_cmd_obj(key_event(&ctx, Key::Ctrl('e')));
+
+        // still filed under the type the digits were created with
+        let posint_type = PosIntEditor::get_type(&ctx, 10, Endianness::BigEndian);
+        let posint = node.data.read().unwrap().descend(posint_type.clone()).unwrap();
+        assert_eq!(posint.read().unwrap().get_type(), &posint_type);
+
+        let edit = node.get_edit::<MachineIntEditor>().unwrap();
+        let edit = edit.read().unwrap();
+        assert_eq!(PosIntEditor::get_node_digits(&edit.inner), Some((10, vec![2, 1])));
+        assert_eq!((edit.get_value(), edit.get_bits()), (Some(12), 12));
+    }
+
+    #[test]
+    fn radix_conversion_is_visible_through_the_wrapper() {
+        let ctx = Arc::new(RwLock::new(Context::default()));
+        let mut node = MachineIntEditor::new(ctx.clone(), typ("u8")).into_node();
+        node.goto(TreeCursor::home());
+        for c in "200".chars() {
+            node.send_cmd_obj(ReprTree::from_char(&ctx, c));
+        }
+
+        let posint_type = PosIntEditor::get_type(&ctx, 10, Endianness::BigEndian);
+        let posint = node.data.read().unwrap().descend(posint_type.clone()).unwrap();
+        // a branch added by some morphism
+        posint.write().unwrap().insert_leaf(
+            vec![ Context::parse(&ctx, "ℕ") ].into_iter(),
+            SingletonBuffer::new(200u64).get_port().into()
+        );
+
+        node.send_cmd_obj(PosIntCmd::SetRadix(16).into_repr_tree(&ctx));
+
+        // the same tree, still filed under its type
+        let outer_posint = node.data.read().unwrap().descend(posint_type.clone()).unwrap();
+        assert!(Arc::ptr_eq(&posint, &outer_posint));
+        let posint = posint.read().unwrap();
+        assert_eq!(posint.get_type(), &posint_type);
+        assert!(posint.descend(Context::parse(&ctx, "ℕ")).is_some());
+        assert!(posint.descend(Context::parse(&ctx, "<List <Digit 16>>")).is_some());
+        assert!(posint.descend(Context::parse(&ctx, "<List <Digit 10>>")).is_none());
+
+        let edit = node.get_edit::<MachineIntEditor>().unwrap();
+        let edit = edit.read().unwrap();
+        // 200 = 0xc8
+        assert_eq!(PosIntEditor::get_node_digits(&edit.inner), Some((16, vec![8, 12])));
+        assert_eq!(edit.get_value(), Some(200));
+    }
+}
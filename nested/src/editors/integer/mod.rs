@@ -1,12 +1,16 @@
 pub mod add;
+pub mod bignum;
 pub mod editor;
+pub mod machine;
 pub mod radix;
 pub mod ctx;
 
 pub use {
     add::Add,
-    editor::{DigitEditor, PosIntEditor},
+    bignum::BignumProjection,
+    editor::{DigitEditor, DigitFormat, Endianness, PosIntCmd, PosIntEditor, digits_to_u64, u64_to_digits},
+    machine::{MachineIntEditor, MachineIntType},
     radix::RadixProjection,
-    ctx::init_ctx
+    ctx::{init_ctx, radix_arg}
 };
 
@@ -6,14 +6,111 @@ use {
         },
         buffer::{vec::*}
     },
-    std::sync::{Arc, RwLock},
+    std::{
+        ops::RangeInclusive,
+        sync::{Arc, RwLock},
+    },
 };
 
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `(base, exp)` with `radix = base^exp` and `base` as small as possible
+fn perfect_power(radix: usize) -> (usize, u32) {
+    if radix < 2 {
+        return (radix, 1);
+    }
+
+    let max_exp = usize::BITS - radix.leading_zeros() - 1;
+    for exp in (2 ..= max_exp).rev() {
+        let root = (radix as f64).powf(1.0 / exp as f64).round() as usize;
+        for base in root.saturating_sub(1) ..= root + 1 {
+            if base > 1 && base.checked_pow(exp) == Some(radix) {
+                return (base, exp);
+            }
+        }
+    }
+    (radix, 1)
+}
+
+/// Source and destination radix are powers of a common base:
+/// every digit covers a fixed window of base-digits, so each
+/// destination digit depends on a fixed window of source digits.
+#[derive(Clone, Copy, Debug)]
+struct PowerRadices {
+    base: usize,
+    src_exp: usize,
+    dst_exp: usize,
+}
+
+impl PowerRadices {
+    fn new(src_radix: usize, dst_radix: usize) -> Option<Self> {
+        let (src_base, src_exp) = perfect_power(src_radix);
+        let (dst_base, dst_exp) = perfect_power(dst_radix);
+
+        if src_base == dst_base {
+            Some(PowerRadices {
+                base: src_base,
+                src_exp: src_exp as usize,
+                dst_exp: dst_exp as usize,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// base-digit `k` of the number
+    fn base_digit(&self, src: &impl Fn(usize) -> usize, k: usize) -> usize {
+        (src(k / self.src_exp) / self.base.pow((k % self.src_exp) as u32)) % self.base
+    }
+
+    fn dst_digit(&self, src: &impl Fn(usize) -> usize, j: usize) -> usize {
+        (0 .. self.dst_exp).rev().fold(0, |val, t| {
+            val * self.base + self.base_digit(src, j * self.dst_exp + t)
+        })
+    }
+
+    /// number of significant destination digits,
+    /// given the most significant nonzero source digit
+    fn dst_len(&self, src: &impl Fn(usize) -> usize, src_top: Option<usize>) -> usize {
+        match src_top {
+            Some(i) => {
+                let mut d = src(i);
+                let mut base_len = i * self.src_exp;
+                while d > 0 {
+                    d /= self.base;
+                    base_len += 1;
+                }
+                (base_len + self.dst_exp - 1) / self.dst_exp
+            }
+            None => 0
+        }
+    }
+
+    /// destination digits depending on source digit `i`
+    fn affected(&self, i: usize) -> RangeInclusive<usize> {
+        (i * self.src_exp) / self.dst_exp ..= ((i + 1) * self.src_exp - 1) / self.dst_exp
+    }
+}
+
+/// index of the most significant nonzero digit below `len`
+fn top_digit(src: &impl Fn(usize) -> usize, len: usize) -> Option<usize> {
+    (0 .. len).rev().find(|i| src(*i) > 0)
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
 pub struct RadixProjection {
     src_radix: usize,
     dst_radix: usize,
     src_digits: Option<Arc<dyn SequenceView<Item = usize>>>,
     dst_digits: RwLock<VecBuffer<usize>>,
+    cur_dst_digits: Vec<usize>,
+
+    /// set if digits can be updated incrementally
+    power_radices: Option<PowerRadices>,
+
+    /// most significant nonzero source digit
+    src_top: Option<usize>,
 }
 
 impl RadixProjection {
@@ -38,64 +135,230 @@ impl RadixProjection {
             dst_radix,
             src_digits: None,
             dst_digits: RwLock::new(VecBuffer::with_port(dst_digits)),
+            cur_dst_digits: Vec::new(),
+            power_radices: PowerRadices::new(src_radix, dst_radix),
+            src_top: None,
         }));
         src_digits.add_observer(proj.clone());
         proj
     }
 
-    fn machine_int(&self) -> usize {
-        let mut val = 0;
-        let mut r = 1;
-        for i in 0..self.src_digits.len().unwrap_or(0) {
-            val += r * self.src_digits.get(&i).unwrap();
-            r *= self.src_radix;
-        }
+    // recalculate everything
+    fn update(&mut self) {
+        let src = self.src_digits.as_ref()
+            .map(|v| super::bignum::to_digits(v))
+            .unwrap_or(vec![]);
+        let dst = super::bignum::convert_radix(self.src_radix, &src, self.dst_radix);
 
-        val
+        super::bignum::set_digits(
+            &mut self.dst_digits.write().unwrap(),
+            &mut self.cur_dst_digits,
+            dst,
+        );
     }
 
-    // recalculate everything
-    fn update(&self) {
+    fn set_dst_digit(&mut self, idx: usize, v: usize) {
         let mut dst = self.dst_digits.write().unwrap();
-        dst.clear();
+        if idx < self.cur_dst_digits.len() {
+            if self.cur_dst_digits[idx] != v {
+                self.cur_dst_digits[idx] = v;
+                *dst.get_mut(idx) = v;
+            }
+        } else if idx == self.cur_dst_digits.len() {
+            self.cur_dst_digits.push(v);
+            dst.push(v);
+        }
+    }
 
-        let mut val = self.machine_int();
+    /// source digit `src_idx` changed: update only the
+    /// destination digits covering the same base-digits
+    fn update_incremental(&mut self, radices: PowerRadices, src_idx: usize) {
+        let src_view = match self.src_digits.clone() {
+            Some(v) => v,
+            None => return,
+        };
+        let src = |i: usize| src_view.get(&i).unwrap_or(0);
+        let src_len = src_view.len().unwrap_or(0);
 
-        while val > 0 {
-            dst.push(val % self.dst_radix);
-            val /= self.dst_radix;
+        // rescan only if the top digit vanished
+        let mut src_top = match self.src_top {
+            Some(top) if top >= src_len || src(top) == 0 => top_digit(&src, std::cmp::min(top + 1, src_len)),
+            top => top
+        };
+        if src_idx < src_len && src(src_idx) > 0 {
+            src_top = std::cmp::max(src_top, Some(src_idx));
         }
-    }
+        self.src_top = src_top;
 
-    fn _update_dst_digit(&mut self, _idx: usize) {
-        /*
-                let v = 0; // calculate new digit value
+        let dst_len = radices.dst_len(&src, src_top);
 
-                // which src-digits are responsible?
+        // number got shorter
+        while self.cur_dst_digits.len() > dst_len {
+            self.cur_dst_digits.pop();
+            self.dst_digits.write().unwrap().remove(self.cur_dst_digits.len());
+        }
 
-                if idx < self.dst_digits.len() {
-                    self.dst_digits.get_mut(idx) = v;
-                } else if idx == self.dst_digits.len() {
-                    self.dst_digits.push(v);
-                } else {
-                    // error
-                }
-        */
+        for j in radices.affected(src_idx) {
+            if j < self.cur_dst_digits.len() {
+                self.set_dst_digit(j, radices.dst_digit(&src, j));
+            }
+        }
+
+        // number got longer
+        for j in self.cur_dst_digits.len() .. dst_len {
+            self.set_dst_digit(j, radices.dst_digit(&src, j));
+        }
     }
 }
 
 impl Observer<dyn SequenceView<Item = usize>> for RadixProjection {
     fn reset(&mut self, view: Option<Arc<dyn SequenceView<Item = usize>>>) {
         self.src_digits = view;
+        self.src_top = self.src_digits.as_ref().and_then(|v| {
+            top_digit(&|i| v.get(&i).unwrap_or(0), v.len().unwrap_or(0))
+        });
+        self.update();
     }
 
-    fn notify(&mut self, _idx: &usize) {
-        // todo:
-        // src digit i changed.
-        // which dst-digits does it affect?
-        // update dst-digit j:
+    fn notify(&mut self, idx: &usize) {
+        match self.power_radices {
+            Some(radices) => self.update_incremental(radices, *idx),
 
-        // ...but for now the easy way
-        self.update();
+            // every destination digit may depend on every source digit
+            None => self.update(),
+        }
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::editors::integer::bignum::{convert_radix, to_digits},
+        r3vi::view::ViewPort,
+    };
+
+    /// radix pairs with a common base, in both directions
+    const POWER_RADICES: [(usize, usize); 8] = [
+        (2, 16), (16, 2),
+        (8, 16), (16, 8),
+        (16, 256), (256, 16),
+        (10, 100), (100, 10),
+    ];
+
+    #[test]
+    fn power_radices_are_detected() {
+        for (src, dst) in POWER_RADICES.iter() {
+            assert!(PowerRadices::new(*src, *dst).is_some(), "{} -> {}", src, dst);
+        }
+        assert!(PowerRadices::new(10, 16).is_none());
+        assert!(PowerRadices::new(6, 36 * 6).is_some());
+        assert!(PowerRadices::new(4, 6).is_none());
+    }
+
+    #[test]
+    fn affected_digits_cover_the_same_bits() {
+        // octal digit 2 covers bits 6..=8, i.e. hex digits 1 and 2
+        let r = PowerRadices::new(8, 16).unwrap();
+        assert_eq!(r.affected(2), 1 ..= 2);
+        assert_eq!(r.affected(1), 0 ..= 1);
+
+        // hex digit 3 lies in byte 1
+        let r = PowerRadices::new(16, 256).unwrap();
+        assert_eq!(r.affected(3), 1 ..= 1);
+
+        // byte 1 covers hex digits 2 and 3
+        let r = PowerRadices::new(256, 16).unwrap();
+        assert_eq!(r.affected(1), 2 ..= 3);
+    }
+
+    #[test]
+    fn dst_len_counts_significant_digits() {
+        // 0b1_0000_0000 = 0x100
+        let r = PowerRadices::new(2, 16).unwrap();
+        let src = |i: usize| if i == 8 { 1 } else { 0 };
+        assert_eq!(r.dst_len(&src, top_digit(&src, 9)), 3);
+        assert_eq!(r.dst_len(&src, None), 0);
+
+        // 99 = (99)₁₀₀, 100 = (1 0)₁₀₀
+        let r = PowerRadices::new(10, 100).unwrap();
+        let nines = |_| 9;
+        assert_eq!(r.dst_len(&nines, top_digit(&nines, 2)), 1);
+        let hundred = |i: usize| if i == 2 { 1 } else { 0 };
+        assert_eq!(r.dst_len(&hundred, top_digit(&hundred, 3)), 2);
+    }
+
+    #[test]
+    fn top_digit_skips_leading_zeros() {
+        let src = |i: usize| [3, 0, 5, 0, 0][i];
+        assert_eq!(top_digit(&src, 5), Some(2));
+        assert_eq!(top_digit(&src, 2), Some(0));
+        assert_eq!(top_digit(&|_| 0, 4), None);
+    }
+
+    /// pseudo random numbers, reproducible across runs
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((self.0 >> 33) % n as u64) as usize
+        }
+    }
+
+    #[test]
+    fn incremental_updates_match_full_conversion() {
+        for (src_radix, dst_radix) in POWER_RADICES.iter() {
+            let src_port = ViewPort::new();
+            let dst_port = ViewPort::new();
+            let mut src = VecBuffer::<usize>::with_port(src_port.inner());
+            let proj = RadixProjection::new(*src_radix, *dst_radix, src_port.outer().to_sequence(), dst_port.inner());
+            assert!(proj.read().unwrap().power_radices.is_some());
+            let dst = dst_port.outer().to_sequence().get_view().unwrap();
+
+            let mut rng = Lcg(*src_radix as u64 * 1000 + *dst_radix as u64);
+            for step in 0 .. 500 {
+                // zeros often, to move the top digit around
+                let digit = match rng.below(3) {
+                    0 => 0,
+                    _ => rng.below(*src_radix),
+                };
+                let len = src.len();
+
+                let edit = match rng.below(5) {
+                    0 if len < 16 => {
+                        src.push(digit);
+                        "push"
+                    }
+                    1 if len > 0 => {
+                        src.remove(len - 1);
+                        "pop"
+                    }
+                    2 if len < 16 => {
+                        src.insert(rng.below(len + 1), digit);
+                        "insert"
+                    }
+                    3 if len > 0 => {
+                        src.remove(rng.below(len));
+                        "remove"
+                    }
+                    _ if len > 0 => {
+                        *src.get_mut(rng.below(len)) = digit;
+                        "set"
+                    }
+                    _ => continue,
+                };
+
+                let src_digits: Vec<usize> = (0 .. src.len()).map(|i| src.get(i)).collect();
+                assert_eq!(
+                    to_digits(&dst),
+                    convert_radix(*src_radix, &src_digits, *dst_radix),
+                    "{} -> {}, step {} ({}) with source digits {:?}",
+                    src_radix, dst_radix, step, edit, src_digits
+                );
+            }
+        }
     }
 }
@@ -19,6 +19,9 @@ use {
 pub struct ListEditor {
     pub(super) cursor: SingletonBuffer<ListCursor>,
 
+    /// range of selected items `[from, to)`
+    pub(super) selection: SingletonBuffer<Option<(isize, isize)>>,
+
     // todo: (?) remove RwLock<..> around NestedNode ??
     pub data: VecBuffer< Arc<RwLock<NestedNode>> >,
 
@@ -95,6 +98,7 @@ impl ListEditor {
                 .to_sequence()
                 .flatten(),
             cursor,
+            selection: SingletonBuffer::new(None),
             data,
             spillbuf: Arc::new(RwLock::new(Vec::new())),
             ctx,
@@ -144,6 +148,11 @@ impl ListEditor {
         self.typ.clone()
     }
 
+    /// items inserted from now on are created with type `typ`
+    pub fn set_item_type(&mut self, typ: TypeTerm) {
+        self.typ = typ;
+    }
+
     pub fn get_seq_type(&self) -> TypeTerm {
         TypeTerm::App(vec![
             TypeTerm::TypeID(self.ctx.read().unwrap().get_typeid("List").unwrap()),
@@ -155,6 +164,10 @@ impl ListEditor {
         self.cursor.get_port()
     }
 
+    pub fn get_selection_port(&self) -> OuterViewPort<dyn SingletonView<Item = Option<(isize, isize)>>> {
+        self.selection.get_port()
+    }
+
     pub fn get_data_port(&self) -> OuterViewPort<dyn SequenceView<Item = NestedNode>> {
         self.data.get_port().to_sequence().map(
             |x| x.read().unwrap().clone()
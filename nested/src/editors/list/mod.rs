@@ -12,7 +12,7 @@ pub use {
     cursor::{ListCursor, ListCursorMode},
     editor::ListEditor,
     segment::{ListSegment, ListSegmentSequence},
-    pty_editor::{PTYListStyle, PTYListController},
+    pty_editor::{PTYListStyle, PTYListController, PTYListHitTest},
     cmd::ListCmd,
     ctx::init_ctx
 };
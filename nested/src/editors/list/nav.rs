@@ -121,6 +121,8 @@ impl TreeNav for ListEditor {
     }
 
     fn goto(&mut self, new_cur: TreeCursor) -> TreeNavResult {
+        self.selection.set(None);
+
         let old_cur = self.cursor.get();
         if let Some(i) = old_cur.idx {
             if i < self.data.len() as isize {
@@ -181,6 +183,54 @@ impl TreeNav for ListEditor {
         }
     }
 
+    fn select_to(&mut self, head: TreeCursor) -> TreeNavResult {
+        let anchor = self.get_cursor();
+
+        // both ends inside the same item: let the item handle it
+        if anchor.tree_addr.len() > 1 &&
+            head.tree_addr.len() > 1 &&
+            anchor.tree_addr[0] == head.tree_addr[0]
+        {
+            let idx = anchor.tree_addr[0];
+            if idx >= 0 && idx < self.data.len() as isize {
+                self.selection.set(None);
+                return self.data
+                    .get_mut(idx as usize)
+                    .write().unwrap()
+                    .select_to(TreeCursor {
+                        leaf_mode: head.leaf_mode,
+                        tree_addr: head.tree_addr[1..].iter().cloned().collect()
+                    });
+            }
+        }
+
+        // an insert-position lies between items,
+        // anything else covers the whole item
+        let bounds = |c: &TreeCursor| -> Option<(isize, isize)> {
+            let idx = *c.tree_addr.first()?;
+            if c.tree_addr.len() == 1 && c.leaf_mode == ListCursorMode::Insert {
+                Some((idx, idx))
+            } else {
+                Some((idx, idx + 1))
+            }
+        };
+
+        match (bounds(&anchor), bounds(&head)) {
+            (Some(a), Some(h)) => {
+                let from = std::cmp::max(0, std::cmp::min(a.0, h.0));
+                let to = std::cmp::min(self.data.len() as isize, std::cmp::max(a.1, h.1));
+                self.selection.set(
+                    if from < to { Some((from, to)) } else { None }
+                );
+                TreeNavResult::Continue
+            }
+            _ => {
+                self.selection.set(None);
+                TreeNavResult::Exit
+            }
+        }
+    }
+
     fn goby(&mut self, direction: Vector2<isize>) -> TreeNavResult {
         let mut cur = self.get_cursor();
 
@@ -1,18 +1,19 @@
 use {
     r3vi::{
-        view::{ViewPort, OuterViewPort, sequence::*},
+        view::{ViewPort, OuterViewPort, sequence::*, index::*},
         projection::decorate_sequence::*,
     },
     crate::{
         type_system::{Context, ReprTree},
         editors::list::*,
-        terminal::{TerminalEvent, TerminalView, make_label},
-        tree::{TreeCursor, TreeNav, TreeNavResult},
+        terminal::{TerminalEvent, TerminalView, make_label, area_extent, display_width},
+        tree::{TreeCursor, TreeNav, TreeNavResult, TreeHeightOp, HitTest},
         tree::NestedNode,
         PtySegment
     },
     std::sync::{Arc, RwLock},
-    termion::event::{Event, Key}
+    termion::event::{Event, Key},
+    cgmath::Point2
 };
 
 //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
@@ -28,24 +29,28 @@ impl PTYListStyle {
         }
     }
 
-    pub fn get_seg_seq_view(&self, editor: &ListEditor) -> OuterViewPort<dyn SequenceView<Item = OuterViewPort<dyn TerminalView>>> {
+    pub fn get_segment_port(editor: &ListEditor) -> OuterViewPort<dyn SequenceView<Item = ListSegment>> {
         let seg_seq = ListSegmentSequence::new(
             editor.get_cursor_port(),
-            editor.get_data_port()
+            editor.get_data_port(),
+            editor.get_selection_port(),
+            editor.ctx.read().unwrap().get_theme()
         );
         let se = seg_seq.read().unwrap();
-        se.get_view().map(move |segment| segment.pty_view())
+        se.get_view()
+    }
+
+    pub fn get_seg_seq_view(&self, editor: &ListEditor) -> OuterViewPort<dyn SequenceView<Item = OuterViewPort<dyn TerminalView>>> {
+        Self::get_segment_port(editor)
+            .map(move |segment| segment.pty_view())
     }
 
     pub fn pty_view(&self, editor: &ListEditor) -> OuterViewPort<dyn TerminalView> {
-        let seg_seq = ListSegmentSequence::new(
-            editor.get_cursor_port(),
-            editor.get_data_port()
-        );
-        let seg_seq = seg_seq.read().unwrap();
+        self.layout(Self::get_segment_port(editor))
+    }
 
-        seg_seq
-            .get_view()
+    fn layout(&self, segments: OuterViewPort<dyn SequenceView<Item = ListSegment>>) -> OuterViewPort<dyn TerminalView> {
+        segments
             .map(move |segment| segment.pty_view())
             .separate(make_label(&self.style.1))
             .wrap(make_label(&self.style.0), make_label(&self.style.2))
@@ -53,13 +58,93 @@ impl PTYListStyle {
             .flatten()
     }
 
+    /// view of `editor` in this style, together with its hit test
+    pub fn layout_editor(&self, editor: &ListEditor) -> (OuterViewPort<dyn TerminalView>, PTYListHitTest) {
+        let segments = Self::get_segment_port(editor);
+        (
+            self.layout(segments.clone()),
+            PTYListHitTest {
+                segments,
+                style: self.style.clone()
+            }
+        )
+    }
+
     pub fn for_node(node: &mut NestedNode, style: (&str, &str, &str)) {
-        node.view = Some(
-            Self::new(style)
-                .pty_view(
-                    &node.get_edit::<ListEditor>().unwrap().read().unwrap()
-                )
+        let (view, hit) = Self::new(style).layout_editor(
+            &node.get_edit::<ListEditor>().unwrap().read().unwrap()
         );
+
+        node.view = Some(view);
+        node.hit.set(Some(Arc::new(RwLock::new(hit))));
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// maps positions in the horizontal layout of `PTYListStyle`
+/// back to a cursor inside the list
+pub struct PTYListHitTest {
+    segments: OuterViewPort<dyn SequenceView<Item = ListSegment>>,
+    style: (String, String, String)
+}
+
+impl HitTest for PTYListHitTest {
+    fn hit(&self, pos: &Point2<i16>) -> Option<TreeCursor> {
+        let insert_at = |idx: isize| TreeCursor {
+            leaf_mode: ListCursorMode::Insert,
+            tree_addr: vec![ idx ]
+        };
+
+        let segments = self.segments.get_view();
+        let sep_width = display_width(&self.style.1) as i16;
+        let mut x = display_width(&self.style.0) as i16;
+        let mut item_idx = 0;
+
+        if pos.x < x {
+            return Some(insert_at(0));
+        }
+
+        for seg_idx in 0 .. segments.len().unwrap_or(0) {
+            if seg_idx > 0 {
+                if pos.x < x + sep_width {
+                    return Some(insert_at(item_idx));
+                }
+                x += sep_width;
+            }
+
+            match segments.get(&seg_idx)? {
+                ListSegment::InsertCursor{ .. } => {
+                    if pos.x < x + 1 {
+                        return Some(insert_at(item_idx));
+                    }
+                    x += 1;
+                }
+                ListSegment::Item{ editor, .. } => {
+                    let extent = area_extent(&editor.get_view().get_view().area());
+                    if pos.x < x + extent.x {
+                        let rel_pos = Point2::new(pos.x - x, pos.y);
+                        return Some(
+                            if let Some(mut c) = editor.hit(&rel_pos) {
+                                c.tree_addr.insert(0, item_idx);
+                                c
+                            } else if editor.get_height(&TreeHeightOp::Max) == 0 {
+                                insert_at(item_idx)
+                            } else {
+                                TreeCursor {
+                                    leaf_mode: ListCursorMode::Select,
+                                    tree_addr: vec![ item_idx ]
+                                }
+                            }
+                        );
+                    }
+                    x += extent.x;
+                    item_idx += 1;
+                }
+            }
+        }
+
+        Some(insert_at(item_idx))
     }
 }
 
@@ -9,37 +9,43 @@ use {
     },
     crate::{
         editors::list::{ListCursor, ListCursorMode},
-        terminal::{TerminalView, TerminalStyle, make_label},
+        terminal::{TerminalView, TerminalStyle, TerminalCursorShape, Theme, make_label},
         tree::{NestedNode, TreeNav},
-        utils::color::{bg_style_from_depth, fg_style_from_depth},
         PtySegment
     },
     std::sync::Arc,
     std::sync::RwLock,
+    cgmath::Point2,
 };
 
 pub enum ListSegment {
-    InsertCursor,
+    InsertCursor {
+        theme: Arc<RwLock<Theme>>,
+    },
     Item {
         editor: NestedNode,
         cur_dist: isize,
+        selected: bool,
     }
 }
 
 impl PtySegment for ListSegment {
     fn pty_view(&self) -> OuterViewPort<dyn TerminalView> {
         match self {
-            ListSegment::InsertCursor => {
+            ListSegment::InsertCursor{ theme } => {
+                let theme = theme.clone();
                 make_label("|")
                     .map_item(move |_pt, atom| {
-                     atom.add_style_front(TerminalStyle::fg_color((150,80,230)))
-                        .add_style_front(TerminalStyle::bold(true))
+                     atom.add_style_front(theme.read().unwrap().cursor)
+                        .with_cursor(TerminalCursorShape::Bar)
                     })
             }
-            ListSegment::Item{ editor, cur_dist } => {
+            ListSegment::Item{ editor, cur_dist, selected } => {
+                let theme = editor.ctx.read().unwrap().get_theme();
                 let e = editor.clone();
                 let cur_dist = *cur_dist;
-                editor.get_view().map_item(move |_pt, atom| {
+                let selected = *selected;
+                editor.get_view().map_item(move |pt, atom| {
                     let c = e.get_cursor();
                     let cur_depth = c.tree_addr.len();
                     let select =
@@ -48,11 +54,26 @@ impl PtySegment for ListSegment {
                         } else {
                             usize::MAX
                         };
-                    
-                    atom
-                        .add_style_back(bg_style_from_depth(select))
+
+                    let theme = theme.read().unwrap();
+                    let atom = atom
+                        .add_style_back(theme.bg_from_depth(select))
                         .add_style_back(TerminalStyle::bold(select==1))
-                        .add_style_back(fg_style_from_depth(e.depth.get_view().get()))
+                        .add_style_back(theme.fg_from_depth(e.depth.get_view().get()));
+
+                    let atom =
+                        if selected {
+                            atom.add_style_front(theme.selection)
+                        } else {
+                            atom
+                        };
+
+                    // the whole item is selected
+                    if select == 0 && *pt == Point2::new(0, 0) {
+                        atom.with_cursor(TerminalCursorShape::Block)
+                    } else {
+                        atom
+                    }
                 })
             }
         }
@@ -62,6 +83,8 @@ impl PtySegment for ListSegment {
 pub struct ListSegmentSequence {
     data: Arc<dyn SequenceView<Item = NestedNode>>,
     cursor: Arc<dyn SingletonView<Item = ListCursor>>,
+    selection: Arc<dyn SingletonView<Item = Option<(isize, isize)>>>,
+    theme: Arc<RwLock<Theme>>,
 
     cur_cursor: ListCursor,
 
@@ -88,26 +111,40 @@ impl SequenceView for ListSegmentSequence {
 
     fn get(&self, idx: &usize) -> Option<Self::Item> {
         let c = self.cursor.get();
+        let selection = self.selection.get();
+        let is_selected = |data_idx: usize| {
+            if let Some((from, to)) = selection {
+                from <= data_idx as isize && (data_idx as isize) < to
+            } else {
+                false
+            }
+        };
+
         Some(if let Some(cur) = c.idx {
             match c.mode {
                 ListCursorMode::Select => {
                     ListSegment::Item {
                         editor: self.data.get(idx)?,
-                        cur_dist: cur - *idx as isize
+                        cur_dist: cur - *idx as isize,
+                        selected: is_selected(*idx)
                     }
                 }
                 ListCursorMode::Insert => {
                     if *idx < cur as usize {
                         ListSegment::Item {
                             editor: self.data.get(idx)?,
-                            cur_dist: cur - *idx as isize
+                            cur_dist: cur - *idx as isize,
+                            selected: is_selected(*idx)
                         }
                     } else if *idx == cur as usize {
-                        ListSegment::InsertCursor
+                        ListSegment::InsertCursor {
+                            theme: self.theme.clone()
+                        }
                     } else {
                         ListSegment::Item {
                             editor: self.data.get(&(*idx - 1))?,
-                            cur_dist: cur - *idx as isize
+                            cur_dist: cur - *idx as isize,
+                            selected: is_selected(*idx - 1)
                         }
                     }
                 }
@@ -115,7 +152,8 @@ impl SequenceView for ListSegmentSequence {
         } else {
             ListSegment::Item {
                 editor: self.data.get(&idx)?,
-                cur_dist: *idx as isize + 1
+                cur_dist: *idx as isize + 1,
+                selected: is_selected(*idx)
             }
         })
     }
@@ -125,11 +163,14 @@ impl ListSegmentSequence {
     pub fn new(
         cursor_port: OuterViewPort<dyn SingletonView<Item = ListCursor>>,
         data_port: OuterViewPort<dyn SequenceView<Item = NestedNode>>,
+        selection_port: OuterViewPort<dyn SingletonView<Item = Option<(isize, isize)>>>,
+        theme: Arc<RwLock<Theme>>,
     ) -> Arc<RwLock<Self>> {
         let out_port = ViewPort::new();
         let mut proj_helper = ProjectionHelper::new(out_port.update_hooks.clone());
         let proj = Arc::new(RwLock::new(ListSegmentSequence {
             cur_cursor: cursor_port.get_view().get(),
+            theme,
             port: out_port.clone(),
 
             cursor: proj_helper.new_singleton_arg(0, cursor_port, |s: &mut Self, _msg| {
@@ -158,6 +199,10 @@ impl ListSegmentSequence {
                     s.cast.notify(idx);
                 }
             }),
+            selection: proj_helper.new_singleton_arg(2, selection_port, |s: &mut Self, _msg| {
+                // todo: optimize
+                s.cast.notify_each(0..=s.data.len().unwrap_or(0) + 1);
+            }),
             cast: out_port.inner().get_broadcast(),
             proj_helper,
         }));
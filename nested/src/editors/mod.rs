@@ -5,5 +5,7 @@ pub mod sum;
 
 pub mod char;
 pub mod integer;
+pub mod number;
+pub mod temporal;
 pub mod typeterm;
 
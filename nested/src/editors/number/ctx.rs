@@ -0,0 +1,167 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, singleton::*}
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, MorphismTypePattern},
+        editors::{
+            integer::{PosIntEditor, digits_to_u64, radix_arg, bignum::convert_radix},
+            number::*
+        },
+        tree::NestedNode
+    },
+    std::sync::{Arc, RwLock}
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// little endian digits of `src_radix` in `dst_radix`,
+/// a zero keeps one digit
+fn convert_digits(src_radix: u32, digits: &[usize], dst_radix: u32) -> Vec<usize> {
+    let converted = convert_radix(src_radix as usize, digits, dst_radix as usize);
+    if converted.is_empty() && !digits.is_empty() {
+        vec![ 0 ]
+    } else {
+        converted
+    }
+}
+
+/// node of the source type of a morphism,
+/// whose data was already ascended to the destination type
+fn src_node(node: &NestedNode) -> Option<NestedNode> {
+    let mut src = node.clone();
+    src.data = node.data.read().unwrap().descend_single()?;
+    Some(src)
+}
+
+/// morphism adding a machine representation to the root of the data,
+/// projected from the digits of the source node
+fn machine_morphism<T: Clone + Send + Sync + 'static>(
+    value: fn(&NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<T>>>>
+) -> Arc<dyn Fn(NestedNode, TypeTerm) -> Option<NestedNode> + Send + Sync> {
+    Arc::new(move |node, _dst_type| {
+        let port = value(&src_node(&node)?)?;
+        node.data.write().unwrap().insert_leaf(
+            vec![].into_iter(),
+            port.into()
+        );
+        Some(node)
+    })
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+pub fn init_ctx(ctx: &mut Context) {
+    ctx.add_list_typename("Int".into());
+    ctx.add_list_typename("Rational".into());
+    ctx.add_list_typename("Decimal".into());
+    ctx.add_typename("f64".into());
+
+    ctx.add_node_ctor(
+        "Int", Arc::new(
+            |ctx: Arc<RwLock<Context>>, ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+                let node = IntEditor::new(ctx, radix_arg(&ty)?).into_node();
+                node.depth.0.set_view(depth.get_view());
+                Some(node)
+            }
+        )
+    );
+
+    ctx.add_node_ctor(
+        "Rational", Arc::new(
+            |ctx: Arc<RwLock<Context>>, ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+                let node = RationalEditor::new(ctx, radix_arg(&ty)?).into_node();
+                node.depth.0.set_view(depth.get_view());
+                Some(node)
+            }
+        )
+    );
+
+    ctx.add_node_ctor(
+        "Decimal", Arc::new(
+            |ctx: Arc<RwLock<Context>>, ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+                let node = DecimalEditor::new(ctx, radix_arg(&ty)?).into_node();
+                node.depth.0.set_view(depth.get_view());
+                Some(node)
+            }
+        )
+    );
+
+    // PosInt -> Int: positive sign
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("PosInt"), dst_tyid: ctx.get_typeid("Int").unwrap() },
+        Arc::new(|node, dst_type| {
+            Some(SignedEditor::new(src_node(&node)?).into_node(dst_type))
+        })
+    );
+
+    // Int -> Rational: no denominator.
+    // In another radix, the numerator is a converted copy.
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("Int"), dst_tyid: ctx.get_typeid("Rational").unwrap() },
+        Arc::new(|node, dst_type| {
+            let radix = radix_arg(&dst_type)?;
+            let src = src_node(&node)?;
+
+            // the digits may have been switched to another radix
+            // than the declared one, e.g. by a typed prefix
+            let (negative, src_radix, digits) = IntEditor::get_node_digits(&src)?;
+
+            let num =
+                if src_radix == radix {
+                    src
+                } else {
+                    let mut num = IntEditor::new(node.ctx.clone(), radix);
+                    num.set_digits(negative, &convert_digits(src_radix, &digits, radix));
+                    num.into_node()
+                };
+
+            Some(RationalEditor::from_ints(node.ctx.clone(), radix, num, None).into_node())
+        })
+    );
+
+    // Decimal -> Rational: denominator is a power of the radix
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("Decimal"), dst_tyid: ctx.get_typeid("Rational").unwrap() },
+        Arc::new(|node, dst_type| {
+            let radix = radix_arg(&dst_type)?;
+            let digits = DecimalEditor::get_node_digits(&src_node(&node)?)?;
+            let (num_digits, den_digits) = digits.to_fraction();
+
+            // digits are in the radix of the decimal
+            let mut num = IntEditor::new(node.ctx.clone(), radix);
+            num.set_digits(digits.negative, &convert_digits(digits.radix, &num_digits, radix));
+            let mut den = IntEditor::new(node.ctx.clone(), radix);
+            den.set_digits(false, &convert_digits(digits.radix, &den_digits, radix));
+
+            Some(RationalEditor::from_ints(node.ctx.clone(), radix, num.into_node(), Some(den.into_node())).into_node())
+        })
+    );
+
+    // machine types
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("PosInt"), dst_tyid: ctx.get_typeid("u64").unwrap() },
+        machine_morphism(|node| {
+            Some(PosIntEditor::get_node_digits_port(node)?.map(|digits| {
+                let (radix, digits) = digits?;
+                digits_to_u64(radix, &digits)
+            }))
+        })
+    );
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("Int"), dst_tyid: ctx.get_typeid("i64").unwrap() },
+        machine_morphism(IntEditor::get_node_value_i64_port)
+    );
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("Rational"), dst_tyid: ctx.get_typeid("f64").unwrap() },
+        machine_morphism(RationalEditor::get_node_value_f64_port)
+    );
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("Decimal"), dst_tyid: ctx.get_typeid("f64").unwrap() },
+        machine_morphism(|node| {
+            Some(DecimalEditor::get_node_digits_port(node)?
+                .map(|digits| digits.map(|digits| digits.to_f64())))
+        })
+    );
+}
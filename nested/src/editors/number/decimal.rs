@@ -0,0 +1,180 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, sequence::*, singleton::*},
+        buffer::singleton::*
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context},
+        editors::{
+            integer::{PosIntEditor, Endianness, radix_arg},
+            list::{ListEditor, PTYListController, PTYListStyle},
+            number::SignedEditor
+        },
+        tree::NestedNode,
+        utils::combine::{collect_singletons, zip_singletons}
+    },
+    std::sync::{Arc, RwLock}
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// integer and fractional digits of a decimal number
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DecimalDigits {
+    pub negative: bool,
+    pub radix: u32,
+
+    /// least significant first
+    pub int_digits: Vec<usize>,
+
+    /// most significant first, i.e. in reading order
+    pub frac_digits: Vec<usize>,
+}
+
+impl DecimalDigits {
+    /// from the radix and digits of the parts, `None` on invalid
+    /// digits or more than one `.`. A part in another radix has
+    /// no meaning as fraction.
+    fn from_parts(negative: bool, radix: u32, parts: Vec<Option<(u32, Vec<usize>)>>) -> Option<DecimalDigits> {
+        let mut parts = parts.into_iter().map(|part| match part? {
+            (part_radix, digits) if part_radix == radix => Some(digits),
+            _ => None
+        });
+
+        let (int_digits, frac_digits) = match (parts.next(), parts.next(), parts.next()) {
+            (None, _, _) => (vec![], vec![]),
+            (Some(int), None, _) => (int?, vec![]),
+            (Some(int), Some(frac), None) => (int?, frac?.into_iter().rev().collect()),
+            _ => return None
+        };
+
+        Some(DecimalDigits {
+            negative,
+            radix,
+            int_digits,
+            frac_digits
+        })
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let radix = self.radix as f64;
+        let int = self.int_digits.iter().rev().fold(0.0, |v, d| v * radix + *d as f64);
+        let frac = self.frac_digits.iter().rev().fold(0.0, |v, d| (v + *d as f64) / radix);
+        let value = int + frac;
+        if self.negative { -value } else { value }
+    }
+
+    /// `(numerator, denominator)` as little endian digits,
+    /// the denominator being a power of the radix
+    pub fn to_fraction(&self) -> (Vec<usize>, Vec<usize>) {
+        let numerator = self.frac_digits.iter().rev()
+            .chain(self.int_digits.iter())
+            .cloned()
+            .collect();
+
+        let mut denominator = vec![ 0; self.frac_digits.len() ];
+        denominator.push(1);
+
+        (numerator, denominator)
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `<Decimal radix>`: a sign, the integer digits
+/// and the fractional digits separated by `.`
+pub struct DecimalEditor {
+    node: NestedNode,
+}
+
+impl DecimalEditor {
+    pub fn new(ctx: Arc<RwLock<Context>>, radix: u32) -> Self {
+        let ctx = SignedEditor::make_ctx(&ctx);
+
+        let mut parts = Context::make_node(
+            &ctx,
+            Context::parse(&ctx, format!("<List <PosInt {} BigEndian>>", radix).as_str()),
+            SingletonBuffer::new(0).get_port()
+        ).unwrap();
+
+        // parts are created in the context of the list,
+        // their radix is that of the decimal, so the prefix
+        // is hidden and typing one does not switch the radix
+        parts.ctx.write().unwrap().add_node_ctor(
+            "PosInt", Arc::new(
+                move |ctx: Arc<RwLock<Context>>, _ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+                    let mut part = PosIntEditor::new(ctx, radix, Endianness::BigEndian);
+                    part.set_show_prefix(false);
+                    let node = part.into_node();
+                    node.depth.0.set_view(depth.get_view());
+                    Some(node)
+                }
+            )
+        );
+
+        PTYListController::for_node( &mut parts, Some('.'), None );
+        PTYListStyle::for_node( &mut parts, ("", ".", "") );
+
+        DecimalEditor {
+            node: SignedEditor::new(parts)
+                .into_node(DecimalEditor::get_type(&ctx, radix))
+        }
+    }
+
+    /// `<Decimal radix>`
+    pub fn get_type(ctx: &Arc<RwLock<Context>>, radix: u32) -> TypeTerm {
+        let ctx = ctx.read().unwrap();
+        TypeTerm::App(vec![
+            TypeTerm::TypeID(ctx.get_typeid("Decimal").unwrap()),
+            TypeTerm::Num(radix as i64)
+        ])
+    }
+
+    pub fn get_digits(&self) -> Option<DecimalDigits> {
+        DecimalEditor::get_node_digits(&self.node)
+    }
+
+    pub fn get_value_f64(&self) -> Option<f64> {
+        Some(self.get_digits()?.to_f64())
+    }
+
+    /// `None` on invalid digits or more than one `.`
+    pub fn get_node_digits(node: &NestedNode) -> Option<DecimalDigits> {
+        let sign = node.get_edit::<SignedEditor>()?;
+        let sign = sign.read().unwrap();
+
+        let parts = sign.get_inner().get_edit::<ListEditor>()?;
+        let parts = parts.read().unwrap().get_data_port().get_view()?;
+        let parts = (0 .. parts.len()?)
+            .map(|idx| PosIntEditor::get_node_digits(&parts.get(&idx)?))
+            .collect();
+
+        DecimalDigits::from_parts(sign.is_negative(), radix_arg(node.data.read().unwrap().get_type())?, parts)
+    }
+
+    /// projection of `get_node_digits`, following all edits
+    pub fn get_node_digits_port(node: &NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<DecimalDigits>>>> {
+        let radix = radix_arg(node.data.read().unwrap().get_type())?;
+        let sign = node.get_edit::<SignedEditor>()?;
+        let sign = sign.read().unwrap();
+
+        let parts = sign.get_inner().get_edit::<ListEditor>()?;
+        let parts = collect_singletons(
+            parts.read().unwrap().get_data_port().map(|part| {
+                PosIntEditor::get_node_digits_port(part)
+                    .unwrap_or_else(|| SingletonBuffer::new(None).get_port())
+            })
+        );
+
+        Some(zip_singletons(
+            sign.get_negative_port(),
+            parts,
+            move |negative, parts| DecimalDigits::from_parts(negative, radix, parts)
+        ))
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.node
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, singleton::*}
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context},
+        editors::{
+            integer::{PosIntEditor, Endianness, digits_to_u64},
+            number::SignedEditor
+        },
+        tree::NestedNode,
+        utils::combine::zip_singletons
+    },
+    std::{
+        convert::TryFrom,
+        sync::{Arc, RwLock}
+    }
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `<Int radix>`: a sign and a `<PosInt radix BigEndian>`
+pub struct IntEditor {
+    posint: PosIntEditor,
+    node: NestedNode,
+}
+
+impl IntEditor {
+    pub fn new(ctx: Arc<RwLock<Context>>, radix: u32) -> Self {
+        let ctx = SignedEditor::make_ctx(&ctx);
+        let posint = PosIntEditor::new(ctx.clone(), radix, Endianness::BigEndian);
+        let node = SignedEditor::new(posint.get_node())
+            .into_node(IntEditor::get_type(&ctx, radix));
+
+        IntEditor {
+            posint,
+            node
+        }
+    }
+
+    pub fn from_i64(ctx: Arc<RwLock<Context>>, radix: u32, value: i64) -> Self {
+        let mut edit = IntEditor::new(ctx, radix);
+        edit.set_value_i64(value);
+        edit
+    }
+
+    /// `<Int radix>`
+    pub fn get_type(ctx: &Arc<RwLock<Context>>, radix: u32) -> TypeTerm {
+        let ctx = ctx.read().unwrap();
+        TypeTerm::App(vec![
+            TypeTerm::TypeID(ctx.get_typeid("Int").unwrap()),
+            TypeTerm::Num(radix as i64)
+        ])
+    }
+
+    fn get_sign(&self) -> Arc<RwLock<SignedEditor>> {
+        self.node.get_edit::<SignedEditor>().unwrap()
+    }
+
+    pub fn set_value_i64(&mut self, value: i64) {
+        self.get_sign().write().unwrap().set_negative(value < 0);
+        self.posint.set_value_u64(value.unsigned_abs());
+    }
+
+    /// replace sign and digits, least significant first
+    pub fn set_digits(&mut self, negative: bool, digits: &[usize]) {
+        self.get_sign().write().unwrap().set_negative(negative);
+        self.posint.set_digits(digits);
+    }
+
+    /// `None` if some digit is invalid or the value exceeds `i64`
+    pub fn get_value_i64(&self) -> Option<i64> {
+        IntEditor::get_node_value_i64(&self.node)
+    }
+
+    /// sign, radix and digits of an `<Int radix>` node,
+    /// least significant first
+    pub fn get_node_digits(node: &NestedNode) -> Option<(bool, u32, Vec<usize>)> {
+        let sign = node.get_edit::<SignedEditor>()?;
+        let sign = sign.read().unwrap();
+        let (radix, digits) = PosIntEditor::get_node_digits(&sign.get_inner())?;
+        Some((sign.is_negative(), radix, digits))
+    }
+
+    /// projection of `get_node_digits`, following all edits
+    pub fn get_node_digits_port(node: &NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<(bool, u32, Vec<usize>)>>>> {
+        let sign = node.get_edit::<SignedEditor>()?;
+        let sign = sign.read().unwrap();
+        Some(zip_singletons(
+            sign.get_negative_port(),
+            PosIntEditor::get_node_digits_port(&sign.get_inner())?,
+            |negative, digits| {
+                let (radix, digits) = digits?;
+                Some((negative, radix, digits))
+            }
+        ))
+    }
+
+    pub fn get_node_value_i64(node: &NestedNode) -> Option<i64> {
+        let (negative, radix, digits) = IntEditor::get_node_digits(node)?;
+        digits_to_i64(negative, radix, &digits)
+    }
+
+    /// projection of `get_node_value_i64`, following all edits
+    pub fn get_node_value_i64_port(node: &NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<i64>>>> {
+        Some(IntEditor::get_node_digits_port(node)?.map(|digits| {
+            let (negative, radix, digits) = digits?;
+            digits_to_i64(negative, radix, &digits)
+        }))
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.node
+    }
+}
+
+/// `None` if the value exceeds `i64`
+fn digits_to_i64(negative: bool, radix: u32, digits: &[usize]) -> Option<i64> {
+    let value = digits_to_u64(radix, digits)? as i128;
+    i64::try_from(if negative { -value } else { value }).ok()
+}
@@ -0,0 +1,13 @@
+pub mod signed;
+pub mod int;
+pub mod rational;
+pub mod decimal;
+pub mod ctx;
+
+pub use {
+    signed::SignedEditor,
+    int::IntEditor,
+    rational::RationalEditor,
+    decimal::{DecimalDigits, DecimalEditor},
+    ctx::init_ctx
+};
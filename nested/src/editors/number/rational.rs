@@ -0,0 +1,147 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, sequence::*, singleton::*},
+        buffer::singleton::*
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, ReprTree},
+        editors::{
+            list::{ListEditor, PTYListController, PTYListStyle},
+            number::IntEditor
+        },
+        tree::{NestedNode, TreeNav, TreeCursor},
+        commander::ObjCommander,
+        utils::combine::collect_singletons
+    },
+    std::{
+        convert::TryFrom,
+        sync::{Arc, RwLock}
+    }
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `<Rational radix>`: numerator and denominator,
+/// both `<Int radix>`, separated by `/`
+pub struct RationalEditor {
+    node: NestedNode,
+}
+
+impl RationalEditor {
+    pub fn new(ctx: Arc<RwLock<Context>>, radix: u32) -> Self {
+        let mut node = Context::make_node(
+            &ctx,
+            Context::parse(&ctx, format!("<List <Int {}>>", radix).as_str()),
+            SingletonBuffer::new(0).get_port()
+        ).unwrap();
+
+        node.data = ReprTree::ascend(
+            &node.data.clone(),
+            RationalEditor::get_type(&ctx, radix)
+        );
+
+        PTYListController::for_node( &mut node, Some('/'), None );
+        PTYListStyle::for_node( &mut node, ("", "/", "") );
+
+        RationalEditor {
+            node
+        }
+    }
+
+    /// `numerator / denominator`
+    pub fn from_ints(ctx: Arc<RwLock<Context>>, radix: u32, numerator: NestedNode, denominator: Option<NestedNode>) -> Self {
+        let mut edit = RationalEditor::new(ctx, radix);
+
+        edit.node.goto(TreeCursor::home());
+        for n in std::iter::once(numerator).chain(denominator.into_iter()) {
+            edit.node.send_cmd_obj(
+                ReprTree::new_leaf(
+                    Context::parse(&edit.node.ctx, "NestedNode"),
+                    SingletonBuffer::new(n).get_port().into()
+                )
+            );
+        }
+        edit.node.goto(TreeCursor::none());
+
+        edit
+    }
+
+    /// `<Rational radix>`
+    pub fn get_type(ctx: &Arc<RwLock<Context>>, radix: u32) -> TypeTerm {
+        let ctx = ctx.read().unwrap();
+        TypeTerm::App(vec![
+            TypeTerm::TypeID(ctx.get_typeid("Rational").unwrap()),
+            TypeTerm::Num(radix as i64)
+        ])
+    }
+
+    /// `(numerator, denominator)` with positive denominator,
+    /// not reduced. A missing denominator counts as 1.
+    /// `None` on invalid digits, overflow, zero denominator
+    /// or more than one `/`
+    pub fn get_value_i64(&self) -> Option<(i64, i64)> {
+        RationalEditor::get_node_value_i64(&self.node)
+    }
+
+    pub fn get_value_f64(&self) -> Option<f64> {
+        RationalEditor::get_node_value_f64(&self.node)
+    }
+
+    pub fn get_node_value_i64(node: &NestedNode) -> Option<(i64, i64)> {
+        let list = node.get_edit::<ListEditor>()?;
+        let items = list.read().unwrap().get_data_port().get_view()?;
+
+        let values = (0 .. items.len()?)
+            .map(|idx| IntEditor::get_node_value_i64(&items.get(&idx)?))
+            .collect::<Vec<_>>();
+        to_fraction(&values)
+    }
+
+    /// projection of `get_node_value_i64`, following all edits
+    pub fn get_node_value_i64_port(node: &NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<(i64, i64)>>>> {
+        let list = node.get_edit::<ListEditor>()?;
+        let values = collect_singletons(
+            list.read().unwrap().get_data_port().map(|item| {
+                IntEditor::get_node_value_i64_port(item)
+                    .unwrap_or_else(|| SingletonBuffer::new(None).get_port())
+            })
+        );
+        Some(values.map(|values| to_fraction(&values)))
+    }
+
+    pub fn get_node_value_f64(node: &NestedNode) -> Option<f64> {
+        let (num, den) = RationalEditor::get_node_value_i64(node)?;
+        Some(num as f64 / den as f64)
+    }
+
+    /// projection of `get_node_value_f64`, following all edits
+    pub fn get_node_value_f64_port(node: &NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<f64>>>> {
+        Some(RationalEditor::get_node_value_i64_port(node)?
+            .map(|value| {
+                let (num, den) = value?;
+                Some(num as f64 / den as f64)
+            }))
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.node
+    }
+}
+
+/// numerator and optional denominator, made positive
+fn to_fraction(values: &[Option<i64>]) -> Option<(i64, i64)> {
+    let (num, den) = match values {
+        [num] => ((*num)?, 1),
+        [num, den] => ((*num)?, (*den)?),
+        _ => return None
+    };
+
+    if den == 0 {
+        None
+    } else if den < 0 {
+        Some((i64::try_from(-(num as i128)).ok()?, i64::try_from(-(den as i128)).ok()?))
+    } else {
+        Some((num, den))
+    }
+}
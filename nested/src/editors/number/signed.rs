@@ -0,0 +1,134 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, singleton::*},
+        buffer::{singleton::*, index_hashmap::*}
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, ReprTree},
+        terminal::make_label,
+        tree::{NestedNode, TreeNavResult, TreeCursor, HitTest},
+        commander::ObjCommander
+    },
+    std::sync::{Arc, RwLock},
+    cgmath::Point2
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Puts a sign in front of some unsigned number node.
+/// `-` toggles the sign, `+` makes the number positive,
+/// everything else is handled by the inner node.
+pub struct SignedEditor {
+    negative: SingletonBuffer<bool>,
+    inner: NestedNode,
+}
+
+impl SignedEditor {
+    /// context with the sign characters as meta chars,
+    /// to be used for the inner node
+    pub fn make_ctx(ctx: &Arc<RwLock<Context>>) -> Arc<RwLock<Context>> {
+        let ctx = Arc::new(RwLock::new(Context::with_parent(Some(ctx.clone()))));
+        ctx.write().unwrap().meta_chars.push('-');
+        ctx.write().unwrap().meta_chars.push('+');
+        ctx
+    }
+
+    pub fn new(inner: NestedNode) -> Self {
+        SignedEditor {
+            negative: SingletonBuffer::new(false),
+            inner,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative.get()
+    }
+
+    pub fn set_negative(&mut self, negative: bool) {
+        self.negative.set(negative);
+    }
+
+    pub fn get_negative_port(&self) -> OuterViewPort<dyn SingletonView<Item = bool>> {
+        self.negative.get_port()
+    }
+
+    pub fn get_inner(&self) -> NestedNode {
+        self.inner.clone()
+    }
+
+    /// node of type `typ`, sharing navigation and
+    /// diagnostics with the inner node
+    pub fn into_node(self, typ: TypeTerm) -> NestedNode {
+        let negative = self.negative.clone();
+        let inner = self.inner.clone();
+        let editor = Arc::new(RwLock::new(self));
+
+        let mut grid = IndexBuffer::new();
+        grid.insert_iter(vec![
+            (Point2::new(0, 0), negative.get_port()
+                .map(|n| make_label(if n { "-" } else { "" }))
+                .to_grid()
+                .flatten()),
+            (Point2::new(1, 0), inner.get_view()),
+        ]);
+
+        // the inner node keeps its own editor, commander and hit test
+        let mut node = inner.clone();
+        node.data = ReprTree::ascend(&inner.data, typ);
+        node.view = Some(grid.get_port().flatten());
+        node.editor = SingletonBuffer::new(None);
+        node.cmd = SingletonBuffer::new(None);
+        node.hit = SingletonBuffer::new(Some(Arc::new(RwLock::new(
+            SignedHitTest {
+                negative,
+                inner: inner.hit.clone()
+            }
+        ))));
+
+        node.set_editor(editor.clone())
+            .set_cmd(editor)
+    }
+}
+
+impl ObjCommander for SignedEditor {
+    fn send_cmd_obj(&mut self, cmd_obj: Arc<RwLock<ReprTree>>) -> TreeNavResult {
+        let co = cmd_obj.read().unwrap();
+
+        if let Some(c) = co.get_view::<dyn SingletonView<Item = char>>() {
+            match c.get() {
+                '-' => {
+                    self.negative.set(!self.negative.get());
+                    return TreeNavResult::Continue;
+                }
+                '+' => {
+                    self.negative.set(false);
+                    return TreeNavResult::Continue;
+                }
+                _ => {}
+            }
+        }
+
+        drop(co);
+        self.inner.send_cmd_obj(cmd_obj)
+    }
+}
+
+/// shifts positions by the width of the sign
+struct SignedHitTest {
+    negative: SingletonBuffer<bool>,
+    inner: SingletonBuffer<Option<Arc<RwLock<dyn HitTest + Send + Sync>>>>,
+}
+
+impl HitTest for SignedHitTest {
+    fn hit(&self, pos: &Point2<i16>) -> Option<TreeCursor> {
+        let sign_width = if self.negative.get() { 1 } else { 0 };
+        if pos.x < sign_width {
+            Some(TreeCursor::home())
+        } else {
+            self.inner.get()?
+                .read().unwrap()
+                .hit(&Point2::new(pos.x - sign_width, pos.y))
+        }
+    }
+}
@@ -24,7 +24,6 @@ use {
         },
         tree::{TreeNav, TreeNavResult},
         diagnostics::{Diagnostics},
-        terminal::{TerminalStyle},
         tree::NestedNode
     },
     cgmath::{Point2},
@@ -36,7 +35,7 @@ use {
 pub struct ProductEditor {
     msg_buf: VecBuffer<Option<OuterViewPort<dyn SequenceView<Item = crate::diagnostics::Message>>>>,
     msg_port:  OuterViewPort<dyn SequenceView<Item = crate::diagnostics::Message>>,
-    segments: IndexBuffer<Point2<i16>, ProductEditorSegment>,
+    pub(super) segments: IndexBuffer<Point2<i16>, ProductEditorSegment>,
     pub(super) n_indices: Vec<Point2<i16>>,
 
     pub(super) ctx: Arc<RwLock<Context>>,    
@@ -91,7 +90,7 @@ impl ProductEditor {
         self.n_indices.push(pos);
 
         let mut b = VecBuffer::new();
-        b.push(crate::diagnostics::make_todo(crate::terminal::make_label(&format!("complete {}", self.ctx.read().unwrap().type_term_to_str(n)))));
+        b.push(crate::diagnostics::make_todo(&self.ctx, crate::terminal::make_label(&format!("complete {}", self.ctx.read().unwrap().type_term_to_str(n)))));
         self.msg_buf.push(Some(b.get_port().to_sequence()));
         self
     }
@@ -158,16 +157,18 @@ impl ProductEditor {
                 self.msg_buf.update(idx as usize, Some(e.get_msg_port()));
             } else {
                 let mut b = VecBuffer::new();
-                b.push(crate::diagnostics::make_todo(crate::terminal::make_label(&format!("complete {}", self.ctx.read().unwrap().type_term_to_str(&t)))));
+                b.push(crate::diagnostics::make_todo(&self.ctx, crate::terminal::make_label(&format!("complete {}", self.ctx.read().unwrap().type_term_to_str(&t)))));
 
                 self.msg_buf.update(idx as usize, Some(b.get_port().to_sequence()));
 
                 if cur.tree_addr.len() > 0 {
                     if cur.tree_addr[0] == idx {
+                        let theme = self.ctx.read().unwrap().get_theme();
                         self.msg_buf.update(idx as usize, Some(b.get_port().to_sequence().map(
-                            |msg| {
+                            move |msg| {
                                 let mut msg = msg.clone();
-                                msg.port = msg.port.map_item(|_p,a| a.add_style_back(TerminalStyle::bg_color((40,40,40))));
+                                let theme = theme.clone();
+                                msg.port = msg.port.map_item(move |_p,a| a.add_style_back(theme.read().unwrap().message_active));
                                 msg
                             }
                         )));
@@ -187,6 +188,23 @@ impl ProductEditor {
         }
     }
 
+    /// node of type `typ`, with mouse clicks
+    /// mapped to segments by the hit test of the product
+    pub fn into_node(self, typ: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>) -> NestedNode {
+        let ctx = self.ctx.clone();
+        let view = self.get_term_view();
+        let diag = self.get_msg_port();
+        let editor = Arc::new(RwLock::new(self));
+
+        NestedNode::new(ctx, ReprTree::new_arc(typ), depth)
+            .set_view(view)
+            .set_diag(diag)
+            .set_editor(editor.clone())
+            .set_nav(editor.clone())
+            .set_cmd(editor.clone())
+            .set_hit(editor)
+    }
+
     pub fn get_term_view(&self) -> OuterViewPort<dyn TerminalView> {
         let ctx = self.ctx.clone();
         self.segments
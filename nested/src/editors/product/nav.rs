@@ -1,13 +1,15 @@
 use {
+    r3vi::view::{grid::*, index::*},
     crate::{
         type_system::Context,
-        tree::{TreeNav, TreeNavResult, TreeCursor},
+        terminal::area_extent,
+        tree::{TreeNav, TreeNavResult, TreeCursor, HitTest},
         editors::{
             list::ListCursorMode,
             product::{segment::ProductEditorSegment, ProductEditor},
         }
     },
-    cgmath::{Vector2},
+    cgmath::{Point2, Vector2},
     std::{ops::{DerefMut}},
 };
 
@@ -245,3 +247,70 @@ impl TreeNav for ProductEditor {
     }
 }
 
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+impl HitTest for ProductEditor {
+    fn hit(&self, pos: &Point2<i16>) -> Option<TreeCursor> {
+        let segments = self.segments.get_port().get_view();
+        let keys = match segments.area() {
+            IndexArea::Set(keys) => keys,
+            IndexArea::Range(r) => GridWindowIterator::from(r).collect(),
+            _ => vec![]
+        };
+
+        // segments are laid out as grid,
+        // each column is as wide as its widest segment
+        // and each row as high as its highest segment
+        let mut col_widths: Vec<i16> = Vec::new();
+        let mut row_heights: Vec<i16> = Vec::new();
+
+        for key in keys.iter() {
+            if key.x < 0 || key.y < 0 {
+                continue;
+            }
+            if let Some(segment) = segments.get(key) {
+                let extent = area_extent(&segment.get_view(self.ctx.clone()).get_view().area());
+                let (x, y) = (key.x as usize, key.y as usize);
+                if col_widths.len() <= x { col_widths.resize(x + 1, 0); }
+                if row_heights.len() <= y { row_heights.resize(y + 1, 0); }
+                col_widths[x] = std::cmp::max(col_widths[x], extent.x);
+                row_heights[y] = std::cmp::max(row_heights[y], extent.y);
+            }
+        }
+
+        let locate = |sizes: &Vec<i16>, p: i16| -> Option<(i16, i16)> {
+            let mut offset = 0;
+            for (i, s) in sizes.iter().enumerate() {
+                if p >= offset && p < offset + s {
+                    return Some((i as i16, p - offset));
+                }
+                offset += s;
+            }
+            None
+        };
+
+        let (col, x) = locate(&col_widths, pos.x)?;
+        let (row, y) = locate(&row_heights, pos.y)?;
+        let key = Point2::new(col, row);
+
+        let idx = self.n_indices.iter().position(|p| *p == key)? as isize;
+        match segments.get(&key)? {
+            ProductEditorSegment::N{ editor: Some(e), .. } => {
+                let mut c = e.hit(&Point2::new(x, y))
+                    .unwrap_or(TreeCursor {
+                        leaf_mode: ListCursorMode::Select,
+                        tree_addr: vec![]
+                    });
+                c.tree_addr.insert(0, idx);
+                Some(c)
+            }
+            _ => {
+                Some(TreeCursor {
+                    leaf_mode: ListCursorMode::Select,
+                    tree_addr: vec![ idx ]
+                })
+            }
+        }
+    }
+}
@@ -8,13 +8,13 @@ use {
     crate::{
         type_system::{Context},
         terminal::{
-            TerminalStyle, TerminalView,
+            TerminalStyle, TerminalView, TerminalCursorShape,
             make_label
         },
-        utils::color::{bg_style_from_depth, fg_style_from_depth},
         tree::{NestedNode, TreeNav}
     },
     std::{sync::{Arc, RwLock}},
+    cgmath::Point2,
 };
 
 #[derive(Clone)]
@@ -31,13 +31,16 @@ pub enum ProductEditorSegment {
 
 impl ProductEditorSegment {
     pub fn get_view(&self, ctx: Arc<RwLock<Context>>) -> OuterViewPort<dyn TerminalView> {
+        let theme = ctx.read().unwrap().get_theme();
         match self {
             ProductEditorSegment::T(t, depth) =>
                 make_label(t.as_str())
                 .map_item({
                     let depth = *depth;
-                    move |_i, x|
-                    x.add_style_back(fg_style_from_depth(depth)).add_style_back(TerminalStyle::italic(true))
+                    move |_i, x| {
+                        let theme = theme.read().unwrap();
+                        x.add_style_back(theme.fg_from_depth(depth)).add_style_back(theme.keyword)
+                    }
                 }
             ),
 
@@ -48,7 +51,7 @@ impl ProductEditorSegment {
                     let d = *ed_depth;
                     let cur_dist = *cur_dist;
 
-                    move |_i, x| {
+                    move |i, x| {
                         let c = e.get_cursor();
                         let cur_depth = c.tree_addr.len();
                         let select =
@@ -58,10 +61,17 @@ impl ProductEditorSegment {
                                 usize::MAX
                             };
 
-                        return x
-                            .add_style_back(bg_style_from_depth(select))
+                        let theme = theme.read().unwrap();
+                        let x = x
+                            .add_style_back(theme.bg_from_depth(select))
                             .add_style_back(TerminalStyle::bold(select==1))
-                            .add_style_back(fg_style_from_depth(d));
+                            .add_style_back(theme.fg_from_depth(d));
+
+                        if select == 0 && *i == Point2::new(0, 0) {
+                            x.with_cursor(TerminalCursorShape::Block)
+                        } else {
+                            x
+                        }
                     }
                 }),
 
@@ -72,10 +82,18 @@ impl ProductEditorSegment {
                     let _ed_depth = *ed_depth;
                     let cur_dist = *cur_dist;
 
-                    move |_i, x|
-                    x.add_style_back(TerminalStyle::fg_color((215,140,95)))
-                        .add_style_back(bg_style_from_depth(if cur_dist == 0 { 0 } else { usize::MAX }))
-                        .add_style_back(TerminalStyle::bold(cur_dist == 0))
+                    move |i, x| {
+                        let theme = theme.read().unwrap();
+                        let x = x.add_style_back(theme.placeholder)
+                            .add_style_back(theme.bg_from_depth(if cur_dist == 0 { 0 } else { usize::MAX }))
+                            .add_style_back(TerminalStyle::bold(cur_dist == 0));
+
+                        if cur_dist == 0 && *i == Point2::new(0, 0) {
+                            x.with_cursor(TerminalCursorShape::Block)
+                        } else {
+                            x
+                        }
+                    }
                 })
         }        
     }
@@ -0,0 +1,239 @@
+/// proleptic gregorian calendar
+pub fn is_leap_year(year: u64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// days since 1970-01-01
+pub fn days_from_civil(year: u64, month: u64, day: u64) -> i64 {
+    let (year, month, day) = (year as i64, month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// year, month and day of the day `days` after 1970-01-01
+pub fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// checks those parts of a date which are given
+pub fn check_date(year: Option<u64>, month: Option<u64>, day: Option<u64>) -> Result<(), String> {
+    if let Some(year) = year {
+        if year > 9999 {
+            return Err(format!("year {} has more than four digits", year));
+        }
+    }
+    if let Some(month) = month {
+        if month < 1 || month > 12 {
+            return Err(format!("invalid month {}", month));
+        }
+    }
+    if let Some(day) = day {
+        // without a year, february may be in a leap year
+        let max_day = match month {
+            Some(month) => days_in_month(year.unwrap_or(2000), month),
+            None => 31
+        };
+        if day < 1 || day > max_day {
+            return Err(format!("invalid day {}, the month has {} days", day, max_day));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_time(hour: u64, minute: u64, second: u64) -> Result<(), String> {
+    if hour > 23 {
+        Err(format!("invalid hour {}", hour))
+    } else if minute > 59 {
+        Err(format!("invalid minute {}", minute))
+    } else if second > 59 {
+        Err(format!("invalid second {}", second))
+    } else {
+        Ok(())
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `9999-12-31T23:59:59Z`, the last point in time with a four digit year
+pub const MAX_UNIX_SECONDS: u64 = 253402300799;
+
+/// Point in time (UTC), to the second
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct DateTime {
+    pub year: u64,
+    pub month: u64,
+    pub day: u64,
+    pub hour: u64,
+    pub minute: u64,
+    pub second: u64,
+}
+
+impl DateTime {
+    /// Beyond `MAX_UNIX_SECONDS` the year has five or more digits,
+    /// `to_iso8601` writes it nevertheless, but `parse_iso8601` rejects it.
+    pub fn from_unix_seconds(seconds: u64) -> Self {
+        let (year, month, day) = civil_from_days(seconds / 86400);
+        let time = seconds % 86400;
+        DateTime {
+            year, month, day,
+            hour: time / 3600,
+            minute: time / 60 % 60,
+            second: time % 60
+        }
+    }
+
+    /// `None` before 1970-01-01T00:00:00Z
+    pub fn to_unix_seconds(&self) -> Option<u64> {
+        let days = days_from_civil(self.year, self.month, self.day);
+        if days < 0 {
+            None
+        } else {
+            Some(days as u64 * 86400 + self.hour * 3600 + self.minute * 60 + self.second)
+        }
+    }
+
+    /// `YYYY-MM-DDThh:mm:ssZ`
+    pub fn to_iso8601(&self) -> String {
+        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                self.year, self.month, self.day,
+                self.hour, self.minute, self.second)
+    }
+
+    /// `YYYY-MM-DD`, optionally followed by `Thh:mm` or `Thh:mm:ss`
+    /// (or a space instead of `T`) and `Z`.
+    /// All fields have their full width, `Z` needs a time of day.
+    pub fn parse_iso8601(text: &str) -> Result<Self, String> {
+        let (text, utc) = match text.strip_suffix('Z') {
+            Some(text) => (text, true),
+            None => (text, false)
+        };
+        let (date, time) = match text.find(|c| c == 'T' || c == ' ') {
+            Some(i) => (&text[..i], Some(&text[i + 1..])),
+            None => (text, None)
+        };
+        if utc && time.is_none() {
+            return Err("expected a time of day before 'Z'".into());
+        }
+
+        let (year, month, day) = match parse_fields(date, '-', &[4, 2, 2])?.as_slice() {
+            [year, month, day] => (*year, *month, *day),
+            _ => return Err("expected a date like 1970-01-01".into())
+        };
+        check_date(Some(year), Some(month), Some(day))?;
+
+        let (hour, minute, second) = match time.map(|t| parse_fields(t, ':', &[2, 2, 2])).transpose()?.as_deref() {
+            None => (0, 0, 0),
+            Some([hour, minute]) => (*hour, *minute, 0),
+            Some([hour, minute, second]) => (*hour, *minute, *second),
+            Some(_) => return Err("expected a time like 00:00:00".into())
+        };
+        check_time(hour, minute, second)?;
+
+        Ok(DateTime { year, month, day, hour, minute, second })
+    }
+}
+
+/// decimal numbers separated by `sep`, the first
+/// ones with exactly as many digits as given in `widths`
+fn parse_fields(text: &str, sep: char, widths: &[usize]) -> Result<Vec<u64>, String> {
+    text.split(sep)
+        .enumerate()
+        .map(|(i, field)|
+            if field.is_empty() || !field.chars().all(|c| c.is_ascii_digit()) {
+                Err(format!("invalid number '{}'", field))
+            } else if widths.get(i).map(|width| field.len() != *width).unwrap_or(false) {
+                Err(format!("expected {} digits in '{}'", widths[i], field))
+            } else {
+                field.parse::<u64>().map_err(|_| format!("number '{}' is too large", field))
+            }
+        )
+        .collect()
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> DateTime {
+        DateTime::parse_iso8601(text).unwrap()
+    }
+
+    #[test]
+    fn epoch() {
+        assert_eq!(parse("1970-01-01").to_unix_seconds(), Some(0));
+        assert_eq!(DateTime::from_unix_seconds(0).to_iso8601(), "1970-01-01T00:00:00Z");
+        assert_eq!(parse("1969-12-31T23:59:59Z").to_unix_seconds(), None);
+    }
+
+    #[test]
+    fn leap_days() {
+        assert_eq!(parse("2000-02-29").to_unix_seconds(), Some(951782400));
+        assert_eq!(DateTime::from_unix_seconds(951782400 + 86399).to_iso8601(), "2000-02-29T23:59:59Z");
+
+        // divisible by 100, but not by 400
+        assert!(DateTime::parse_iso8601("1900-02-29").is_err());
+        assert!(check_date(Some(1900), Some(2), Some(29)).is_err());
+        assert!(check_date(None, Some(2), Some(29)).is_ok());
+    }
+
+    #[test]
+    fn last_four_digit_year() {
+        assert_eq!(parse("9999-12-31").to_unix_seconds(), Some(253402214400));
+        assert_eq!(parse("9999-12-31T23:59:59Z").to_unix_seconds(), Some(MAX_UNIX_SECONDS));
+        assert_eq!(DateTime::from_unix_seconds(MAX_UNIX_SECONDS).to_iso8601(), "9999-12-31T23:59:59Z");
+
+        let beyond = DateTime::from_unix_seconds(MAX_UNIX_SECONDS + 1);
+        assert_eq!(beyond.to_iso8601(), "10000-01-01T00:00:00Z");
+        assert!(DateTime::parse_iso8601(&beyond.to_iso8601()).is_err());
+    }
+
+    #[test]
+    fn full_width_fields() {
+        assert_eq!(parse("1970-01-01T00:00").to_unix_seconds(), Some(0));
+        assert_eq!(parse("1970-01-01 00:00:00Z").to_unix_seconds(), Some(0));
+
+        for text in ["1970-1-1", "1970-01-1", "70-01-01", "01970-01-01", "1970-01-01T0:00", "1970-01-01T00:00:0"].iter() {
+            assert!(DateTime::parse_iso8601(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn utc_needs_a_time() {
+        assert!(DateTime::parse_iso8601("1970-01-01Z").is_err());
+        assert!(DateTime::parse_iso8601("1970-01-01T00:00Z").is_ok());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut seconds = 0;
+        while seconds <= MAX_UNIX_SECONDS {
+            let datetime = DateTime::from_unix_seconds(seconds);
+            assert_eq!(parse(&datetime.to_iso8601()), datetime);
+            assert_eq!(datetime.to_unix_seconds(), Some(seconds));
+            seconds += 86400 * 13 + 3671;
+        }
+    }
+}
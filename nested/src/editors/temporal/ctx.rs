@@ -0,0 +1,123 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, singleton::*, sequence::*},
+        buffer::vec::*
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, MorphismTypePattern},
+        editors::{
+            integer::{PosIntEditor, Endianness, u64_to_digits},
+            temporal::*
+        },
+        terminal::make_label,
+        tree::NestedNode,
+        utils::combine::vec_items
+    },
+    std::sync::{Arc, RwLock}
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+fn node_ctor(
+    mk_node: fn(Arc<RwLock<Context>>) -> NestedNode
+) -> Arc<dyn Fn(Arc<RwLock<Context>>, TypeTerm, OuterViewPort<dyn SingletonView<Item = usize>>) -> Option<NestedNode> + Send + Sync> {
+    Arc::new(move |ctx: Arc<RwLock<Context>>, _ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+        let node = mk_node(ctx);
+        node.depth.0.set_view(depth.get_view());
+        Some(node)
+    })
+}
+
+pub fn init_ctx(ctx: &mut Context) {
+    ctx.add_node_ctor("Date", node_ctor(|ctx| DateEditor::new(ctx).into_node()));
+    ctx.add_node_ctor("Duration", node_ctor(|ctx| DurationEditor::new(ctx).into_node()));
+    ctx.add_node_ctor("ISO-8601", node_ctor(|ctx| Iso8601Editor::new(ctx).into_node()));
+
+    let unix_epoch = TypeTerm::TypeID(ctx.get_typeid("UnixEpoch").unwrap());
+    ctx.add_node_ctor(
+        "TimeSince", Arc::new(
+            move |ctx: Arc<RwLock<Context>>, ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+                match ty {
+                    TypeTerm::App(args) if args.get(1) == Some(&unix_epoch) => {
+                        let node = TimestampEditor::new(ctx).into_node();
+                        node.depth.0.set_view(depth.get_view());
+                        Some(node)
+                    }
+                    _ => None
+                }
+            }
+        )
+    );
+
+    // ISO-8601 -> <TimeSince UnixEpoch>:
+    // seconds since the epoch, projected from the text
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("ISO-8601"), dst_tyid: ctx.get_typeid("TimeSince").unwrap() },
+        Arc::new(|mut node, _dst_type| {
+            let ctx = node.ctx.clone();
+            let datetime = Iso8601Editor::get_node_text_port(&node)?
+                .map(|text| DateTime::parse_iso8601(&text?).ok());
+
+            let value = datetime.map(|datetime| datetime?.to_unix_seconds());
+            {
+                let mut data = node.data.write().unwrap();
+                data.insert_leaf(seconds_ladder(&ctx).into_iter(), value.clone().into());
+                data.insert_leaf(
+                    vec![
+                        seconds_ladder(&ctx)[0].clone(),
+                        PosIntEditor::get_type(&ctx, 10, Endianness::BigEndian),
+                        Context::parse(&ctx, "Digits")
+                    ].into_iter(),
+                    value.map(|secs| Some((10, u64_to_digits(10, secs?)))).into()
+                );
+            }
+
+            let before_epoch = datetime
+                .map(move |datetime| {
+                    let mut msg = VecBuffer::new();
+                    if datetime.map(|d| d.to_unix_seconds().is_none()).unwrap_or(false) {
+                        msg.push(crate::diagnostics::make_error(&ctx, make_label("date before the unix epoch")));
+                    }
+                    msg.get_port().to_sequence()
+                })
+                .to_sequence()
+                .flatten();
+
+            node.diag = Some(
+                VecBuffer::with_data(vec![
+                    node.get_diag(),
+                    before_epoch
+                ])
+                    .get_port()
+                    .to_sequence()
+                    .flatten()
+            );
+
+            Some(node)
+        })
+    );
+
+    // <TimeSince UnixEpoch> -> ISO-8601:
+    // text of the point in time, projected from the seconds,
+    // empty beyond the year 9999, which parse_iso8601 rejects
+    ctx.add_morphism(
+        MorphismTypePattern { src_tyid: ctx.get_typeid("TimeSince"), dst_tyid: ctx.get_typeid("ISO-8601").unwrap() },
+        Arc::new(|node, _dst_type| {
+            let src = node.data.read().unwrap().descend_single()?;
+            let text = get_seconds_port(&src, &node.ctx)?
+                .map(|secs| match secs.filter(|s| *s <= MAX_UNIX_SECONDS) {
+                    Some(secs) => DateTime::from_unix_seconds(secs).to_iso8601().chars().collect(),
+                    None => vec![]
+                });
+
+            // only the characters that changed are notified
+            node.data.write().unwrap().insert_leaf(
+                vec![ Context::parse(&node.ctx, "<List Char>") ].into_iter(),
+                vec_items(text).into()
+            );
+
+            Some(node)
+        })
+    );
+}
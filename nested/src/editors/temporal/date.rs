@@ -0,0 +1,78 @@
+use {
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, ReprTree},
+        editors::temporal::{
+            calendar::check_date,
+            fields::{make_fields_node, get_node_fields, set_node_fields}
+        },
+        terminal::make_label,
+        tree::{NestedNode, WatchController}
+    },
+    std::sync::{Arc, RwLock}
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `Date`: year, month and day separated by `-`.
+/// Months and days out of range are reported while typing.
+pub struct DateEditor {
+    node: NestedNode,
+}
+
+impl DateEditor {
+    pub fn new(ctx: Arc<RwLock<Context>>) -> Self {
+        let mut node = make_fields_node(&ctx, '-');
+        node.data = ReprTree::ascend(&node.data.clone(), DateEditor::get_type(&ctx));
+
+        WatchController::for_node(&mut node, |node, msg| {
+            if let Err(err) = DateEditor::check_node(node) {
+                msg.push(crate::diagnostics::make_error(&node.ctx, make_label(&err)));
+            }
+        });
+
+        DateEditor {
+            node
+        }
+    }
+
+    pub fn from_ymd(ctx: Arc<RwLock<Context>>, year: u64, month: u64, day: u64) -> Self {
+        let mut edit = DateEditor::new(ctx);
+        set_node_fields(&mut edit.node, &[year, month, day]);
+        edit
+    }
+
+    pub fn get_type(ctx: &Arc<RwLock<Context>>) -> TypeTerm {
+        TypeTerm::TypeID(ctx.read().unwrap().get_typeid("Date").unwrap())
+    }
+
+    /// `None` if the date is incomplete or invalid
+    pub fn get_ymd(&self) -> Option<(u64, u64, u64)> {
+        DateEditor::get_node_ymd(&self.node)
+    }
+
+    pub fn get_node_ymd(node: &NestedNode) -> Option<(u64, u64, u64)> {
+        match get_node_fields(node)?.as_slice() {
+            [Some(year), Some(month), Some(day)] => {
+                check_date(Some(*year), Some(*month), Some(*day)).ok()?;
+                Some((*year, *month, *day))
+            }
+            _ => None
+        }
+    }
+
+    /// an incomplete date is fine as long as its fields are in range
+    fn check_node(node: &NestedNode) -> Result<(), String> {
+        let fields = get_node_fields(node).unwrap_or_default();
+        if fields.len() > 3 {
+            return Err("a date has three fields: year-month-day".into());
+        }
+
+        let field = |i: usize| fields.get(i).cloned().flatten();
+        check_date(field(0), field(1), field(2))
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.node
+    }
+}
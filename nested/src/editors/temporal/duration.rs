@@ -0,0 +1,112 @@
+use {
+    r3vi::{
+        view::{singleton::*},
+        buffer::singleton::*
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, ReprTree},
+        editors::temporal::{
+            fields::{make_fields_node, get_node_fields, set_node_fields},
+            seconds_ladder
+        },
+        terminal::make_label,
+        tree::{NestedNode, WatchController}
+    },
+    std::sync::{Arc, RwLock}
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `Duration`: `[[hours:]minutes:]seconds`.
+/// The leading field is unbounded, all others must be below 60.
+/// The total number of seconds is kept in `Duration ~ Seconds ~ ℕ`.
+pub struct DurationEditor {
+    node: NestedNode,
+}
+
+impl DurationEditor {
+    pub fn new(ctx: Arc<RwLock<Context>>) -> Self {
+        let mut node = make_fields_node(&ctx, ':');
+        node.data = ReprTree::ascend(&node.data.clone(), DurationEditor::get_type(&ctx));
+
+        let mut seconds: SingletonBuffer<Option<u64>> = SingletonBuffer::new(None);
+        node.data.write().unwrap().insert_leaf(
+            seconds_ladder(&ctx).into_iter(),
+            seconds.get_port().into()
+        );
+
+        WatchController::for_node(&mut node, move |node, msg| {
+            match DurationEditor::get_fields_seconds(node) {
+                Ok(total) => seconds.set(total),
+                Err(err) => {
+                    seconds.set(None);
+                    msg.push(crate::diagnostics::make_error(&node.ctx, make_label(&err)));
+                }
+            }
+        });
+
+        DurationEditor {
+            node
+        }
+    }
+
+    pub fn from_seconds(ctx: Arc<RwLock<Context>>, seconds: u64) -> Self {
+        let mut edit = DurationEditor::new(ctx);
+        let fields =
+            if seconds >= 3600 {
+                vec![ seconds / 3600, seconds / 60 % 60, seconds % 60 ]
+            } else if seconds >= 60 {
+                vec![ seconds / 60, seconds % 60 ]
+            } else {
+                vec![ seconds ]
+            };
+        set_node_fields(&mut edit.node, &fields);
+        edit
+    }
+
+    pub fn get_type(ctx: &Arc<RwLock<Context>>) -> TypeTerm {
+        TypeTerm::TypeID(ctx.read().unwrap().get_typeid("Duration").unwrap())
+    }
+
+    /// `None` if the duration is incomplete or invalid
+    pub fn get_seconds(&self) -> Option<u64> {
+        DurationEditor::get_node_seconds(&self.node)
+    }
+
+    pub fn get_node_seconds(node: &NestedNode) -> Option<u64> {
+        ReprTree::descend_ladder(&node.data, seconds_ladder(&node.ctx).into_iter())?
+            .read().unwrap()
+            .get_view::<dyn SingletonView<Item = Option<u64>>>()?
+            .get()
+    }
+
+    /// total seconds, `None` while some field is empty or invalid
+    fn get_fields_seconds(node: &NestedNode) -> Result<Option<u64>, String> {
+        let fields = get_node_fields(node).unwrap_or_default();
+        if fields.len() > 3 {
+            return Err("a duration has at most three fields: hours:minutes:seconds".into());
+        }
+
+        let names = ["seconds", "minutes"];
+        for (i, field) in fields.iter().rev().enumerate().take(fields.len().saturating_sub(1)) {
+            if let Some(value) = field {
+                if *value >= 60 {
+                    return Err(format!("{} must be less than 60", names[i]));
+                }
+            }
+        }
+
+        Ok(
+            if fields.is_empty() {
+                None
+            } else {
+                fields.iter().try_fold(0u64, |total, field| total.checked_mul(60)?.checked_add((*field)?))
+            }
+        )
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.node
+    }
+}
@@ -0,0 +1,88 @@
+use {
+    r3vi::{
+        view::{sequence::*, singleton::*, OuterViewPort},
+        buffer::singleton::*
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, ReprTree},
+        editors::{
+            list::{ListCmd, ListEditor, PTYListController, PTYListStyle},
+            integer::{PosIntEditor, Endianness, digits_to_u64}
+        },
+        tree::{NestedNode, TreeNav, TreeCursor},
+        commander::ObjCommander
+    },
+    std::sync::{Arc, RwLock}
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `<List <PosInt 10 BigEndian>>` of numbers separated by `sep`,
+/// like the parts of a date or a time of day.
+/// The radix of the fields is implied, so their prefix is hidden.
+pub fn make_fields_node(ctx: &Arc<RwLock<Context>>, sep: char) -> NestedNode {
+    let mut node = Context::make_node(
+        ctx,
+        Context::parse(ctx, "<List <PosInt 10 BigEndian>>"),
+        SingletonBuffer::new(0).get_port()
+    ).unwrap();
+
+    // fields are created in the context of the list
+    node.ctx.write().unwrap().add_node_ctor(
+        "PosInt", Arc::new(
+            |ctx: Arc<RwLock<Context>>, _ty: TypeTerm, depth: OuterViewPort<dyn SingletonView<Item = usize>>| {
+                let node = make_field(ctx).into_node();
+                node.depth.0.set_view(depth.get_view());
+                Some(node)
+            }
+        )
+    );
+
+    PTYListController::for_node( &mut node, Some(sep), None );
+    PTYListStyle::for_node( &mut node, ("", &sep.to_string(), "") );
+
+    node
+}
+
+fn make_field(ctx: Arc<RwLock<Context>>) -> PosIntEditor {
+    let mut field = PosIntEditor::new(ctx, 10, Endianness::BigEndian);
+    field.set_show_prefix(false);
+    field
+}
+
+/// value of each field, `None` for empty or invalid ones
+pub fn get_node_fields(node: &NestedNode) -> Option<Vec<Option<u64>>> {
+    let list = node.get_edit::<ListEditor>()?;
+    let fields = list.read().unwrap().get_data_port().get_view()?;
+    Some(
+        fields.iter()
+            .map(|field| {
+                let (radix, digits) = PosIntEditor::get_node_digits(&field)?;
+                if digits.is_empty() {
+                    None
+                } else {
+                    digits_to_u64(radix, &digits)
+                }
+            })
+            .collect()
+    )
+}
+
+/// replace all fields
+pub fn set_node_fields(node: &mut NestedNode, values: &[u64]) {
+    node.send_cmd_obj(ListCmd::Clear.into_repr_tree(&node.ctx));
+
+    node.goto(TreeCursor::home());
+    for value in values {
+        let mut field = make_field(node.ctx.clone());
+        field.set_value_u64(*value);
+        node.send_cmd_obj(
+            ReprTree::new_leaf(
+                Context::parse(&node.ctx, "NestedNode"),
+                SingletonBuffer::new(field.into_node()).get_port().into()
+            )
+        );
+    }
+    node.goto(TreeCursor::none());
+}
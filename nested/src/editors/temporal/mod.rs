@@ -0,0 +1,14 @@
+pub mod calendar;
+pub mod fields;
+pub mod date;
+pub mod duration;
+pub mod timestamp;
+pub mod ctx;
+
+pub use {
+    calendar::{DateTime, MAX_UNIX_SECONDS},
+    date::DateEditor,
+    duration::DurationEditor,
+    timestamp::{TimestampEditor, Iso8601Editor, seconds_ladder, get_seconds_port},
+    ctx::init_ctx
+};
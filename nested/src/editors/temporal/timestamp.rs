@@ -0,0 +1,215 @@
+use {
+    r3vi::{
+        view::{OuterViewPort, singleton::*, sequence::*},
+        buffer::{singleton::*, index_hashmap::*}
+    },
+    laddertypes::{TypeTerm},
+    crate::{
+        type_system::{Context, ReprTree},
+        editors::{
+            char::CharEditor,
+            list::{ListEditor, PTYListController, PTYListStyle},
+            integer::{PosIntEditor, Endianness, digits_to_u64},
+            temporal::DateTime
+        },
+        terminal::{TerminalProjections, make_label},
+        tree::{NestedNode, TreeNav, TreeCursor, WatchController},
+        commander::ObjCommander,
+        utils::combine::collect_singletons
+    },
+    std::sync::{Arc, RwLock},
+    cgmath::Point2
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `Seconds ~ ℕ`: where durations and timestamps
+/// keep their number of seconds, as `Option<u64>`
+pub fn seconds_ladder(ctx: &Arc<RwLock<Context>>) -> Vec<TypeTerm> {
+    let ctx = ctx.read().unwrap();
+    vec![
+        TypeTerm::TypeID(ctx.get_typeid("Seconds").unwrap()),
+        TypeTerm::TypeID(ctx.get_typeid("ℕ").unwrap())
+    ]
+}
+
+pub fn get_seconds_port(data: &Arc<RwLock<ReprTree>>, ctx: &Arc<RwLock<Context>>) -> Option<OuterViewPort<dyn SingletonView<Item = Option<u64>>>> {
+    ReprTree::descend_ladder(data, seconds_ladder(ctx).into_iter())?
+        .read().unwrap()
+        .get_port::<dyn SingletonView<Item = Option<u64>>>()
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `<TimeSince UnixEpoch> ~ Seconds ~ <PosInt 10 BigEndian>`,
+/// displayed together with the point in time it denotes
+pub struct TimestampEditor {
+    seconds: PosIntEditor,
+    node: NestedNode,
+}
+
+impl TimestampEditor {
+    pub fn new(ctx: Arc<RwLock<Context>>) -> Self {
+        let seconds = PosIntEditor::new(ctx.clone(), 10, Endianness::BigEndian);
+        let value = PosIntEditor::get_node_digits_port(&seconds.get_node()).unwrap()
+            .map(|digits| {
+                let (radix, digits) = digits?;
+                if digits.is_empty() {
+                    None
+                } else {
+                    digits_to_u64(radix, &digits)
+                }
+            });
+
+        let mut node = seconds.get_node();
+        node.data = ReprTree::ascend(
+            &ReprTree::ascend(&node.data, seconds_ladder(&ctx)[0].clone()),
+            TimestampEditor::get_type(&ctx)
+        );
+        node.data.write().unwrap().insert_leaf(
+            seconds_ladder(&ctx).into_iter(),
+            value.clone().into()
+        );
+
+        let theme = ctx.read().unwrap().get_theme();
+        let mut grid = IndexBuffer::new();
+        grid.insert_iter(vec![
+            (Point2::new(0, 0), node.get_view()),
+            (Point2::new(1, 0), value
+                .map(move |secs| match secs {
+                    Some(secs) =>
+                        make_label(&format!(" = {}", DateTime::from_unix_seconds(secs).to_iso8601()))
+                            .with_style(theme.read().unwrap().inactive),
+                    None => make_label("")
+                })
+                .to_grid()
+                .flatten())
+        ]);
+        node.view = Some(grid.get_port().flatten());
+
+        TimestampEditor {
+            seconds,
+            node
+        }
+    }
+
+    pub fn from_seconds(ctx: Arc<RwLock<Context>>, seconds: u64) -> Self {
+        let mut edit = TimestampEditor::new(ctx);
+        edit.set_seconds(seconds);
+        edit
+    }
+
+    /// `<TimeSince UnixEpoch>`
+    pub fn get_type(ctx: &Arc<RwLock<Context>>) -> TypeTerm {
+        let ctx = ctx.read().unwrap();
+        TypeTerm::App(vec![
+            TypeTerm::TypeID(ctx.get_typeid("TimeSince").unwrap()),
+            TypeTerm::TypeID(ctx.get_typeid("UnixEpoch").unwrap())
+        ])
+    }
+
+    pub fn set_seconds(&mut self, seconds: u64) {
+        self.seconds.set_value_u64(seconds);
+    }
+
+    pub fn get_seconds(&self) -> Option<u64> {
+        TimestampEditor::get_node_seconds(&self.node)
+    }
+
+    pub fn get_node_seconds(node: &NestedNode) -> Option<u64> {
+        get_seconds_port(&node.data, &node.ctx)?.get_view()?.get()
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.node
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `ISO-8601 ~ <List Char>`: a date with optional time of day,
+/// like `1970-01-01T00:00:00Z`. Syntax errors are reported while typing.
+pub struct Iso8601Editor {
+    node: NestedNode,
+}
+
+impl Iso8601Editor {
+    pub fn new(ctx: Arc<RwLock<Context>>) -> Self {
+        let mut node = Context::make_node(
+            &ctx,
+            Context::parse(&ctx, "<List Char>"),
+            SingletonBuffer::new(0).get_port()
+        ).unwrap();
+
+        node.data = ReprTree::ascend(&node.data.clone(), Iso8601Editor::get_type(&ctx));
+
+        PTYListController::for_node( &mut node, None, None );
+        PTYListStyle::for_node( &mut node, ("", "", "") );
+
+        WatchController::for_node(&mut node, |node, msg| {
+            let text = Iso8601Editor::get_node_text(node).unwrap_or_default();
+            if !text.is_empty() {
+                if let Err(err) = DateTime::parse_iso8601(&text) {
+                    msg.push(crate::diagnostics::make_error(&node.ctx, make_label(&err)));
+                }
+            }
+        });
+
+        Iso8601Editor {
+            node
+        }
+    }
+
+    pub fn from_datetime(ctx: Arc<RwLock<Context>>, datetime: &DateTime) -> Self {
+        let mut edit = Iso8601Editor::new(ctx);
+        edit.node.goto(TreeCursor::home());
+        for c in datetime.to_iso8601().chars() {
+            edit.node.send_cmd_obj(ReprTree::from_char(&edit.node.ctx, c));
+        }
+        edit.node.goto(TreeCursor::none());
+        edit
+    }
+
+    pub fn get_type(ctx: &Arc<RwLock<Context>>) -> TypeTerm {
+        TypeTerm::TypeID(ctx.read().unwrap().get_typeid("ISO-8601").unwrap())
+    }
+
+    pub fn get_text(&self) -> Option<String> {
+        Iso8601Editor::get_node_text(&self.node)
+    }
+
+    /// `None` if the text is no valid timestamp
+    pub fn get_datetime(&self) -> Option<DateTime> {
+        Iso8601Editor::get_node_datetime(&self.node)
+    }
+
+    pub fn get_node_text(node: &NestedNode) -> Option<String> {
+        let list = node.get_edit::<ListEditor>()?;
+        let chars = list.read().unwrap().get_data_port().get_view()?;
+        chars.iter()
+            .map(|c| Some(c.get_edit::<CharEditor>()?.read().unwrap().get()))
+            .collect()
+    }
+
+    pub fn get_node_datetime(node: &NestedNode) -> Option<DateTime> {
+        DateTime::parse_iso8601(&Iso8601Editor::get_node_text(node)?).ok()
+    }
+
+    /// projection of `get_node_text`, following all edits
+    pub fn get_node_text_port(node: &NestedNode) -> Option<OuterViewPort<dyn SingletonView<Item = Option<String>>>> {
+        let list = node.get_edit::<ListEditor>()?;
+        let chars = collect_singletons(
+            list.read().unwrap().get_data_port().map(|c| {
+                match c.get_edit::<CharEditor>() {
+                    Some(c) => c.read().unwrap().get_port().map(|c| Some(c)),
+                    None => SingletonBuffer::new(None).get_port()
+                }
+            })
+        );
+        Some(chars.map(|chars| chars.into_iter().collect()))
+    }
+
+    pub fn into_node(self) -> NestedNode {
+        self.node
+    }
+}
@@ -117,12 +117,13 @@ pub fn init_ctx(ctx: &mut Context) {
         MorphismTypePattern { src_tyid: ctx.get_typeid("Char"), dst_tyid: ctx.get_typeid("Type::Lit::Char").unwrap() },
         Arc::new(|mut node, _dst_type:_| {
             node.ctx.write().unwrap().meta_chars = vec![ '\'' ];
+            let theme = node.ctx.read().unwrap().get_theme();
             let mut grid = r3vi::buffer::index_hashmap::IndexBuffer::new();
 
             grid.insert_iter(
                 vec![
                     (Point2::new(0,0), crate::terminal::make_label("'")),
-                    (Point2::new(1,0), node.view.clone().unwrap_or( crate::terminal::make_label(".").with_fg_color((220,200,20))) ),
+                    (Point2::new(1,0), node.view.clone().unwrap_or( crate::terminal::make_label(".").with_style(theme.read().unwrap().literal)) ),
                     (Point2::new(2,0), crate::terminal::make_label("'")),
                 ]
             );
@@ -112,7 +112,7 @@ impl TypeTermEditor {
 
                 let mut editor = editor.write().unwrap();
                 editor.cur_node.set(
-                    crate::editors::integer::PosIntEditor::from_u64(parent_ctx, 10, *n as u64)
+                    crate::editors::integer::PosIntEditor::from_u64(parent_ctx, 10, crate::editors::integer::Endianness::BigEndian, *n as u64)
                         .into_node()
                 );
                 editor.state = State::Num;
@@ -130,6 +130,11 @@ impl TypeTermEditor {
         node
     }
 
+    /// the list-, char- or integer-node which currently edits this term
+    pub fn get_cur_node(&self) -> NestedNode {
+        self.cur_node.get()
+    }
+
     fn set_state(&mut self, new_state: State) {
         let mut node = match new_state {
             State::Any => {
@@ -157,7 +162,7 @@ impl TypeTermEditor {
                     .morph( Context::parse(&self.ctx, "Type::Sym::Var") )
             }
             State::Num => {
-                crate::editors::integer::PosIntEditor::new(self.ctx.clone(), 10)
+                crate::editors::integer::PosIntEditor::new(self.ctx.clone(), 10, crate::editors::integer::Endianness::BigEndian)
                     .into_node()
                     .morph( Context::parse(&self.ctx, "Type::Lit::Num") )
             }
@@ -0,0 +1,1266 @@
+use {
+    crate::terminal::{
+        CellWidth, TerminalAtom, TerminalCursor, TerminalCursorShape, TerminalStyle, UnderlineStyle,
+    },
+    cgmath::{Point2, Vector2},
+    std::collections::HashSet,
+    unicode_width::UnicodeWidthChar,
+    vte::{Params, ParamsIter, Parser, Perform},
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Modes which can be switched by the program running in the terminal
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TerminalModes {
+    /// DECAWM: wrap to the next line after the last column
+    pub autowrap: bool,
+
+    /// DECOM: cursor addressing relative to the scroll region
+    pub origin: bool,
+
+    /// IRM: printed chars shift the rest of the line to the right
+    pub insert: bool,
+
+    /// LNM: line feed also returns the carriage
+    pub linefeed_newline: bool,
+
+    /// DECTCEM
+    pub cursor_visible: bool,
+
+    /// DECCKM: cursor keys send `ESC O x` instead of `ESC [ x`
+    pub application_cursor: bool,
+
+    /// DECKPAM / DECKPNM
+    pub application_keypad: bool,
+
+    pub bracketed_paste: bool,
+
+    /// mouse reporting (1000, 1002, 1003)
+    pub mouse_tracking: bool,
+
+    /// SGR encoding of mouse reports (1006)
+    pub sgr_mouse: bool,
+
+    /// 47, 1047, 1049
+    pub alternate_screen: bool,
+}
+
+impl Default for TerminalModes {
+    fn default() -> Self {
+        TerminalModes {
+            autowrap: true,
+            origin: false,
+            insert: false,
+            linefeed_newline: false,
+            cursor_visible: true,
+            application_cursor: false,
+            application_keypad: false,
+            bracketed_paste: false,
+            mouse_tracking: false,
+            sgr_mouse: false,
+            alternate_screen: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Charset {
+    Ascii,
+
+    /// line drawing characters selected with `ESC ( 0`
+    DecSpecialGraphics,
+}
+
+impl Charset {
+    fn map(&self, c: char) -> char {
+        match self {
+            Charset::Ascii => c,
+            Charset::DecSpecialGraphics => match c {
+                '`' => '◆', 'a' => '▒', 'f' => '°', 'g' => '±',
+                'j' => '┘', 'k' => '┐', 'l' => '┌', 'm' => '└', 'n' => '┼',
+                'o' => '⎺', 'p' => '⎻', 'q' => '─', 'r' => '⎼', 's' => '⎽',
+                't' => '├', 'u' => '┤', 'v' => '┴', 'w' => '┬', 'x' => '│',
+                'y' => '≤', 'z' => '≥', '{' => 'π', '|' => '≠', '}' => '£', '~' => '·',
+                c => c,
+            },
+        }
+    }
+}
+
+/// state saved by DECSC and restored by DECRC
+#[derive(Clone, Copy, Debug)]
+struct SavedCursor {
+    pos: Point2<i16>,
+    style: TerminalStyle,
+    pending_wrap: bool,
+    origin: bool,
+    charsets: [Charset; 2],
+    active_charset: usize,
+}
+
+impl Default for SavedCursor {
+    fn default() -> Self {
+        SavedCursor {
+            pos: Point2::new(0, 0),
+            style: TerminalStyle::default(),
+            pending_wrap: false,
+            origin: false,
+            charsets: [Charset::Ascii; 2],
+            active_charset: 0,
+        }
+    }
+}
+
+/// device control string which is currently received
+struct Dcs {
+    intermediates: Vec<u8>,
+    action: char,
+    data: Vec<u8>,
+}
+
+enum TTYColor {
+    Rgb(u8, u8, u8),
+
+    // 3-Bit/4-Bit colors
+    Black,
+    LightBlack,
+    Red,
+    LightRed,
+    Green,
+    LightGreen,
+    Yellow,
+    LightYellow,
+    Blue,
+    LightBlue,
+    Magenta,
+    LightMagenta,
+    Cyan,
+    LightCyan,
+    White,
+    LightWhite,
+}
+
+struct ColorPalett {
+    black: (u8, u8, u8),
+    red: (u8, u8, u8),
+    green: (u8, u8, u8),
+    yellow: (u8, u8, u8),
+    blue: (u8, u8, u8),
+    magenta: (u8, u8, u8),
+    cyan: (u8, u8, u8),
+    white: (u8, u8, u8),
+}
+
+impl Default for ColorPalett {
+    fn default() -> Self {
+        ColorPalett {
+            black: (1, 1, 1),
+            red: (222, 56, 43),
+            green: (0, 64, 0),
+            yellow: (255, 199, 6),
+            blue: (0, 111, 184),
+            magenta: (118, 38, 113),
+            cyan: (44, 181, 233),
+            white: (204, 204, 204),
+        }
+    }
+}
+
+impl ColorPalett {
+    fn get_rgb(&self, col: &TTYColor) -> (u8, u8, u8) {
+        match col {
+            TTYColor::Rgb(r, g, b) => (*r, *g, *b),
+            TTYColor::Black | TTYColor::LightBlack => self.black,
+            TTYColor::Red | TTYColor::LightRed => self.red,
+            TTYColor::Green | TTYColor::LightGreen => self.green,
+            TTYColor::Yellow | TTYColor::LightYellow => self.yellow,
+            TTYColor::Blue | TTYColor::LightBlue => self.blue,
+            TTYColor::Magenta | TTYColor::LightMagenta => self.magenta,
+            TTYColor::Cyan | TTYColor::LightCyan => self.cyan,
+            TTYColor::White | TTYColor::LightWhite => self.white,
+        }
+    }
+}
+
+/// Parse the color of SGR 38/48/58, either as `38;2;r;g;b` / `38;5;n`
+/// or with colon sub-parameters `38:2:r:g:b`, `38:2:cs:r:g:b` / `38:5:n`.
+fn parse_extended_color(n: &[u16], piter: &mut ParamsIter) -> Option<(u8, u8, u8)> {
+    if n.len() > 1 {
+        match n[1] {
+            2 if n.len() >= 6 => Some((n[n.len() - 3] as u8, n[n.len() - 2] as u8, n[n.len() - 1] as u8)),
+            2 if n.len() == 5 => Some((n[2] as u8, n[3] as u8, n[4] as u8)),
+            5 if n.len() >= 3 => Some(ansi_colours::rgb_from_ansi256(n[2] as u8)),
+            _ => None,
+        }
+    } else {
+        match piter.next()?[0] {
+            2 => {
+                let r = piter.next()?[0] as u8;
+                let g = piter.next()?[0] as u8;
+                let b = piter.next()?[0] as u8;
+                Some((r, g, b))
+            }
+            5 => Some(ansi_colours::rgb_from_ansi256(piter.next()?[0] as u8)),
+            _ => None,
+        }
+    }
+}
+
+/// SGR parameters which select `style`, as reported by DECRQSS
+fn sgr_params(style: &TerminalStyle) -> String {
+    let mut params = vec![ String::from("0") ];
+    if style.bold == Some(true) { params.push("1".into()); }
+    if style.dim == Some(true) { params.push("2".into()); }
+    if style.italic == Some(true) { params.push("3".into()); }
+    if style.underline == Some(true) {
+        params.push(match style.underline_style {
+            Some(UnderlineStyle::Double) => "4:2",
+            Some(UnderlineStyle::Curly) => "4:3",
+            _ => "4",
+        }.into());
+    }
+    if style.inverse == Some(true) { params.push("7".into()); }
+    if style.strikethrough == Some(true) { params.push("9".into()); }
+    if let Some((r, g, b)) = style.fg_color { params.push(format!("38:2::{}:{}:{}", r, g, b)); }
+    if let Some((r, g, b)) = style.bg_color { params.push(format!("48:2::{}:{}:{}", r, g, b)); }
+    if let Some((r, g, b)) = style.underline_color { params.push(format!("58:2::{}:{}:{}", r, g, b)); }
+    params.join(";")
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// VT100/xterm compatible terminal emulator core.
+/// Escape sequences written by a program are interpreted
+/// into a grid of atoms, changed cells are collected
+/// until they are fetched with `take_dirty()`.
+pub struct TerminalEmulator {
+    parser: Parser,
+    state: EmulatorState,
+}
+
+impl TerminalEmulator {
+    pub fn new(size: Vector2<i16>) -> Self {
+        TerminalEmulator {
+            parser: Parser::new(),
+            state: EmulatorState::new(size),
+        }
+    }
+
+    pub fn process(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.parser.advance(&mut self.state, *byte);
+        }
+    }
+
+    pub fn resize(&mut self, size: Vector2<i16>) {
+        self.state.resize(size);
+    }
+
+    pub fn size(&self) -> Vector2<i16> {
+        self.state.size
+    }
+
+    pub fn get(&self, pos: &Point2<i16>) -> Option<TerminalAtom> {
+        self.state.get_cell(*pos)
+    }
+
+    pub fn get_cursor_pos(&self) -> Point2<i16> {
+        self.state.cursor
+    }
+
+    /// the cursor if it is visible
+    pub fn get_cursor(&self) -> Option<TerminalCursor> {
+        if self.state.modes.cursor_visible {
+            Some(TerminalCursor {
+                pos: self.state.cursor,
+                shape: self.state.cursor_shape,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_modes(&self) -> TerminalModes {
+        self.state.modes
+    }
+
+    pub fn get_title(&self) -> &str {
+        &self.state.title
+    }
+
+    /// (top, bottom) rows of the scroll region, inclusive
+    pub fn get_scroll_region(&self) -> (i16, i16) {
+        (self.state.scroll_top, self.state.scroll_bottom)
+    }
+
+    /// text of row `y`, empty cells as spaces
+    pub fn get_line(&self, y: i16) -> String {
+        let mut line = String::new();
+        for x in 0 .. self.state.size.x {
+            match self.state.get_cell(Point2::new(x, y)) {
+                Some(atom) if atom.width == CellWidth::Continuation => {}
+                Some(atom) => {
+                    line.push(atom.c.unwrap_or(' '));
+                    line.extend(atom.combining.iter());
+                }
+                None => line.push(' '),
+            }
+        }
+        line
+    }
+
+    /// positions which changed since the last call
+    pub fn take_dirty(&mut self) -> Vec<Point2<i16>> {
+        self.state.dirty.drain().collect()
+    }
+
+    /// replies to status requests (DSR, DA, DECRQSS)
+    /// which have to be written back to the program
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.state.responses)
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+type Row = Vec<Option<TerminalAtom>>;
+
+struct EmulatorState {
+    size: Vector2<i16>,
+
+    /// the active screen buffer
+    rows: Vec<Row>,
+
+    /// the inactive one, primary while the alternate screen is shown
+    /// and vice versa
+    other_rows: Vec<Row>,
+
+    cursor: Point2<i16>,
+    cursor_shape: TerminalCursorShape,
+
+    /// the last column was written and the next printed char
+    /// goes to the next line
+    pending_wrap: bool,
+
+    style: TerminalStyle,
+    scroll_top: i16,
+    scroll_bottom: i16,
+    modes: TerminalModes,
+    tabs: Vec<bool>,
+
+    /// saved cursor of the primary and alternate screen
+    saved: [SavedCursor; 2],
+
+    charsets: [Charset; 2],
+    active_charset: usize,
+    last_char: Option<char>,
+
+    title: String,
+    dcs: Option<Dcs>,
+    colors: ColorPalett,
+
+    responses: Vec<u8>,
+    dirty: HashSet<Point2<i16>>,
+}
+
+impl EmulatorState {
+    fn new(size: Vector2<i16>) -> Self {
+        let size = Vector2::new(std::cmp::max(1, size.x), std::cmp::max(1, size.y));
+        let mut state = EmulatorState {
+            size,
+            rows: vec![ vec![ None; size.x as usize ]; size.y as usize ],
+            other_rows: vec![ vec![ None; size.x as usize ]; size.y as usize ],
+            cursor: Point2::new(0, 0),
+            cursor_shape: TerminalCursorShape::Block,
+            pending_wrap: false,
+            style: TerminalStyle::default(),
+            scroll_top: 0,
+            scroll_bottom: size.y - 1,
+            modes: TerminalModes::default(),
+            tabs: (0 .. size.x).map(|x| x % 8 == 0).collect(),
+            saved: [SavedCursor::default(); 2],
+            charsets: [Charset::Ascii; 2],
+            active_charset: 0,
+            last_char: None,
+            title: String::new(),
+            dcs: None,
+            colors: ColorPalett::default(),
+            responses: Vec::new(),
+            dirty: HashSet::new(),
+        };
+        state.mark_all();
+        state
+    }
+
+    fn mark_all(&mut self) {
+        self.mark_rows(0, self.size.y - 1);
+    }
+
+    fn mark_rows(&mut self, top: i16, bottom: i16) {
+        for y in top ..= bottom {
+            for x in 0 .. self.size.x {
+                self.dirty.insert(Point2::new(x, y));
+            }
+        }
+    }
+
+    fn contains(&self, pos: Point2<i16>) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.size.x && pos.y < self.size.y
+    }
+
+    fn get_cell(&self, pos: Point2<i16>) -> Option<TerminalAtom> {
+        if self.contains(pos) {
+            self.rows[pos.y as usize][pos.x as usize]
+        } else {
+            None
+        }
+    }
+
+    /// erased cells keep the current background color
+    fn blank(&self) -> Option<TerminalAtom> {
+        self.style.bg_color.map(|bg| TerminalAtom::new(' ', TerminalStyle::bg_color(bg)))
+    }
+
+    fn blank_row(&self) -> Row {
+        vec![ self.blank(); self.size.x as usize ]
+    }
+
+    fn set_raw(&mut self, pos: Point2<i16>, atom: Option<TerminalAtom>) {
+        self.rows[pos.y as usize][pos.x as usize] = atom;
+        self.dirty.insert(pos);
+    }
+
+    /// Write a cell. Wide chars which are partially
+    /// overwritten get erased completely.
+    fn set_cell(&mut self, pos: Point2<i16>, atom: Option<TerminalAtom>) {
+        if !self.contains(pos) {
+            return;
+        }
+
+        let new_width = atom.map(|a| a.width).unwrap_or(CellWidth::Single);
+        match self.get_cell(pos).map(|a| a.width) {
+            Some(CellWidth::Double) => {
+                let right = pos + Vector2::new(1, 0);
+                if self.get_cell(right).map(|a| a.width) == Some(CellWidth::Continuation) {
+                    self.set_raw(right, None);
+                }
+            }
+            Some(CellWidth::Continuation) if new_width != CellWidth::Continuation => {
+                let left = pos - Vector2::new(1, 0);
+                if self.get_cell(left).map(|a| a.width) == Some(CellWidth::Double) {
+                    self.set_raw(left, None);
+                }
+            }
+            _ => {}
+        }
+
+        self.set_raw(pos, atom);
+    }
+
+    fn erase(&mut self, pos: Point2<i16>) {
+        let blank = self.blank();
+        self.set_cell(pos, blank);
+    }
+
+    fn erase_line(&mut self, y: i16, from: i16, to: i16) {
+        for x in std::cmp::max(0, from) .. std::cmp::min(to, self.size.x) {
+            self.erase(Point2::new(x, y));
+        }
+    }
+
+    /// remove halves of wide chars at the borders of a row
+    /// after its cells were shifted
+    fn repair_row(&mut self, y: i16) {
+        let last = Point2::new(self.size.x - 1, y);
+        if self.get_cell(last).map(|a| a.width) == Some(CellWidth::Double) {
+            self.set_raw(last, None);
+        }
+        for x in 0 .. self.size.x {
+            let pos = Point2::new(x, y);
+            if self.get_cell(pos).map(|a| a.width) == Some(CellWidth::Continuation)
+                && (x == 0 || self.get_cell(pos - Vector2::new(1, 0)).map(|a| a.width) != Some(CellWidth::Double))
+            {
+                self.set_raw(pos, None);
+            }
+        }
+    }
+
+    //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+    fn resize(&mut self, size: Vector2<i16>) {
+        let size = Vector2::new(std::cmp::max(1, size.x), std::cmp::max(1, size.y));
+        if size == self.size {
+            return;
+        }
+
+        // keep the cursor line visible when shrinking
+        let excess = std::cmp::max(0, self.cursor.y - (size.y - 1)) as usize;
+        self.rows.drain(0 .. excess);
+        self.cursor.y -= excess as i16;
+
+        for rows in [&mut self.rows, &mut self.other_rows].iter_mut() {
+            rows.resize(size.y as usize, vec![ None; size.x as usize ]);
+            for row in rows.iter_mut() {
+                row.resize(size.x as usize, None);
+                if let Some(Some(atom)) = row.last() {
+                    if atom.width == CellWidth::Double {
+                        *row.last_mut().unwrap() = None;
+                    }
+                }
+            }
+        }
+
+        let old_cols = self.tabs.len() as i16;
+        self.tabs.resize(size.x as usize, false);
+        for x in old_cols .. size.x {
+            self.tabs[x as usize] = x % 8 == 0;
+        }
+
+        self.size = size;
+        self.scroll_top = 0;
+        self.scroll_bottom = size.y - 1;
+        self.cursor.x = std::cmp::min(self.cursor.x, size.x - 1);
+        self.cursor.y = std::cmp::min(self.cursor.y, size.y - 1);
+        self.pending_wrap = false;
+
+        self.dirty.retain(|pos| pos.x < size.x && pos.y < size.y);
+        self.mark_all();
+    }
+
+    fn reset(&mut self) {
+        let size = self.size;
+        let title = std::mem::take(&mut self.title);
+        let responses = std::mem::take(&mut self.responses);
+        *self = EmulatorState::new(size);
+        self.title = title;
+        self.responses = responses;
+    }
+
+    //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+    fn scroll_up(&mut self, n: usize) {
+        let (top, bottom) = (self.scroll_top as usize, self.scroll_bottom as usize);
+        let n = std::cmp::min(n, bottom + 1 - top);
+        for _ in 0 .. n {
+            self.rows.remove(top);
+            let row = self.blank_row();
+            self.rows.insert(bottom, row);
+        }
+        self.mark_rows(self.scroll_top, self.scroll_bottom);
+    }
+
+    fn scroll_dn(&mut self, n: usize) {
+        let (top, bottom) = (self.scroll_top as usize, self.scroll_bottom as usize);
+        let n = std::cmp::min(n, bottom + 1 - top);
+        for _ in 0 .. n {
+            self.rows.remove(bottom);
+            let row = self.blank_row();
+            self.rows.insert(top, row);
+        }
+        self.mark_rows(self.scroll_top, self.scroll_bottom);
+    }
+
+    fn in_scroll_region(&self) -> bool {
+        self.cursor.y >= self.scroll_top && self.cursor.y <= self.scroll_bottom
+    }
+
+    /// IL: insert blank lines at the cursor, lines below the
+    /// scroll region are not affected
+    fn insert_blank_lines(&mut self, n: usize) {
+        if !self.in_scroll_region() {
+            return;
+        }
+        let (y, bottom) = (self.cursor.y as usize, self.scroll_bottom as usize);
+        let n = std::cmp::min(n, bottom + 1 - y);
+        for _ in 0 .. n {
+            self.rows.remove(bottom);
+            let row = self.blank_row();
+            self.rows.insert(y, row);
+        }
+        self.mark_rows(self.cursor.y, self.scroll_bottom);
+        self.cursor.x = 0;
+        self.pending_wrap = false;
+    }
+
+    /// DL
+    fn delete_lines(&mut self, n: usize) {
+        if !self.in_scroll_region() {
+            return;
+        }
+        let (y, bottom) = (self.cursor.y as usize, self.scroll_bottom as usize);
+        let n = std::cmp::min(n, bottom + 1 - y);
+        for _ in 0 .. n {
+            self.rows.remove(y);
+            let row = self.blank_row();
+            self.rows.insert(bottom, row);
+        }
+        self.mark_rows(self.cursor.y, self.scroll_bottom);
+        self.cursor.x = 0;
+        self.pending_wrap = false;
+    }
+
+    /// ICH: shift the rest of the line to the right
+    fn insert_blank_chars(&mut self, n: usize) {
+        let Point2 { x, y } = self.cursor;
+        let n = std::cmp::min(n, (self.size.x - x) as usize);
+        let blank = self.blank();
+
+        let row = &mut self.rows[y as usize];
+        for _ in 0 .. n {
+            row.insert(x as usize, blank);
+        }
+        row.truncate(self.size.x as usize);
+
+        for x in x .. self.size.x {
+            self.dirty.insert(Point2::new(x, y));
+        }
+        self.repair_row(y);
+        self.pending_wrap = false;
+    }
+
+    /// DCH: shift the rest of the line to the left
+    fn delete_chars(&mut self, n: usize) {
+        let Point2 { x, y } = self.cursor;
+        let n = std::cmp::min(n, (self.size.x - x) as usize);
+        let blank = self.blank();
+
+        let row = &mut self.rows[y as usize];
+        row.drain(x as usize .. x as usize + n);
+        row.resize(self.size.x as usize, blank);
+
+        for x in x .. self.size.x {
+            self.dirty.insert(Point2::new(x, y));
+        }
+        self.repair_row(y);
+        self.pending_wrap = false;
+    }
+
+    //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+    /// IND: move down, scroll at the bottom of the scroll region
+    fn index(&mut self) {
+        if self.cursor.y == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.cursor.y < self.size.y - 1 {
+            self.cursor.y += 1;
+        }
+        self.pending_wrap = false;
+    }
+
+    /// RI: move up, scroll at the top of the scroll region
+    fn reverse_index(&mut self) {
+        if self.cursor.y == self.scroll_top {
+            self.scroll_dn(1);
+        } else if self.cursor.y > 0 {
+            self.cursor.y -= 1;
+        }
+        self.pending_wrap = false;
+    }
+
+    fn linefeed(&mut self) {
+        self.index();
+        if self.modes.linefeed_newline {
+            self.carriage_return();
+        }
+    }
+
+    fn carriage_return(&mut self) {
+        self.cursor.x = 0;
+        self.pending_wrap = false;
+    }
+
+    fn horizontal_tab(&mut self, n: usize) {
+        for _ in 0 .. n {
+            let mut x = self.cursor.x + 1;
+            while x < self.size.x - 1 && !self.tabs[x as usize] {
+                x += 1;
+            }
+            self.cursor.x = std::cmp::min(x, self.size.x - 1);
+        }
+        self.pending_wrap = false;
+    }
+
+    fn backward_tab(&mut self, n: usize) {
+        for _ in 0 .. n {
+            let mut x = self.cursor.x - 1;
+            while x > 0 && !self.tabs[x as usize] {
+                x -= 1;
+            }
+            self.cursor.x = std::cmp::max(x, 0);
+        }
+        self.pending_wrap = false;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor.x > 0 {
+            self.cursor.x -= 1;
+        }
+        self.pending_wrap = false;
+    }
+
+    /// rows the cursor may move to vertically
+    fn vertical_bounds(&self) -> (i16, i16) {
+        (
+            if self.cursor.y >= self.scroll_top { self.scroll_top } else { 0 },
+            if self.cursor.y <= self.scroll_bottom { self.scroll_bottom } else { self.size.y - 1 },
+        )
+    }
+
+    fn cursor_up(&mut self, n: usize) {
+        let (top, _) = self.vertical_bounds();
+        self.cursor.y = std::cmp::max(top as i32, self.cursor.y as i32 - n as i32) as i16;
+        self.pending_wrap = false;
+    }
+
+    fn cursor_dn(&mut self, n: usize) {
+        let (_, bottom) = self.vertical_bounds();
+        self.cursor.y = std::cmp::min(bottom as i32, self.cursor.y as i32 + n as i32) as i16;
+        self.pending_wrap = false;
+    }
+
+    fn cursor_pxev(&mut self, n: usize) {
+        self.cursor.x = std::cmp::max(0, self.cursor.x as i32 - n as i32) as i16;
+        self.pending_wrap = false;
+    }
+
+    fn cursor_nexd(&mut self, n: usize) {
+        self.cursor.x = std::cmp::min(self.size.x as i32 - 1, self.cursor.x as i32 + n as i32) as i16;
+        self.pending_wrap = false;
+    }
+
+    /// CUP with 0-based coordinates, relative to the
+    /// scroll region in origin mode. Coordinates are
+    /// taken as `i32` and clamped to the screen,
+    /// since parameters may exceed `i16`
+    fn goto(&mut self, row: i32, col: i32) {
+        let (top, bottom) =
+            if self.modes.origin {
+                (self.scroll_top, self.scroll_bottom)
+            } else {
+                (0, self.size.y - 1)
+            };
+
+        self.cursor.y = clamp(top as i32 + row, top, bottom);
+        self.cursor.x = clamp(col, 0, self.size.x - 1);
+        self.pending_wrap = false;
+    }
+
+    fn goto_row(&mut self, row: i32) {
+        let x = self.cursor.x as i32;
+        self.goto(row, x);
+    }
+
+    fn goto_col(&mut self, col: i32) {
+        let row = (self.cursor.y - if self.modes.origin { self.scroll_top } else { 0 }) as i32;
+        self.goto(row, col);
+    }
+
+    fn set_scroll_region(&mut self, top: i32, bottom: i32) {
+        let bottom = std::cmp::min(bottom, self.size.y as i32 - 1);
+        if top >= 0 && top < bottom {
+            self.scroll_top = top as i16;
+            self.scroll_bottom = bottom as i16;
+            self.goto(0, 0);
+        }
+    }
+
+    fn save_cursor_position(&mut self) {
+        let idx = self.modes.alternate_screen as usize;
+        self.saved[idx] = SavedCursor {
+            pos: self.cursor,
+            style: self.style,
+            pending_wrap: self.pending_wrap,
+            origin: self.modes.origin,
+            charsets: self.charsets,
+            active_charset: self.active_charset,
+        };
+    }
+
+    fn restore_cursor_position(&mut self) {
+        let saved = self.saved[self.modes.alternate_screen as usize];
+        self.cursor = Point2::new(
+            std::cmp::min(saved.pos.x, self.size.x - 1),
+            std::cmp::min(saved.pos.y, self.size.y - 1),
+        );
+        self.style = saved.style;
+        self.pending_wrap = saved.pending_wrap;
+        self.modes.origin = saved.origin;
+        self.charsets = saved.charsets;
+        self.active_charset = saved.active_charset;
+    }
+
+    fn set_alternate_screen(&mut self, alt: bool) {
+        if self.modes.alternate_screen != alt {
+            std::mem::swap(&mut self.rows, &mut self.other_rows);
+            self.modes.alternate_screen = alt;
+            self.mark_all();
+        }
+    }
+
+    fn clear_screen(&mut self) {
+        for y in 0 .. self.size.y {
+            self.erase_line(y, 0, self.size.x);
+        }
+    }
+
+    //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+    fn set_fg_color(&mut self, col: &TTYColor) {
+        self.style = self.style.add(TerminalStyle::fg_color(self.colors.get_rgb(col)));
+    }
+
+    fn set_bg_color(&mut self, col: &TTYColor) {
+        self.style = self.style.add(TerminalStyle::bg_color(self.colors.get_rgb(col)));
+    }
+
+    fn select_graphic_rendition(&mut self, params: &Params) {
+        let mut piter = params.into_iter();
+        while let Some(n) = piter.next() {
+            match n[0] {
+                0 => self.style = TerminalStyle::default(),
+                1 => self.style = self.style.add(TerminalStyle::bold(true)),
+                2 => self.style = self.style.add(TerminalStyle::dim(true)),
+                3 => self.style = self.style.add(TerminalStyle::italic(true)),
+                4 => {
+                    // `4:x` selects the underline shape
+                    let style = match n.get(1) {
+                        Some(0) => TerminalStyle::underline(false),
+                        Some(2) => TerminalStyle::underline_style(UnderlineStyle::Double),
+                        Some(3) => TerminalStyle::underline_style(UnderlineStyle::Curly),
+                        _ => TerminalStyle::underline_style(UnderlineStyle::Single),
+                    };
+                    self.style = self.style.add(style);
+                }
+                7 => self.style = self.style.add(TerminalStyle::inverse(true)),
+                9 => self.style = self.style.add(TerminalStyle::strikethrough(true)),
+                21 => self.style = self.style.add(TerminalStyle::underline_style(UnderlineStyle::Double)),
+                22 => self.style = self.style.add(TerminalStyle::bold(false)).add(TerminalStyle::dim(false)),
+                23 => self.style = self.style.add(TerminalStyle::italic(false)),
+                24 => self.style = self.style.add(TerminalStyle::underline(false)),
+                27 => self.style = self.style.add(TerminalStyle::inverse(false)),
+                29 => self.style = self.style.add(TerminalStyle::strikethrough(false)),
+                39 => self.style.fg_color = None,
+                49 => self.style.bg_color = None,
+                59 => self.style.underline_color = None,
+
+                30 => self.set_fg_color(&TTYColor::Black),
+                40 => self.set_bg_color(&TTYColor::Black),
+                90 => self.set_fg_color(&TTYColor::LightBlack),
+                100 => self.set_bg_color(&TTYColor::LightBlack),
+                31 => self.set_fg_color(&TTYColor::Red),
+                41 => self.set_bg_color(&TTYColor::Red),
+                91 => self.set_fg_color(&TTYColor::LightRed),
+                101 => self.set_bg_color(&TTYColor::LightRed),
+                32 => self.set_fg_color(&TTYColor::Green),
+                42 => self.set_bg_color(&TTYColor::Green),
+                92 => self.set_fg_color(&TTYColor::LightGreen),
+                102 => self.set_bg_color(&TTYColor::LightGreen),
+                33 => self.set_fg_color(&TTYColor::Yellow),
+                43 => self.set_bg_color(&TTYColor::Yellow),
+                93 => self.set_fg_color(&TTYColor::LightYellow),
+                103 => self.set_bg_color(&TTYColor::LightYellow),
+                34 => self.set_fg_color(&TTYColor::Blue),
+                44 => self.set_bg_color(&TTYColor::Blue),
+                94 => self.set_fg_color(&TTYColor::LightBlue),
+                104 => self.set_bg_color(&TTYColor::LightBlue),
+                35 => self.set_fg_color(&TTYColor::Magenta),
+                45 => self.set_bg_color(&TTYColor::Magenta),
+                95 => self.set_fg_color(&TTYColor::LightMagenta),
+                105 => self.set_bg_color(&TTYColor::LightMagenta),
+                36 => self.set_fg_color(&TTYColor::Cyan),
+                46 => self.set_bg_color(&TTYColor::Cyan),
+                96 => self.set_fg_color(&TTYColor::LightCyan),
+                106 => self.set_bg_color(&TTYColor::LightCyan),
+                37 => self.set_fg_color(&TTYColor::White),
+                47 => self.set_bg_color(&TTYColor::White),
+                97 => self.set_fg_color(&TTYColor::LightWhite),
+                107 => self.set_bg_color(&TTYColor::LightWhite),
+
+                38 => {
+                    if let Some(rgb) = parse_extended_color(n, &mut piter) {
+                        self.set_fg_color(&TTYColor::Rgb(rgb.0, rgb.1, rgb.2));
+                    }
+                }
+                48 => {
+                    if let Some(rgb) = parse_extended_color(n, &mut piter) {
+                        self.set_bg_color(&TTYColor::Rgb(rgb.0, rgb.1, rgb.2));
+                    }
+                }
+                58 => {
+                    if let Some(rgb) = parse_extended_color(n, &mut piter) {
+                        self.style = self.style.add(TerminalStyle::underline_color(rgb));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    /// SM / RM, `private` for DEC private modes (`CSI ? Pm h`)
+    fn set_mode(&mut self, params: &Params, private: bool, value: bool) {
+        for p in params.iter() {
+            match (private, p[0]) {
+                (false, 4) => self.modes.insert = value,
+                (false, 20) => self.modes.linefeed_newline = value,
+
+                (true, 1) => self.modes.application_cursor = value,
+                (true, 6) => {
+                    self.modes.origin = value;
+                    self.goto(0, 0);
+                }
+                (true, 7) => self.modes.autowrap = value,
+                (true, 25) => self.modes.cursor_visible = value,
+                (true, 1000) | (true, 1002) | (true, 1003) => self.modes.mouse_tracking = value,
+                (true, 1006) => self.modes.sgr_mouse = value,
+                (true, 2004) => self.modes.bracketed_paste = value,
+
+                (true, 47) => self.set_alternate_screen(value),
+                (true, 1047) => {
+                    if value {
+                        self.set_alternate_screen(true);
+                    } else {
+                        if self.modes.alternate_screen {
+                            self.clear_screen();
+                        }
+                        self.set_alternate_screen(false);
+                    }
+                }
+                (true, 1048) => {
+                    if value {
+                        self.save_cursor_position();
+                    } else {
+                        self.restore_cursor_position();
+                    }
+                }
+                (true, 1049) => {
+                    if value {
+                        self.save_cursor_position();
+                        self.set_alternate_screen(true);
+                        self.clear_screen();
+                    } else {
+                        self.set_alternate_screen(false);
+                        self.restore_cursor_position();
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn device_status_report(&mut self, n: u16) {
+        match n {
+            5 => self.responses.extend_from_slice(b"\x1b[0n"),
+            6 => {
+                let row = self.cursor.y - if self.modes.origin { self.scroll_top } else { 0 };
+                let reply = format!("\x1b[{};{}R", row + 1, self.cursor.x + 1);
+                self.responses.extend_from_slice(reply.as_bytes());
+            }
+            _ => {}
+        }
+    }
+
+    /// DECRQSS: report the current setting of a control function
+    fn request_status_string(&mut self, request: &[u8]) {
+        let reply = match request {
+            b"m" => Some(format!("{}m", sgr_params(&self.style))),
+            b"r" => Some(format!("{};{}r", self.scroll_top + 1, self.scroll_bottom + 1)),
+            b" q" => Some(format!("{} q", match self.cursor_shape {
+                TerminalCursorShape::Block => 2,
+                TerminalCursorShape::Underline => 4,
+                TerminalCursorShape::Bar => 6,
+            })),
+            _ => None,
+        };
+
+        let reply = match reply {
+            Some(s) => format!("\x1bP1$r{}\x1b\\", s),
+            None => String::from("\x1bP0$r\x1b\\"),
+        };
+        self.responses.extend_from_slice(reply.as_bytes());
+    }
+
+    /// DECALN: fill the screen with `E`
+    fn screen_alignment_test(&mut self) {
+        self.scroll_top = 0;
+        self.scroll_bottom = self.size.y - 1;
+        for y in 0 .. self.size.y {
+            for x in 0 .. self.size.x {
+                self.set_cell(Point2::new(x, y), Some(TerminalAtom::from('E')));
+            }
+        }
+        self.goto(0, 0);
+    }
+}
+
+/// `value` limited to `min ..= max`
+fn clamp(value: i32, min: i16, max: i16) -> i16 {
+    std::cmp::max(min as i32, std::cmp::min(max as i32, value)) as i16
+}
+
+/// first parameter, or `default` if it is missing or zero
+fn param_or(params: &Params, idx: usize, default: u16) -> u16 {
+    match params.iter().nth(idx) {
+        Some(p) if p[0] != 0 => p[0],
+        _ => default,
+    }
+}
+
+impl Perform for EmulatorState {
+    fn print(&mut self, c: char) {
+        let c = self.charsets[self.active_charset].map(c);
+
+        let width = match c.width() {
+            // combining marks go to the previously printed cell
+            Some(0) => {
+                let mut pos =
+                    if self.pending_wrap {
+                        self.cursor
+                    } else {
+                        self.cursor - Vector2::new(1, 0)
+                    };
+                if self.get_cell(pos).map(|a| a.width) == Some(CellWidth::Continuation) {
+                    pos.x -= 1;
+                }
+                if let Some(mut atom) = self.get_cell(pos) {
+                    atom.combining.push(c);
+                    self.set_raw(pos, Some(atom));
+                }
+                return;
+            }
+            Some(2) => 2,
+            _ => 1,
+        };
+
+        if self.pending_wrap && self.modes.autowrap {
+            self.carriage_return();
+            self.index();
+        }
+        self.pending_wrap = false;
+
+        if width == 2 && self.cursor.x == self.size.x - 1 {
+            if self.modes.autowrap {
+                // a wide char does not fit into the last column
+                self.erase(self.cursor);
+                self.carriage_return();
+                self.index();
+            } else {
+                return;
+            }
+        }
+
+        if self.modes.insert {
+            self.insert_blank_chars(width);
+        }
+
+        let atom = TerminalAtom::new(c, self.style);
+        self.set_cell(self.cursor, Some(atom));
+        if width == 2 {
+            let right = self.cursor + Vector2::new(1, 0);
+            self.set_cell(right, Some(TerminalAtom::continuation(self.style)));
+        }
+        self.last_char = Some(c);
+
+        if self.cursor.x + width as i16 >= self.size.x {
+            self.cursor.x = self.size.x - 1;
+            self.pending_wrap = self.modes.autowrap;
+        } else {
+            self.cursor.x += width as i16;
+        }
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            0x08 => self.backspace(),
+            b'\t' => self.horizontal_tab(1),
+            b'\n' | 0x0b | 0x0c => self.linefeed(),
+            b'\r' => self.carriage_return(),
+
+            // SO / SI
+            0x0e => self.active_charset = 1,
+            0x0f => self.active_charset = 0,
+
+            _ => {}
+        }
+    }
+
+    fn hook(&mut self, _params: &Params, intermediates: &[u8], _ignore: bool, c: char) {
+        self.dcs = Some(Dcs {
+            intermediates: intermediates.to_vec(),
+            action: c,
+            data: Vec::new(),
+        });
+    }
+
+    fn put(&mut self, byte: u8) {
+        if let Some(dcs) = self.dcs.as_mut() {
+            dcs.data.push(byte);
+        }
+    }
+
+    fn unhook(&mut self) {
+        if let Some(dcs) = self.dcs.take() {
+            if dcs.intermediates == b"$" && dcs.action == 'q' {
+                self.request_status_string(&dcs.data);
+            }
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        match params {
+            // window title
+            [b"0", title, ..] | [b"2", title, ..] => {
+                self.title = String::from_utf8_lossy(title).into();
+            }
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, c: char) {
+        let n = param_or(params, 0, 1) as usize;
+
+        match (intermediates, c) {
+            ([], 'm') => self.select_graphic_rendition(params),
+
+            ([], '@') => self.insert_blank_chars(n),
+            ([], 'A') => self.cursor_up(n),
+            ([], 'B') | ([], 'e') => self.cursor_dn(n),
+            ([], 'C') | ([], 'a') => self.cursor_nexd(n),
+            ([], 'D') => self.cursor_pxev(n),
+            ([], 'E') => {
+                self.cursor_dn(n);
+                self.carriage_return();
+            }
+            ([], 'F') => {
+                self.cursor_up(n);
+                self.carriage_return();
+            }
+            ([], 'G') | ([], '`') => self.goto_col(n as i32 - 1),
+            ([], 'd') => self.goto_row(n as i32 - 1),
+            ([], 'H') | ([], 'f') => {
+                let row = param_or(params, 0, 1) as i32 - 1;
+                let col = param_or(params, 1, 1) as i32 - 1;
+                self.goto(row, col);
+            }
+            ([], 'I') => self.horizontal_tab(n),
+            ([], 'Z') => self.backward_tab(n),
+
+            ([], 'J') | ([b'?'], 'J') => {
+                let Point2 { x, y } = self.cursor;
+                match params.iter().next().map(|p| p[0]).unwrap_or(0) {
+                    // from cursor until end of screen
+                    0 => {
+                        self.erase_line(y, x, self.size.x);
+                        for y in y + 1 .. self.size.y {
+                            self.erase_line(y, 0, self.size.x);
+                        }
+                    }
+                    // from begin of screen until cursor
+                    1 => {
+                        for y in 0 .. y {
+                            self.erase_line(y, 0, self.size.x);
+                        }
+                        self.erase_line(y, 0, x + 1);
+                    }
+                    // entire screen (and scrollback, which we do not keep)
+                    2 | 3 => self.clear_screen(),
+                    _ => {}
+                }
+                self.pending_wrap = false;
+            }
+            ([], 'K') | ([b'?'], 'K') => {
+                let Point2 { x, y } = self.cursor;
+                match params.iter().next().map(|p| p[0]).unwrap_or(0) {
+                    0 => self.erase_line(y, x, self.size.x),
+                    1 => self.erase_line(y, 0, x + 1),
+                    2 => self.erase_line(y, 0, self.size.x),
+                    _ => {}
+                }
+                self.pending_wrap = false;
+            }
+            ([], 'X') => {
+                let Point2 { x, y } = self.cursor;
+                self.erase_line(y, x, clamp(x as i32 + n as i32, x, self.size.x));
+                self.pending_wrap = false;
+            }
+
+            ([], 'L') => self.insert_blank_lines(n),
+            ([], 'M') => self.delete_lines(n),
+            ([], 'P') => self.delete_chars(n),
+            ([], 'S') => self.scroll_up(n),
+            ([], 'T') => self.scroll_dn(n),
+
+            ([], 'b') => {
+                if let Some(c) = self.last_char {
+                    for _ in 0 .. n {
+                        self.print(c);
+                    }
+                }
+            }
+
+            ([], 'g') => match params.iter().next().map(|p| p[0]).unwrap_or(0) {
+                0 => self.tabs[self.cursor.x as usize] = false,
+                3 => self.tabs.iter_mut().for_each(|t| *t = false),
+                _ => {}
+            },
+
+            ([], 'h') => self.set_mode(params, false, true),
+            ([], 'l') => self.set_mode(params, false, false),
+            ([b'?'], 'h') => self.set_mode(params, true, true),
+            ([b'?'], 'l') => self.set_mode(params, true, false),
+
+            ([], 'n') => self.device_status_report(param_or(params, 0, 0)),
+            ([], 'c') => self.responses.extend_from_slice(b"\x1b[?62;22c"),
+            ([b'>'], 'c') => self.responses.extend_from_slice(b"\x1b[>0;10;1c"),
+
+            ([], 'r') => {
+                let top = param_or(params, 0, 1) as i32 - 1;
+                let bottom = param_or(params, 1, self.size.y as u16) as i32 - 1;
+                self.set_scroll_region(top, bottom);
+            }
+
+            ([], 's') => self.save_cursor_position(),
+            ([], 'u') => self.restore_cursor_position(),
+
+            // DECSCUSR
+            ([b' '], 'q') => {
+                self.cursor_shape = match params.iter().next().map(|p| p[0]).unwrap_or(0) {
+                    3 | 4 => TerminalCursorShape::Underline,
+                    5 | 6 => TerminalCursorShape::Bar,
+                    _ => TerminalCursorShape::Block,
+                };
+            }
+
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        match (intermediates, byte) {
+            ([], b'D') => self.index(),
+            ([], b'E') => {
+                self.carriage_return();
+                self.index();
+            }
+            ([], b'M') => self.reverse_index(),
+            ([], b'H') => self.tabs[self.cursor.x as usize] = true,
+            ([], b'c') => self.reset(),
+            ([], b'7') => self.save_cursor_position(),
+            ([], b'8') => self.restore_cursor_position(),
+            ([b'#'], b'8') => self.screen_alignment_test(),
+            ([], b'=') => self.modes.application_keypad = true,
+            ([], b'>') => self.modes.application_keypad = false,
+
+            ([b'('], b'0') => self.charsets[0] = Charset::DecSpecialGraphics,
+            ([b'('], _) => self.charsets[0] = Charset::Ascii,
+            ([b')'], b'0') => self.charsets[1] = Charset::DecSpecialGraphics,
+            ([b')'], _) => self.charsets[1] = Charset::Ascii,
+
+            // string terminator, handled by the parser
+            ([], b'\\') => {}
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,239 @@
+pub mod emulator;
+
+pub use emulator::{TerminalEmulator, TerminalModes};
+
+use {
+    r3vi::{
+        view::{
+            InnerViewPort, Observer, ObserverBroadcast, OuterViewPort, View, ViewPort,
+            index::*,
+            singleton::*,
+        },
+        buffer::{singleton::*, index_hashmap::*},
+        projection::projection_helper::ProjectionHelper,
+    },
+    crate::{
+        terminal::{TerminalAtom, TerminalCursor, TerminalCursorShape, TerminalStyle, TerminalView},
+    },
+    cgmath::{Point2, Vector2},
+    std::io::Read,
+    std::sync::{Arc, RwLock},
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+pub fn read_ansi_from<R: Read + Unpin>(
+    ansi_reader: &mut R,
+    max_size: Vector2<i16>,
+    port: InnerViewPort<dyn TerminalView>,
+) {
+    let mut emulator = EmulatorView::new(max_size, port);
+    let mut buf = [0; 2048];
+
+    loop {
+        match ansi_reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => emulator.process(&buf[..n]),
+            Err(_err) => {
+                //println!("err: {}", err);
+                break;
+            }
+        }
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Feeds output of a program into a `TerminalEmulator`
+/// and keeps a `TerminalView` of its screen up to date.
+pub struct EmulatorView {
+    emulator: TerminalEmulator,
+
+    buf: IndexBuffer<Point2<i16>, TerminalAtom>,
+    cursor: SingletonBuffer<Option<TerminalCursor>>,
+    size: SingletonBuffer<Vector2<i16>>,
+
+    _pty_proj: Arc<RwLock<PtyView>>,
+}
+
+impl EmulatorView {
+    pub fn new(size: Vector2<i16>, port: InnerViewPort<dyn TerminalView>) -> Self {
+        let buf_port = ViewPort::new();
+        let cursor_port = ViewPort::new();
+        let size_port = ViewPort::new();
+
+        let emulator = TerminalEmulator::new(size);
+        let mut view = EmulatorView {
+            buf: IndexBuffer::with_port(buf_port.inner()),
+            cursor: SingletonBuffer::with_port(emulator.get_cursor(), cursor_port.inner()),
+            size: SingletonBuffer::with_port(emulator.size(), size_port.inner()),
+            emulator,
+
+            _pty_proj: PtyView::new(
+                buf_port.outer(),
+                cursor_port.outer(),
+                size_port.outer(),
+                port,
+            ),
+        };
+
+        view.update();
+        view
+    }
+
+    pub fn process(&mut self, bytes: &[u8]) {
+        self.emulator.process(bytes);
+        self.update();
+    }
+
+    pub fn resize(&mut self, size: Vector2<i16>) {
+        self.emulator.resize(size);
+        self.update();
+    }
+
+    pub fn get_emulator(&self) -> &TerminalEmulator {
+        &self.emulator
+    }
+
+    /// see `TerminalEmulator::take_responses()`
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        self.emulator.take_responses()
+    }
+
+    fn update(&mut self) {
+        let size = self.emulator.size();
+        if self.size.get() != size {
+            // drop cells outside of the new screen
+            let old = self.size.get();
+            for y in 0 .. old.y {
+                for x in 0 .. old.x {
+                    if x >= size.x || y >= size.y {
+                        self.buf.remove(Point2::new(x, y));
+                    }
+                }
+            }
+            self.size.set(size);
+        }
+
+        for pos in self.emulator.take_dirty() {
+            match self.emulator.get(&pos) {
+                Some(atom) => self.buf.insert(pos, atom),
+                None => self.buf.remove(pos),
+            }
+        }
+
+        let cursor = self.emulator.get_cursor();
+        if self.cursor.get() != cursor {
+            self.cursor.set(cursor);
+        }
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Display Cursor
+struct PtyView {
+    buf: Arc<dyn IndexView<Point2<i16>, Item = TerminalAtom>>,
+    cursor: Arc<dyn SingletonView<Item = Option<TerminalCursor>>>,
+    size: Arc<dyn SingletonView<Item = Vector2<i16>>>,
+
+    old_cursor: Option<TerminalCursor>,
+    old_size: Vector2<i16>,
+
+    cast: Arc<RwLock<ObserverBroadcast<dyn TerminalView>>>,
+    proj_helper: ProjectionHelper<usize, Self>,
+}
+
+impl View for PtyView {
+    type Msg = IndexArea<Point2<i16>>;
+}
+
+impl IndexView<Point2<i16>> for PtyView {
+    type Item = TerminalAtom;
+
+    fn get(&self, pt: &Point2<i16>) -> Option<TerminalAtom> {
+        let atom = self.buf.get(pt);
+        match self.old_cursor {
+            Some(c) if c.pos == *pt => {
+                if c.shape == TerminalCursorShape::Block {
+                    if let Some(mut a) = atom {
+                        let bg_col = a.style.fg_color.unwrap_or((255, 255, 255));
+                        let fg_col = a.style.bg_color.unwrap_or((0, 0, 0));
+                        a.style.fg_color = Some(fg_col);
+                        a.style.bg_color = Some(bg_col);
+                        Some(a.with_cursor(c.shape))
+                    } else {
+                        Some(TerminalAtom::new(
+                            ' ',
+                            TerminalStyle::bg_color((255, 255, 255)),
+                        ).with_cursor(c.shape))
+                    }
+                } else {
+                    Some(atom.unwrap_or(TerminalAtom::from(' ')).with_cursor(c.shape))
+                }
+            }
+            _ => atom
+        }
+    }
+
+    fn area(&self) -> IndexArea<Point2<i16>> {
+        IndexArea::Range(
+            Point2::new(0, 0) ..= Point2::new(self.old_size.x - 1, self.old_size.y - 1)
+        )
+    }
+}
+
+impl PtyView {
+    fn new(
+        buf_port: OuterViewPort<dyn IndexView<Point2<i16>, Item = TerminalAtom>>,
+        cursor_port: OuterViewPort<dyn SingletonView<Item = Option<TerminalCursor>>>,
+        size_port: OuterViewPort<dyn SingletonView<Item = Vector2<i16>>>,
+
+        out_port: InnerViewPort<dyn TerminalView>,
+    ) -> Arc<RwLock<Self>> {
+        let mut proj_helper = ProjectionHelper::new(out_port.0.update_hooks.clone());
+        let proj = Arc::new(RwLock::new(PtyView {
+            old_cursor: None,
+            old_size: Vector2::new(0, 0),
+
+            cursor: proj_helper.new_singleton_arg(0, cursor_port, |s: &mut Self, _msg| {
+                if let Some(c) = s.old_cursor {
+                    s.cast.notify(&IndexArea::Set(vec![ c.pos ]));
+                }
+                s.old_cursor = s.cursor.get();
+                if let Some(c) = s.old_cursor {
+                    s.cast.notify(&IndexArea::Set(vec![ c.pos ]));
+                }
+            }),
+
+            size: proj_helper.new_singleton_arg(1, size_port, |s: &mut Self, _msg| {
+                let new_size = s.size.get();
+                if s.old_size != new_size {
+                    let max = Point2::new(
+                        std::cmp::max(s.old_size.x, new_size.x) - 1,
+                        std::cmp::max(s.old_size.y, new_size.y) - 1,
+                    );
+                    s.old_size = new_size;
+                    s.cast.notify(&IndexArea::Range(Point2::new(0, 0) ..= max));
+                }
+            }),
+
+            buf: proj_helper.new_index_arg(2, buf_port, |s: &mut Self, area| {
+                s.cast.notify(area);
+            }),
+
+            cast: out_port.get_broadcast(),
+            proj_helper,
+        }));
+
+        {
+            let mut p = proj.write().unwrap();
+            p.proj_helper.set_proj(&proj);
+            p.old_cursor = p.cursor.get();
+            p.old_size = p.size.get();
+        }
+        out_port.set_view(Some(proj.clone()));
+
+        proj
+    }
+}
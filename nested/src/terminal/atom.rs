@@ -1,26 +1,150 @@
 use {
     super::TerminalStyle,
     serde::{Deserialize, Serialize},
+    unicode_segmentation::UnicodeSegmentation,
+    unicode_width::{UnicodeWidthChar, UnicodeWidthStr},
 };
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+/// Shape of the hardware cursor
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum TerminalCursorShape {
+    Block,
+    Underline,
+    Bar,
+}
+
+/// How many columns the content of a cell occupies
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum CellWidth {
+    Single,
+
+    /// East Asian wide chars & emoji, the next cell
+    /// has to be a `Continuation`
+    Double,
+
+    /// right half of a `Double` cell, not drawn on its own
+    Continuation,
+}
+
+impl Default for CellWidth {
+    fn default() -> Self {
+        CellWidth::Single
+    }
+}
+
+/// maximum number of chars following the base char of a grapheme cluster
+pub const MAX_COMBINING: usize = 7;
+
+/// Chars following the base char of a grapheme cluster
+/// (combining marks, variation selectors, zero-width joiners),
+/// stored inline so that atoms stay `Copy`.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub struct Combining {
+    chars: [char; MAX_COMBINING],
+    len: u8,
+}
+
+impl Combining {
+    /// returns false if there is no space left
+    pub fn push(&mut self, c: char) -> bool {
+        if (self.len as usize) < MAX_COMBINING {
+            self.chars[self.len as usize] = c;
+            self.len += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        self.chars[.. self.len as usize].iter().cloned()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
 pub struct TerminalAtom {
+    /// base char of the grapheme cluster
     pub c: Option<char>,
+
+    #[serde(default)]
+    pub combining: Combining,
+
+    #[serde(default)]
+    pub width: CellWidth,
+
     pub style: TerminalStyle,
+
+    /// marks the position of the active editing cursor,
+    /// the terminal backend will place the real cursor here
+    pub cursor: Option<TerminalCursorShape>,
 }
 
 impl TerminalAtom {
     pub fn new(c: char, style: TerminalStyle) -> Self {
-        TerminalAtom { c: Some(c), style }
+        TerminalAtom {
+            c: Some(c),
+            combining: Combining::default(),
+            width: char_width(c),
+            style,
+            cursor: None
+        }
     }
 
     pub fn new_bg(bg_color: (u8, u8, u8)) -> Self {
         TerminalAtom {
             c: None,
+            combining: Combining::default(),
+            width: CellWidth::Single,
             style: TerminalStyle::bg_color(bg_color),
+            cursor: None,
+        }
+    }
+
+    /// right half of a double-width atom
+    pub fn continuation(style: TerminalStyle) -> Self {
+        TerminalAtom {
+            c: None,
+            combining: Combining::default(),
+            width: CellWidth::Continuation,
+            style,
+            cursor: None,
         }
     }
 
+    /// Atom for one grapheme cluster.
+    /// Chars exceeding `MAX_COMBINING` are dropped.
+    pub fn from_grapheme(g: &str, style: TerminalStyle) -> Self {
+        let mut chars = g.chars();
+        let mut atom = TerminalAtom::from(chars.next());
+        atom.style = style;
+        for c in chars {
+            atom.combining.push(c);
+        }
+        if atom.c.is_some() {
+            atom.width = str_width(g);
+        }
+        atom
+    }
+
+    /// number of columns covered by this atom,
+    /// 0 for continuation cells
+    pub fn columns(&self) -> usize {
+        match self.width {
+            CellWidth::Single => 1,
+            CellWidth::Double => 2,
+            CellWidth::Continuation => 0,
+        }
+    }
+
+    /// the whole grapheme cluster
+    pub fn grapheme(&self) -> String {
+        self.c.into_iter().chain(self.combining.iter()).collect()
+    }
+
     pub fn add_style_front(mut self, style: TerminalStyle) -> Self {
         self.style = self.style.add(style);
         self
@@ -30,14 +154,16 @@ impl TerminalAtom {
         self.style = style.add(self.style);
         self
     }
+
+    pub fn with_cursor(mut self, shape: TerminalCursorShape) -> Self {
+        self.cursor = Some(shape);
+        self
+    }
 }
 
 impl From<char> for TerminalAtom {
     fn from(c: char) -> Self {
-        TerminalAtom {
-            c: Some(c),
-            style: TerminalStyle::default(),
-        }
+        TerminalAtom::new(c, TerminalStyle::default())
     }
 }
 
@@ -45,16 +171,57 @@ impl From<Option<char>> for TerminalAtom {
     fn from(c: Option<char>) -> Self {
         TerminalAtom {
             c,
+            combining: Combining::default(),
+            width: c.map(char_width).unwrap_or(CellWidth::Single),
             style: TerminalStyle::default(),
+            cursor: None,
         }
     }
 }
 
 impl From<&char> for TerminalAtom {
     fn from(c: &char) -> Self {
-        TerminalAtom {
-            c: Some(*c),
-            style: TerminalStyle::default(),
+        TerminalAtom::from(*c)
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+fn width_from_columns(n: usize) -> CellWidth {
+    if n >= 2 {
+        CellWidth::Double
+    } else {
+        CellWidth::Single
+    }
+}
+
+/// display width of a single char,
+/// zero-width and control chars still occupy one cell
+pub fn char_width(c: char) -> CellWidth {
+    width_from_columns(c.width().unwrap_or(1))
+}
+
+pub fn str_width(g: &str) -> CellWidth {
+    width_from_columns(g.width())
+}
+
+/// Split a string into grapheme clusters, each taking one atom,
+/// followed by a continuation atom for double-width clusters.
+pub fn atoms_from_str(s: &str, style: TerminalStyle) -> Vec<TerminalAtom> {
+    let mut atoms = Vec::new();
+    for g in s.graphemes(true) {
+        let atom = TerminalAtom::from_grapheme(g, style);
+        atoms.push(atom);
+        if atom.width == CellWidth::Double {
+            atoms.push(TerminalAtom::continuation(style));
         }
     }
+    atoms
+}
+
+/// number of columns `s` occupies when rendered with `atoms_from_str()`
+pub fn display_width(s: &str) -> usize {
+    s.graphemes(true)
+        .map(|g| TerminalAtom::from_grapheme(g, TerminalStyle::default()).columns())
+        .sum()
 }
@@ -0,0 +1,176 @@
+use std::fmt::{Formatter, Result};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// How many colors the terminal can display.
+/// Styles are always specified as RGB and get
+/// downsampled when they are written out.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorMode {
+    /// 24-bit RGB
+    TrueColor,
+
+    /// xterm 256-color palette
+    Ansi256,
+
+    /// the 8 basic colors and their bright variants
+    Ansi16,
+
+    /// no color escapes at all, only bold/italic/underline
+    NoColor,
+}
+
+/// default palette of the 16 basic colors (xterm)
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+impl ColorMode {
+    /// guess the capabilities of the terminal from the environment
+    pub fn detect() -> ColorMode {
+        ColorMode::from_env(
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref()
+        )
+    }
+
+    /// `NO_COLOR` set to anything non-empty disables colors
+    /// (see https://no-color.org), `COLORTERM=truecolor|24bit`
+    /// enables RGB, otherwise `TERM` decides.
+    pub fn from_env(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> ColorMode {
+        if no_color.map(|v| !v.is_empty()).unwrap_or(false) {
+            return ColorMode::NoColor;
+        }
+
+        if let Some("truecolor") | Some("24bit") = colorterm {
+            return ColorMode::TrueColor;
+        }
+
+        match term {
+            None | Some("") | Some("dumb") => ColorMode::NoColor,
+            Some(term) => {
+                if term.ends_with("-direct") || term.contains("truecolor") {
+                    ColorMode::TrueColor
+                } else if term.contains("256color") {
+                    ColorMode::Ansi256
+                } else {
+                    ColorMode::Ansi16
+                }
+            }
+        }
+    }
+
+    pub fn write_fg(&self, fmt: &mut Formatter, color: Option<(u8, u8, u8)>) -> Result {
+        match (self, color) {
+            (ColorMode::NoColor, _) => Ok(()),
+            (_, None) => write!(fmt, "{}", termion::color::Fg(termion::color::Reset)),
+            (ColorMode::TrueColor, Some((r, g, b))) => write!(fmt, "{}", termion::color::Fg(termion::color::Rgb(r, g, b))),
+            (ColorMode::Ansi256, Some(rgb)) => write!(fmt, "{}", termion::color::Fg(termion::color::AnsiValue(ansi_colours::ansi256_from_rgb(rgb)))),
+            (ColorMode::Ansi16, Some(rgb)) => {
+                let idx = ansi16_from_rgb(rgb);
+                write!(fmt, "\x1b[{}m", if idx < 8 { 30 + idx } else { 90 + idx - 8 })
+            }
+        }
+    }
+
+    pub fn write_bg(&self, fmt: &mut Formatter, color: Option<(u8, u8, u8)>) -> Result {
+        match (self, color) {
+            (ColorMode::NoColor, _) => Ok(()),
+            (_, None) => write!(fmt, "{}", termion::color::Bg(termion::color::Reset)),
+            (ColorMode::TrueColor, Some((r, g, b))) => write!(fmt, "{}", termion::color::Bg(termion::color::Rgb(r, g, b))),
+            (ColorMode::Ansi256, Some(rgb)) => write!(fmt, "{}", termion::color::Bg(termion::color::AnsiValue(ansi_colours::ansi256_from_rgb(rgb)))),
+            (ColorMode::Ansi16, Some(rgb)) => {
+                let idx = ansi16_from_rgb(rgb);
+                write!(fmt, "\x1b[{}m", if idx < 8 { 40 + idx } else { 100 + idx - 8 })
+            }
+        }
+    }
+
+    /// SGR 58/59, supported by most terminals which know curly underlines
+    pub fn write_underline_color(&self, fmt: &mut Formatter, color: Option<(u8, u8, u8)>) -> Result {
+        match (self, color) {
+            (ColorMode::NoColor, _) => Ok(()),
+            (_, None) => write!(fmt, "\x1b[59m"),
+            (ColorMode::TrueColor, Some((r, g, b))) => write!(fmt, "\x1b[58;2;{};{};{}m", r, g, b),
+            (ColorMode::Ansi256, Some(rgb)) => write!(fmt, "\x1b[58;5;{}m", ansi_colours::ansi256_from_rgb(rgb)),
+            (ColorMode::Ansi16, Some(rgb)) => write!(fmt, "\x1b[58;5;{}m", ansi16_from_rgb(rgb)),
+        }
+    }
+}
+
+/// index of the closest basic color
+pub fn ansi16_from_rgb((r, g, b): (u8, u8, u8)) -> u8 {
+    let dist = |(pr, pg, pb): &(u8, u8, u8)| {
+        let dr = r as i32 - *pr as i32;
+        let dg = g as i32 - *pg as i32;
+        let db = b as i32 - *pb as i32;
+        dr * dr + dg * dg + db * db
+    };
+
+    ANSI16_PALETTE.iter()
+        .enumerate()
+        .min_by_key(|(_, c)| dist(c))
+        .map(|(idx, _)| idx as u8)
+        .unwrap_or(0)
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// foreground escape of `rgb` in `mode`
+    struct Fg(ColorMode, (u8, u8, u8));
+
+    impl std::fmt::Display for Fg {
+        fn fmt(&self, fmt: &mut Formatter) -> Result {
+            self.0.write_fg(fmt, Some(self.1))
+        }
+    }
+
+    #[test]
+    fn env_detection() {
+        assert_eq!(ColorMode::from_env(Some("1"), Some("truecolor"), Some("xterm-256color")), ColorMode::NoColor);
+        assert_eq!(ColorMode::from_env(Some(""), None, Some("xterm")), ColorMode::Ansi16);
+        assert_eq!(ColorMode::from_env(None, Some("truecolor"), Some("dumb")), ColorMode::TrueColor);
+        assert_eq!(ColorMode::from_env(None, Some("24bit"), None), ColorMode::TrueColor);
+        assert_eq!(ColorMode::from_env(None, None, Some("xterm-direct")), ColorMode::TrueColor);
+        assert_eq!(ColorMode::from_env(None, None, Some("xterm-256color")), ColorMode::Ansi256);
+        assert_eq!(ColorMode::from_env(None, Some("yes"), Some("screen-256color")), ColorMode::Ansi256);
+        assert_eq!(ColorMode::from_env(None, None, Some("linux")), ColorMode::Ansi16);
+        assert_eq!(ColorMode::from_env(None, None, Some("dumb")), ColorMode::NoColor);
+        assert_eq!(ColorMode::from_env(None, None, Some("")), ColorMode::NoColor);
+        assert_eq!(ColorMode::from_env(None, None, None), ColorMode::NoColor);
+    }
+
+    #[test]
+    fn palette_colors_map_to_themselves() {
+        for (idx, rgb) in ANSI16_PALETTE.iter().enumerate() {
+            assert_eq!(ansi16_from_rgb(*rgb) as usize, idx);
+        }
+    }
+
+    #[test]
+    fn nearest_basic_color() {
+        assert_eq!(ansi16_from_rgb((10, 10, 10)), 0);
+        assert_eq!(ansi16_from_rgb((180, 20, 20)), 1);
+        assert_eq!(ansi16_from_rgb((250, 250, 240)), 15);
+        assert_eq!(ansi16_from_rgb((100, 100, 255)), 12);
+    }
+
+    #[test]
+    fn foreground_escapes() {
+        assert_eq!(Fg(ColorMode::TrueColor, (1, 2, 3)).to_string(), "\x1b[38;2;1;2;3m");
+        assert_eq!(Fg(ColorMode::Ansi256, (255, 0, 0)).to_string(), "\x1b[38;5;196m");
+        assert_eq!(Fg(ColorMode::Ansi256, (0, 0, 0)).to_string(), "\x1b[38;5;16m");
+        assert_eq!(Fg(ColorMode::Ansi256, (255, 255, 255)).to_string(), "\x1b[38;5;231m");
+        assert_eq!(Fg(ColorMode::Ansi16, (205, 0, 0)).to_string(), "\x1b[31m");
+        assert_eq!(Fg(ColorMode::Ansi16, (255, 255, 0)).to_string(), "\x1b[93m");
+        assert_eq!(Fg(ColorMode::NoColor, (255, 0, 0)).to_string(), "");
+    }
+}
@@ -7,15 +7,115 @@ use {
         projection::projection_helper::*,
     },
     crate::{terminal::{TerminalAtom, TerminalView}},
-    cgmath::Point2,
+    cgmath::{Point2, Vector2},
+    std::ops::RangeInclusive,
     std::sync::Arc,
     std::sync::RwLock,
 };
 
 //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
 
+/// handle to a layer of a `TerminalCompositor`,
+/// stays valid while other layers are added, removed or reordered
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LayerId(usize);
+
+struct Layer {
+    id: LayerId,
+    view: Arc<dyn TerminalView>,
+
+    /// position of the layer's origin in compositor coordinates
+    offset: Vector2<i16>,
+
+    /// only this rectangle (in compositor coordinates) of the layer is visible
+    clip: Option<RangeInclusive<Point2<i16>>>,
+
+    /// opaque layers hide everything below them inside their bounds,
+    /// transparent layers let lower layers shine through empty cells
+    /// and inherit their style
+    opaque: bool,
+}
+
+impl Layer {
+    /// area of the layer in compositor coordinates
+    fn area(&self) -> IndexArea<Point2<i16>> {
+        let offset = self.offset;
+        let area = self.view.area().map(|pt| *pt + offset);
+
+        if let Some(clip) = self.clip.as_ref() {
+            clip_area(area, clip)
+        } else {
+            area
+        }
+    }
+
+    fn contains(&self, pos: &Point2<i16>) -> bool {
+        if let Some(clip) = self.clip.as_ref() {
+            if !rect_contains(clip, pos) {
+                return false;
+            }
+            if self.opaque {
+                return true;
+            }
+        }
+
+        match self.area() {
+            IndexArea::Empty => false,
+            IndexArea::Full => true,
+            IndexArea::Range(r) => rect_contains(&r, pos),
+            IndexArea::Set(v) => v.contains(pos),
+        }
+    }
+
+    fn get(&self, pos: &Point2<i16>) -> Option<TerminalAtom> {
+        if self.contains(pos) {
+            self.view.get(&(*pos - self.offset))
+        } else {
+            None
+        }
+    }
+}
+
+fn rect_contains(r: &RangeInclusive<Point2<i16>>, pos: &Point2<i16>) -> bool {
+    pos.x >= r.start().x && pos.x <= r.end().x &&
+    pos.y >= r.start().y && pos.y <= r.end().y
+}
+
+fn clip_area(area: IndexArea<Point2<i16>>, clip: &RangeInclusive<Point2<i16>>) -> IndexArea<Point2<i16>> {
+    match area {
+        IndexArea::Empty => IndexArea::Empty,
+        IndexArea::Full => IndexArea::Range(clip.clone()),
+        IndexArea::Range(r) => {
+            let begin = Point2::new(
+                std::cmp::max(r.start().x, clip.start().x),
+                std::cmp::max(r.start().y, clip.start().y)
+            );
+            let end = Point2::new(
+                std::cmp::min(r.end().x, clip.end().x),
+                std::cmp::min(r.end().y, clip.end().y)
+            );
+
+            if begin.x <= end.x && begin.y <= end.y {
+                IndexArea::Range(begin ..= end)
+            } else {
+                IndexArea::Empty
+            }
+        }
+        IndexArea::Set(v) => IndexArea::Set(
+            v.into_iter().filter(|pt| rect_contains(clip, pt)).collect()
+        ),
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Stacks terminal views on top of each other.
+/// Layers are ordered from bottom to top and each one
+/// can be moved, clipped, raised or removed after it was pushed.
 pub struct TerminalCompositor {
-    layers: Vec<Arc<dyn TerminalView>>,
+    layers: Vec<Layer>,
+    next_id: usize,
+
     cast: Arc<RwLock<ObserverBroadcast<dyn TerminalView>>>,
     proj_helper: ProjectionHelper<usize, Self>,
 }
@@ -26,6 +126,7 @@ impl TerminalCompositor {
     pub fn new(port: InnerViewPort<dyn TerminalView>) -> Arc<RwLock<Self>> {
         let comp = Arc::new(RwLock::new(TerminalCompositor {
             layers: Vec::new(),
+            next_id: 0,
             cast: port.get_broadcast(),
             proj_helper: ProjectionHelper::new(port.0.update_hooks.clone()),
         }));
@@ -36,14 +137,108 @@ impl TerminalCompositor {
         comp
     }
 
-    pub fn push(&mut self, v: OuterViewPort<dyn TerminalView>) {
-        let idx = self.layers.len();
-        self.layers.push(
-            self.proj_helper
-                .new_index_arg(idx, v, |s: &mut Self, area| {
-                    s.cast.notify(area);
-                }),
-        );
+    /// add a transparent layer at the origin on top of all other layers
+    pub fn push(&mut self, v: OuterViewPort<dyn TerminalView>) -> LayerId {
+        let id = LayerId(self.next_id);
+        self.next_id += 1;
+
+        let view = self.proj_helper
+            .new_index_arg(id.0, v, move |s: &mut Self, area| {
+                if let Some(layer) = s.get_layer(id) {
+                    let offset = layer.offset;
+                    let area = area.map(|pt| *pt + offset);
+                    let area = if let Some(clip) = layer.clip.as_ref() {
+                        clip_area(area, clip)
+                    } else {
+                        area
+                    };
+                    s.cast.notify(&area);
+                }
+            });
+
+        self.layers.push(Layer {
+            id,
+            view,
+            offset: Vector2::new(0, 0),
+            clip: None,
+            opaque: false,
+        });
+
+        self.cast.notify(&self.layers.last().unwrap().area());
+        id
+    }
+
+    pub fn remove(&mut self, id: LayerId) {
+        if let Some(idx) = self.get_layer_idx(id) {
+            let area = self.layer_bounds(idx);
+            self.layers.remove(idx);
+            self.proj_helper.remove_arg(&id.0);
+            self.cast.notify(&area);
+        }
+    }
+
+    /// move layer to the top
+    pub fn raise(&mut self, id: LayerId) {
+        if let Some(idx) = self.get_layer_idx(id) {
+            let layer = self.layers.remove(idx);
+            self.layers.push(layer);
+            self.cast.notify(&self.layer_bounds(self.layers.len() - 1));
+        }
+    }
+
+    /// move layer to the bottom
+    pub fn lower(&mut self, id: LayerId) {
+        if let Some(idx) = self.get_layer_idx(id) {
+            let layer = self.layers.remove(idx);
+            self.layers.insert(0, layer);
+            self.cast.notify(&self.layer_bounds(0));
+        }
+    }
+
+    pub fn set_offset(&mut self, id: LayerId, offset: Vector2<i16>) {
+        self.update_layer(id, |layer| layer.offset = offset);
+    }
+
+    pub fn set_clip(&mut self, id: LayerId, clip: Option<RangeInclusive<Point2<i16>>>) {
+        self.update_layer(id, |layer| layer.clip = clip);
+    }
+
+    pub fn set_opaque(&mut self, id: LayerId, opaque: bool) {
+        self.update_layer(id, |layer| layer.opaque = opaque);
+    }
+
+    /// topmost layer which is visible at `pos`
+    pub fn layer_at(&self, pos: &Point2<i16>) -> Option<LayerId> {
+        self.layers.iter().rev()
+            .find(|layer| layer.get(pos).is_some() || (layer.opaque && layer.contains(pos)))
+            .map(|layer| layer.id)
+    }
+
+    fn get_layer(&self, id: LayerId) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.id == id)
+    }
+
+    fn get_layer_idx(&self, id: LayerId) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.id == id)
+    }
+
+    /// area covered by a layer, including the
+    /// empty cells of opaque layers
+    fn layer_bounds(&self, idx: usize) -> IndexArea<Point2<i16>> {
+        let layer = &self.layers[idx];
+        match (layer.opaque, layer.clip.as_ref()) {
+            (true, Some(clip)) => IndexArea::Range(clip.clone()),
+            _ => layer.area()
+        }
+    }
+
+    fn update_layer(&mut self, id: LayerId, f: impl FnOnce(&mut Layer)) {
+        if let Some(idx) = self.get_layer_idx(id) {
+            let old_area = self.layer_bounds(idx);
+            f(&mut self.layers[idx]);
+            let new_area = self.layer_bounds(idx);
+            self.cast.notify(&old_area.union(new_area));
+        }
     }
 }
 
@@ -60,10 +255,16 @@ impl IndexView<Point2<i16>> for TerminalCompositor {
         let mut atom = None;
 
         for layer in self.layers.iter() {
-            match (atom, layer.get(pos)) {
-                (None, next) => atom = next,
-                (Some(last), Some(next)) => atom = Some(next.add_style_back(last.style)),
-                _ => {}
+            if layer.opaque {
+                if layer.contains(pos) {
+                    atom = Some(layer.get(pos).unwrap_or(TerminalAtom::from(' ')));
+                }
+            } else {
+                match (atom, layer.get(pos)) {
+                    (None, next) => atom = next,
+                    (Some(last), Some(next)) => atom = Some(next.add_style_back(last.style)),
+                    _ => {}
+                }
             }
         }
 
@@ -73,8 +274,8 @@ impl IndexView<Point2<i16>> for TerminalCompositor {
     fn area(&self) -> IndexArea<Point2<i16>> {
         let mut area = IndexArea::Empty;
 
-        for layer in self.layers.iter() {
-            area = area.union(layer.area());
+        for idx in 0..self.layers.len() {
+            area = area.union(self.layer_bounds(idx));
         }
 
         area
@@ -1,23 +1,43 @@
 pub mod ansi_parser;
 pub mod atom;
+pub mod color;
 pub mod compositor;
+pub mod protocol;
+pub mod remote;
+pub mod screen;
 pub mod style;
 pub mod terminal;
+pub mod theme;
 pub mod widgets;
 
 pub use {
-    atom::TerminalAtom,
-    compositor::TerminalCompositor,
-    style::TerminalStyle,
-    terminal::{Terminal, TerminalEvent},
+    atom::{TerminalAtom, TerminalCursorShape, CellWidth, atoms_from_str, display_width},
+    color::ColorMode,
+    compositor::{TerminalCompositor, LayerId},
+    screen::ScreenBuffer,
+    style::{TerminalStyle, StyleTransition, UnderlineStyle},
+    terminal::{Terminal, TerminalCursor, TerminalEvent},
+    theme::Theme,
 };
 
-use r3vi::view::grid::*;
+use r3vi::view::{grid::*, index::*};
 
 //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
 
 pub trait TerminalView = GridView<Item = TerminalAtom>;
 
+/// size of the smallest rectangle starting at (0,0) that covers `area`
+pub fn area_extent(area: &IndexArea<Point2<i16>>) -> Vector2<i16> {
+    match area {
+        IndexArea::Range(r) => Vector2::new(r.end().x + 1, r.end().y + 1),
+        IndexArea::Set(v) => v.iter().fold(
+            Vector2::new(0, 0),
+            |e, p| Vector2::new(std::cmp::max(e.x, p.x + 1), std::cmp::max(e.y, p.y + 1))
+        ),
+        _ => Vector2::new(0, 0),
+    }
+}
+
 //<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
 
 pub enum TerminalEditorResult {
@@ -37,15 +57,15 @@ use {
         view::{OuterViewPort},
         buffer::vec::*,
     },
-    cgmath::Point2,
+    cgmath::{Point2, Vector2},
+    std::sync::{Arc, RwLock},
 };
 
 pub fn make_label(s: &str) -> OuterViewPort<dyn TerminalView> {
-    let label = VecBuffer::with_data(s.chars().collect());
+    let label = VecBuffer::with_data(atoms_from_str(s, TerminalStyle::default()));
 
     let v = label.get_port()
         .to_sequence()
-        .map(|c| TerminalAtom::from(c))
         .to_index()
         .map_key(
             |idx| Point2::new(*idx as i16, 0),
@@ -59,6 +79,9 @@ pub trait TerminalProjections {
     fn with_style(&self, style: TerminalStyle) -> OuterViewPort<dyn TerminalView>;
     fn with_fg_color(&self, col: (u8, u8, u8)) -> OuterViewPort<dyn TerminalView>;
     fn with_bg_color(&self, col: (u8, u8, u8)) -> OuterViewPort<dyn TerminalView>;
+
+    /// apply the style of a theme role, looked up whenever an atom is rendered
+    fn with_theme_style(&self, theme: Arc<RwLock<Theme>>, role: fn(&Theme) -> TerminalStyle) -> OuterViewPort<dyn TerminalView>;
 }
 
 impl TerminalProjections for OuterViewPort<dyn TerminalView> {
@@ -76,5 +99,12 @@ impl TerminalProjections for OuterViewPort<dyn TerminalView> {
     fn with_bg_color(&self, col: (u8, u8, u8)) -> OuterViewPort<dyn TerminalView> {
         self.with_style(TerminalStyle::bg_color(col))
     }
+
+    fn with_theme_style(&self, theme: Arc<RwLock<Theme>>, role: fn(&Theme) -> TerminalStyle) -> OuterViewPort<dyn TerminalView> {
+        self.map_item(
+            move |_idx, a|
+            a.add_style_front(role(&theme.read().unwrap()))
+        )
+    }
 }
 
@@ -0,0 +1,166 @@
+//! Atom stream protocol between display clients and the display server.
+//!
+//! A client sends the contents of its `TerminalView` as a stream
+//! of bincode encoded values (default bincode options, i.e.
+//! little endian with fixed-size integers):
+//!
+//! 1. a `Header`: the magic bytes `NTRM` followed by the
+//!    protocol version as `u16`
+//!
+//! 2. any number of atom updates `(Point2<i16>, Option<TerminalAtom>)`:
+//!    the atom at the given cell changed, `None` clears the cell
+//!
+//! The stream ends when the connection is closed.
+//!
+//! Versions:
+//! - `1`: header and atom updates as described above.
+//!   Streams written before the header was introduced
+//!   (bare atom updates) are not accepted anymore.
+
+use {
+    r3vi::view::{Observer, index::*, grid::*},
+    crate::terminal::{TerminalAtom, TerminalView},
+    cgmath::Point2,
+    serde::{Deserialize, Serialize},
+    std::{
+        io::{Read, Write},
+        sync::Arc,
+    },
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+pub const MAGIC: [u8; 4] = *b"NTRM";
+pub const PROTOCOL_VERSION: u16 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Header {
+    pub magic: [u8; 4],
+    pub version: u16,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Header {
+            magic: MAGIC,
+            version: PROTOCOL_VERSION,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Decode(bincode::Error),
+    BadMagic([u8; 4]),
+    UnsupportedVersion(u16),
+    Rejected(String),
+    UnexpectedMessage,
+}
+
+impl std::fmt::Display for ProtocolError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProtocolError::Decode(err) => write!(fmt, "decoding error: {}", err),
+            ProtocolError::BadMagic(magic) => write!(fmt, "not an atom stream (magic {:?})", magic),
+            ProtocolError::UnsupportedVersion(v) => write!(
+                fmt,
+                "unsupported protocol version {} (expected {})",
+                v, PROTOCOL_VERSION
+            ),
+            ProtocolError::Rejected(reason) => write!(fmt, "rejected by peer: {}", reason),
+            ProtocolError::UnexpectedMessage => write!(fmt, "unexpected message"),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<bincode::Error> for ProtocolError {
+    fn from(err: bincode::Error) -> Self {
+        ProtocolError::Decode(err)
+    }
+}
+
+impl From<std::io::Error> for ProtocolError {
+    fn from(err: std::io::Error) -> Self {
+        ProtocolError::Decode(err.into())
+    }
+}
+
+pub fn write_header(out: &mut impl Write) -> bincode::Result<()> {
+    bincode::serialize_into(out, &Header::default())
+}
+
+/// read and check the header of an atom stream
+pub fn read_header(input: &mut impl Read) -> Result<Header, ProtocolError> {
+    let header: Header = bincode::deserialize_from(input)?;
+    if header.magic != MAGIC {
+        Err(ProtocolError::BadMagic(header.magic))
+    } else if header.version != PROTOCOL_VERSION {
+        Err(ProtocolError::UnsupportedVersion(header.version))
+    } else {
+        Ok(header)
+    }
+}
+
+pub fn write_atom(out: &mut impl Write, pos: Point2<i16>, atom: Option<TerminalAtom>) -> bincode::Result<()> {
+    bincode::serialize_into(out, &(pos, atom))
+}
+
+pub fn read_atom(input: &mut impl Read) -> bincode::Result<(Point2<i16>, Option<TerminalAtom>)> {
+    bincode::deserialize_from(input)
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Observes a `TerminalView` and writes every change
+/// as atom update, starting with the header.
+pub struct AtomStreamWriter<W: Write> {
+    out: W,
+    view: Option<Arc<dyn TerminalView>>,
+}
+
+impl<W: Write> AtomStreamWriter<W> {
+    pub fn new(mut out: W) -> bincode::Result<Self> {
+        write_header(&mut out)?;
+        Ok(AtomStreamWriter { out, view: None })
+    }
+
+    fn write_area(&mut self, area: &IndexArea<Point2<i16>>) -> bincode::Result<()> {
+        if let Some(view) = self.view.clone() {
+            match area {
+                IndexArea::Empty => {}
+                IndexArea::Full => {
+                    // avoid looping on views without bounded area
+                    if let IndexArea::Full = view.area() {
+                    } else {
+                        self.write_area(&view.area())?;
+                    }
+                }
+                IndexArea::Range(range) => {
+                    for pos in GridWindowIterator::from(range.clone()) {
+                        write_atom(&mut self.out, pos, view.get(&pos))?;
+                    }
+                }
+                IndexArea::Set(positions) => {
+                    for pos in positions.iter() {
+                        write_atom(&mut self.out, *pos, view.get(pos))?;
+                    }
+                }
+            }
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Send + Sync> Observer<dyn TerminalView> for AtomStreamWriter<W> {
+    fn reset(&mut self, view: Option<Arc<dyn TerminalView>>) {
+        self.view = view;
+        self.write_area(&IndexArea::Full).ok();
+    }
+
+    fn notify(&mut self, area: &IndexArea<Point2<i16>>) {
+        self.write_area(area).ok();
+    }
+}
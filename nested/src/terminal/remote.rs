@@ -0,0 +1,448 @@
+//! Remote display sessions.
+//!
+//! An editor runs headless as `RemoteServer` and publishes its
+//! `TerminalView`; any number of thin clients (`RemoteClient`)
+//! attach over a unix or tcp socket, show the view and send
+//! their terminal events back.
+//!
+//! All messages are bincode encoded (default options). Messages
+//! above `MAX_CLIENT_MESSAGE` resp. `MAX_SERVER_MESSAGE` bytes
+//! are refused and end the connection.
+//!
+//! 1. the client opens with `ClientMessage::Hello`, containing
+//!    the magic bytes `NTRM` and the range of versions it speaks
+//!
+//! 2. the server answers with `ServerMessage::Welcome` carrying
+//!    the highest common version, or with `ServerMessage::Reject`
+//!    and closes the connection
+//!
+//! 3. then the server sends `ServerMessage::Diff`s, starting with
+//!    the complete view, and the client sends `ClientMessage::Event`s,
+//!    including a `TerminalEvent::Resize` whenever its size changes,
+//!    until either side closes the connection.
+//!
+//! A `Diff` is keyed by an `IndexArea`: it carries one `Option<TerminalAtom>`
+//! for each position of the area, ranges enumerated row by row.
+//!
+//! There is no authentication: whoever can connect sees the view
+//! and may send events. Unix sockets are protected by their file
+//! permissions, tcp should only be used on trusted networks.
+//!
+//! Versions:
+//! - `1`: handshake, diffs and events as described above.
+
+use {
+    r3vi::{
+        view::{
+            channel::{queue_channel, ChannelReceiver, ChannelSender},
+            Observer, OuterViewPort,
+            grid::*,
+            index::*,
+        },
+        buffer::index_hashmap::*,
+    },
+    crate::terminal::{protocol::{ProtocolError, MAGIC}, TerminalAtom, TerminalEvent, TerminalView},
+    async_std::stream::StreamExt,
+    cgmath::Point2,
+    bincode::Options,
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream, ToSocketAddrs},
+        os::unix::net::{UnixListener, UnixStream},
+        path::Path,
+        collections::HashSet,
+        sync::{Arc, Condvar, Mutex, RwLock},
+    },
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+pub const REMOTE_MIN_VERSION: u16 = 1;
+pub const REMOTE_VERSION: u16 = 1;
+
+/// largest message a server accepts
+pub const MAX_CLIENT_MESSAGE: u64 = 64 * 1024;
+
+/// largest message a client accepts, enough for the
+/// complete view of any reasonable terminal size
+pub const MAX_SERVER_MESSAGE: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum DiffArea {
+    Set(Vec<Point2<i16>>),
+
+    /// inclusive
+    Range(Point2<i16>, Point2<i16>),
+}
+
+impl DiffArea {
+    /// `None` for areas that can not be enumerated
+    pub fn from_area(area: &IndexArea<Point2<i16>>) -> Option<Self> {
+        match area {
+            IndexArea::Set(v) => Some(DiffArea::Set(v.clone())),
+            IndexArea::Range(r) => Some(DiffArea::Range(*r.start(), *r.end())),
+            _ => None,
+        }
+    }
+
+    pub fn positions(&self) -> Vec<Point2<i16>> {
+        match self {
+            DiffArea::Set(v) => v.clone(),
+            DiffArea::Range(start, end) => GridWindowIterator::from(*start ..= *end).collect(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum ClientMessage {
+    Hello {
+        magic: [u8; 4],
+        min_version: u16,
+        max_version: u16,
+    },
+    Event(TerminalEvent),
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub enum ServerMessage {
+    Welcome { version: u16 },
+    Reject(String),
+    Diff {
+        area: DiffArea,
+        atoms: Vec<Option<TerminalAtom>>,
+    },
+}
+
+/// highest version both sides speak
+pub fn negotiate_version(min_version: u16, max_version: u16) -> Option<u16> {
+    let version = std::cmp::min(max_version, REMOTE_VERSION);
+    if version >= std::cmp::max(min_version, REMOTE_MIN_VERSION) {
+        Some(version)
+    } else {
+        None
+    }
+}
+
+/// decode one message with the default options,
+/// refusing it if it would exceed `limit` bytes
+fn read_message<T: DeserializeOwned>(reader: &mut impl Read, limit: u64) -> bincode::Result<T> {
+    bincode::options()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(limit)
+        .deserialize_from(reader)
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Changes not yet sent to a client
+#[derive(Default)]
+struct PendingDiff {
+    view: Option<Arc<dyn TerminalView>>,
+    full: bool,
+    positions: HashSet<Point2<i16>>,
+    closed: bool,
+}
+
+impl PendingDiff {
+    fn is_empty(&self) -> bool {
+        !self.full && self.positions.is_empty()
+    }
+
+    /// area to send next, `None` if there is nothing to send
+    fn take_area(&mut self) -> Option<(Arc<dyn TerminalView>, DiffArea)> {
+        let full = std::mem::take(&mut self.full);
+        let positions: Vec<_> = self.positions.drain().collect();
+        let view = self.view.clone()?;
+        let area = match full {
+            true => DiffArea::from_area(&view.area()),
+            false => None,
+        };
+        Some((view, area.unwrap_or(DiffArea::Set(positions))))
+    }
+}
+
+/// Sends every change of the view to one client.
+/// Notifications only mark cells as pending, a separate thread
+/// reads and writes them, so a slow client does not hold up the
+/// editor. Cells changing again before they are written are
+/// merged into one diff, nothing is dropped.
+struct DiffWriter {
+    pending: Arc<(Mutex<PendingDiff>, Condvar)>,
+}
+
+impl DiffWriter {
+    fn new<W: Write + Send + 'static>(mut out: W) -> Self {
+        let pending = Arc::new((Mutex::new(PendingDiff::default()), Condvar::new()));
+
+        // ends when the writer is dropped or the client disconnects
+        std::thread::spawn({
+            let pending = pending.clone();
+            move || loop {
+                let next = {
+                    let (lock, cond) = &*pending;
+                    let mut pending = lock.lock().unwrap();
+                    while pending.is_empty() && !pending.closed {
+                        pending = cond.wait(pending).unwrap();
+                    }
+                    if pending.closed {
+                        break;
+                    }
+                    pending.take_area()
+                };
+
+                if let Some((view, area)) = next {
+                    let atoms = area.positions().iter().map(|pos| view.get(pos)).collect();
+                    let msg = ServerMessage::Diff { area, atoms };
+                    if bincode::serialize_into(&mut out, &msg).is_err() || out.flush().is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        DiffWriter { pending }
+    }
+
+    fn mark(&self, update: impl FnOnce(&mut PendingDiff)) {
+        let (lock, cond) = &*self.pending;
+        update(&mut lock.lock().unwrap());
+        cond.notify_one();
+    }
+}
+
+impl Drop for DiffWriter {
+    fn drop(&mut self) {
+        self.mark(|pending| pending.closed = true);
+    }
+}
+
+impl Observer<dyn TerminalView> for DiffWriter {
+    fn reset(&mut self, view: Option<Arc<dyn TerminalView>>) {
+        self.mark(|pending| {
+            pending.view = view;
+            pending.full = true;
+        });
+    }
+
+    fn notify(&mut self, area: &IndexArea<Point2<i16>>) {
+        self.mark(|pending| match area {
+            IndexArea::Empty => {}
+            IndexArea::Full => pending.full = true,
+            IndexArea::Range(range) => pending.positions.extend(GridWindowIterator::from(range.clone())),
+            IndexArea::Set(positions) => pending.positions.extend(positions.iter().cloned()),
+        });
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Publishes a `TerminalView` to remote clients
+/// and collects their events.
+pub struct RemoteServer {
+    view: OuterViewPort<dyn TerminalView>,
+    event_tx: ChannelSender<Vec<TerminalEvent>>,
+    events: ChannelReceiver<Vec<TerminalEvent>>,
+}
+
+impl RemoteServer {
+    pub fn new(view: OuterViewPort<dyn TerminalView>) -> Self {
+        let (event_tx, events) = queue_channel();
+        RemoteServer { view, event_tx, events }
+    }
+
+    /// serve one client in a background thread
+    pub fn attach<R, W>(&self, reader: R, writer: W)
+    where
+        R: Read + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        let view = self.view.clone();
+        let event_tx = self.event_tx.clone();
+        std::thread::spawn(move || {
+            if let Err(err) = serve_client(view, event_tx, reader, writer) {
+                eprintln!("remote client: {}", err);
+            }
+        });
+    }
+
+    /// accept clients on a unix socket
+    pub fn listen_unix(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let listener = UnixListener::bind(path)?;
+        let view = self.view.clone();
+        let event_tx = self.event_tx.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Ok(reader) = stream.try_clone() {
+                    let (view, event_tx) = (view.clone(), event_tx.clone());
+                    std::thread::spawn(move || serve_client(view, event_tx, reader, stream).ok());
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// accept clients on a tcp socket.
+    /// Clients are not authenticated, so only bind
+    /// to addresses reachable from trusted networks.
+    pub fn listen_tcp(&self, addr: impl ToSocketAddrs) -> std::io::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        let view = self.view.clone();
+        let event_tx = self.event_tx.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                stream.set_nodelay(true).ok();
+                if let Ok(reader) = stream.try_clone() {
+                    let (view, event_tx) = (view.clone(), event_tx.clone());
+                    std::thread::spawn(move || serve_client(view, event_tx, reader, stream).ok());
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// next event of any attached client
+    pub async fn next_event(&mut self) -> TerminalEvent {
+        self.events.next().await.unwrap()
+    }
+}
+
+fn serve_client<R: Read, W: Write + Send + 'static>(
+    view: OuterViewPort<dyn TerminalView>,
+    event_tx: ChannelSender<Vec<TerminalEvent>>,
+    mut reader: R,
+    mut writer: W,
+) -> Result<(), ProtocolError> {
+    let version = match read_message(&mut reader, MAX_CLIENT_MESSAGE)? {
+        ClientMessage::Hello { magic, min_version, max_version } => {
+            if magic != MAGIC {
+                Err(ProtocolError::BadMagic(magic))
+            } else {
+                negotiate_version(min_version, max_version)
+                    .ok_or(ProtocolError::UnsupportedVersion(max_version))
+            }
+        }
+        ClientMessage::Event(_) => Err(ProtocolError::UnexpectedMessage),
+    };
+
+    let version = match version {
+        Ok(version) => version,
+        Err(err) => {
+            bincode::serialize_into(&mut writer, &ServerMessage::Reject(err.to_string())).ok();
+            return Err(err);
+        }
+    };
+
+    bincode::serialize_into(&mut writer, &ServerMessage::Welcome { version })?;
+    writer.flush()?;
+
+    // observers are only referenced weakly by the port,
+    // so the client gets detached when this function returns
+    let diff_writer = Arc::new(RwLock::new(DiffWriter::new(writer)));
+    view.add_observer(diff_writer.clone());
+
+    while let Ok(msg) = read_message(&mut reader, MAX_CLIENT_MESSAGE) {
+        match msg {
+            ClientMessage::Event(event) => event_tx.send(event),
+            ClientMessage::Hello { .. } => {}
+        }
+    }
+
+    drop(diff_writer);
+    Ok(())
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Shows the view of a `RemoteServer`.
+pub struct RemoteClient {
+    version: u16,
+    out: Mutex<Box<dyn Write + Send>>,
+    _buf: Arc<RwLock<IndexBuffer<Point2<i16>, TerminalAtom>>>,
+    view: OuterViewPort<dyn TerminalView>,
+}
+
+impl RemoteClient {
+    /// perform the handshake and start receiving diffs
+    pub fn connect<R, W>(mut reader: R, mut writer: W) -> Result<Self, ProtocolError>
+    where
+        R: Read + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        bincode::serialize_into(&mut writer, &ClientMessage::Hello {
+            magic: MAGIC,
+            min_version: REMOTE_MIN_VERSION,
+            max_version: REMOTE_VERSION,
+        })?;
+        writer.flush()?;
+
+        let version = match read_message(&mut reader, MAX_SERVER_MESSAGE)? {
+            ServerMessage::Welcome { version } => version,
+            ServerMessage::Reject(reason) => return Err(ProtocolError::Rejected(reason)),
+            ServerMessage::Diff { .. } => return Err(ProtocolError::UnexpectedMessage),
+        };
+
+        let buf = IndexBuffer::new();
+        let view = buf.get_port();
+        let buf = Arc::new(RwLock::new(buf));
+
+        std::thread::spawn({
+            let buf = buf.clone();
+            move || {
+                while let Ok(ServerMessage::Diff { area, atoms }) = read_message(&mut reader, MAX_SERVER_MESSAGE) {
+                    let mut buf = buf.write().unwrap();
+                    for (pos, atom) in area.positions().into_iter().zip(atoms.into_iter()) {
+                        match atom {
+                            Some(atom) => buf.insert(pos, atom),
+                            None => buf.remove(pos),
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(RemoteClient {
+            version,
+            out: Mutex::new(Box::new(writer)),
+            _buf: buf,
+            view,
+        })
+    }
+
+    pub fn connect_unix(path: impl AsRef<Path>) -> Result<Self, ProtocolError> {
+        let stream = UnixStream::connect(path)?;
+        let reader = stream.try_clone()?;
+        RemoteClient::connect(reader, stream)
+    }
+
+    pub fn connect_tcp(addr: impl ToSocketAddrs) -> Result<Self, ProtocolError> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true).ok();
+        let reader = stream.try_clone()?;
+        RemoteClient::connect(reader, stream)
+    }
+
+    /// negotiated protocol version
+    pub fn get_version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn get_view(&self) -> OuterViewPort<dyn TerminalView> {
+        self.view.clone()
+    }
+
+    /// current content of a cell, as far as received
+    pub fn get(&self, pos: &Point2<i16>) -> Option<TerminalAtom> {
+        self.view.get_view().get(pos)
+    }
+
+    pub fn send_event(&self, event: &TerminalEvent) -> bincode::Result<()> {
+        let mut out = self.out.lock().unwrap();
+        bincode::serialize_into(&mut *out, &ClientMessage::Event(event.clone()))?;
+        out.flush()?;
+        Ok(())
+    }
+}
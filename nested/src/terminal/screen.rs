@@ -0,0 +1,129 @@
+use {
+    crate::terminal::{CellWidth, TerminalAtom},
+    cgmath::{Point2, Vector2},
+    std::collections::BTreeSet,
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Fixed-size grid of atoms as they are (or will be) visible on screen.
+/// `TermOutWriter` keeps two of these: the *front* buffer mirrors
+/// what was last written to the terminal, the *back* buffer collects
+/// the next frame. Only cells that differ between both get written.
+#[derive(Clone)]
+pub struct ScreenBuffer {
+    size: Vector2<i16>,
+    cells: Vec<Option<TerminalAtom>>,
+
+    /// `(y, x)` of all cells carrying a cursor
+    cursors: BTreeSet<(i16, i16)>,
+}
+
+impl ScreenBuffer {
+    pub fn new(size: Vector2<i16>) -> Self {
+        ScreenBuffer {
+            size,
+            cells: vec![None; (size.x.max(0) as usize) * (size.y.max(0) as usize)],
+            cursors: BTreeSet::new(),
+        }
+    }
+
+    pub fn size(&self) -> Vector2<i16> {
+        self.size
+    }
+
+    /// resize and clear all cells
+    pub fn resize(&mut self, size: Vector2<i16>) {
+        *self = ScreenBuffer::new(size);
+    }
+
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            *cell = None;
+        }
+        self.cursors.clear();
+    }
+
+    pub fn contains(&self, pos: &Point2<i16>) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.size.x && pos.y < self.size.y
+    }
+
+    fn idx(&self, pos: &Point2<i16>) -> Option<usize> {
+        if self.contains(pos) {
+            Some(pos.y as usize * self.size.x as usize + pos.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: &Point2<i16>) -> Option<TerminalAtom> {
+        self.cells[self.idx(pos)?]
+    }
+
+    pub fn set(&mut self, pos: &Point2<i16>, atom: Option<TerminalAtom>) {
+        if let Some(idx) = self.idx(pos) {
+            self.cells[idx] = atom;
+            if atom.map(|a| a.cursor.is_some()).unwrap_or(false) {
+                self.cursors.insert((pos.y, pos.x));
+            } else {
+                self.cursors.remove(&(pos.y, pos.x));
+            }
+        }
+    }
+
+    /// The cell whose cursor is shown if several atoms carry one:
+    /// the first in reading order, so it does not depend on the
+    /// order in which cells were updated.
+    pub fn cursor_pos(&self) -> Option<Point2<i16>> {
+        self.cursors.iter().next().map(|(y, x)| Point2::new(*x, *y))
+    }
+
+    /// Compare `self` (back) against `front` at the given positions
+    /// and return all positions that need to be redrawn,
+    /// sorted in row-major order without duplicates.
+    /// Both halves of a double-width cell are redrawn together.
+    pub fn diff(&self, front: &ScreenBuffer, dirty: impl IntoIterator<Item = Point2<i16>>) -> Vec<Point2<i16>> {
+        let mut changed = Vec::new();
+        for pos in dirty {
+            if self.contains(&pos) && self.get(&pos) != front.get(&pos) {
+                changed.push(pos);
+
+                for atom in [self.get(&pos), front.get(&pos)].iter().flatten() {
+                    let neighbour = match atom.width {
+                        CellWidth::Double => pos + Vector2::new(1, 0),
+                        CellWidth::Continuation => pos - Vector2::new(1, 0),
+                        CellWidth::Single => continue,
+                    };
+                    if self.contains(&neighbour) {
+                        changed.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        changed.sort_by(|a, b| (a.y, a.x).cmp(&(b.y, b.x)));
+        changed.dedup();
+        changed
+    }
+
+    /// Group sorted positions into horizontal runs `(start, length)`.
+    /// Positions separated by a gap of at most `max_gap` unchanged cells
+    /// are merged into the same run, since rewriting a few cells
+    /// is cheaper than a cursor jump.
+    pub fn runs(changed: &[Point2<i16>], max_gap: i16) -> Vec<(Point2<i16>, i16)> {
+        let mut runs: Vec<(Point2<i16>, i16)> = Vec::new();
+
+        for pos in changed {
+            if let Some((start, len)) = runs.last_mut() {
+                let end = start.x + *len;
+                if start.y == pos.y && pos.x >= end && pos.x - end <= max_gap {
+                    *len = pos.x - start.x + 1;
+                    continue;
+                }
+            }
+            runs.push((*pos, 1));
+        }
+
+        runs
+    }
+}
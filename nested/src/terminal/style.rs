@@ -1,12 +1,44 @@
-use serde::{Deserialize, Serialize};
+use {
+    super::ColorMode,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum UnderlineStyle {
+    Single,
+    Double,
+    Curly,
+}
+
+impl UnderlineStyle {
+    /// SGR parameter (with colon sub-parameter for the extended styles)
+    fn sgr(&self) -> &'static str {
+        match self {
+            UnderlineStyle::Single => "4",
+            UnderlineStyle::Double => "4:2",
+            UnderlineStyle::Curly => "4:3",
+        }
+    }
+}
 
 #[derive(Default, Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
 pub struct TerminalStyle {
     pub fg_color: Option<(u8, u8, u8)>,
     pub bg_color: Option<(u8, u8, u8)>,
     pub bold: Option<bool>,
+    pub dim: Option<bool>,
     pub italic: Option<bool>,
     pub underline: Option<bool>,
+
+    /// shape of the underline, `Single` if unset
+    pub underline_style: Option<UnderlineStyle>,
+
+    /// color of the underline, the foreground color if unset
+    pub underline_color: Option<(u8, u8, u8)>,
+
+    pub inverse: Option<bool>,
+    pub strikethrough: Option<bool>,
 }
 
 impl TerminalStyle {
@@ -20,12 +52,27 @@ impl TerminalStyle {
         if dominant.bold == None {
             dominant.bold = self.bold;
         }
+        if dominant.dim == None {
+            dominant.dim = self.dim;
+        }
         if dominant.italic == None {
             dominant.italic = self.italic;
         }
         if dominant.underline == None {
             dominant.underline = self.underline;
         }
+        if dominant.underline_style == None {
+            dominant.underline_style = self.underline_style;
+        }
+        if dominant.underline_color == None {
+            dominant.underline_color = self.underline_color;
+        }
+        if dominant.inverse == None {
+            dominant.inverse = self.inverse;
+        }
+        if dominant.strikethrough == None {
+            dominant.strikethrough = self.strikethrough;
+        }
         dominant
     }
 
@@ -47,6 +94,12 @@ impl TerminalStyle {
         style
     }
 
+    pub fn dim(d: bool) -> Self {
+        let mut style = TerminalStyle::default();
+        style.dim = Some(d);
+        style
+    }
+
     pub fn italic(i: bool) -> Self {
         let mut style = TerminalStyle::default();
         style.italic = Some(i);
@@ -58,30 +111,157 @@ impl TerminalStyle {
         style.underline = Some(u);
         style
     }
+
+    /// enables the underline with the given shape
+    pub fn underline_style(s: UnderlineStyle) -> Self {
+        let mut style = TerminalStyle::default();
+        style.underline = Some(true);
+        style.underline_style = Some(s);
+        style
+    }
+
+    pub fn underline_color(rgb: (u8, u8, u8)) -> Self {
+        let mut style = TerminalStyle::default();
+        style.underline_color = Some(rgb);
+        style
+    }
+
+    pub fn inverse(i: bool) -> Self {
+        let mut style = TerminalStyle::default();
+        style.inverse = Some(i);
+        style
+    }
+
+    pub fn strikethrough(s: bool) -> Self {
+        let mut style = TerminalStyle::default();
+        style.strikethrough = Some(s);
+        style
+    }
+
+    fn is_bold(&self) -> bool {
+        self.bold.unwrap_or(false)
+    }
+
+    fn is_dim(&self) -> bool {
+        self.dim.unwrap_or(false)
+    }
+
+    /// shape of the underline if it is enabled
+    fn get_underline(&self) -> Option<UnderlineStyle> {
+        if self.underline.unwrap_or(false) {
+            Some(self.underline_style.unwrap_or(UnderlineStyle::Single))
+        } else {
+            None
+        }
+    }
 }
 
+/// All attributes of the style, with colors downsampled
+/// for the terminal as detected from the environment.
 impl std::fmt::Display for TerminalStyle {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self.fg_color {
-            Some((r, g, b)) => write!(fmt, "{}", termion::color::Fg(termion::color::Rgb(r, g, b)))?,
-            None => write!(fmt, "{}", termion::color::Fg(termion::color::Reset))?,
-        };
-        match self.bg_color {
-            Some((r, g, b)) => write!(fmt, "{}", termion::color::Bg(termion::color::Rgb(r, g, b)))?,
-            None => write!(fmt, "{}", termion::color::Bg(termion::color::Reset))?,
-        };
-        match self.bold {
-            Some(true) => write!(fmt, "{}", termion::style::Bold)?,
-            _ => write!(fmt, "{}", termion::style::NoBold)?,
-        };
+        let mode = ColorMode::detect();
+        mode.write_fg(fmt, self.fg_color)?;
+        mode.write_bg(fmt, self.bg_color)?;
+        mode.write_underline_color(fmt, self.underline_color)?;
+
+        // SGR 22 resets both bold and dim,
+        // SGR 21 means double-underline on many terminals
+        write!(fmt, "{}", termion::style::NoFaint)?;
+        if self.is_bold() {
+            write!(fmt, "{}", termion::style::Bold)?;
+        }
+        if self.is_dim() {
+            write!(fmt, "{}", termion::style::Faint)?;
+        }
         match self.italic {
             Some(true) => write!(fmt, "{}", termion::style::Italic)?,
             _ => write!(fmt, "{}", termion::style::NoItalic)?,
         };
-        match self.underline {
-            Some(true) => write!(fmt, "{}", termion::style::Underline)?,
-            _ => write!(fmt, "{}", termion::style::NoUnderline)?,
+        match self.get_underline() {
+            Some(u) => write!(fmt, "\x1b[{}m", u.sgr())?,
+            None => write!(fmt, "{}", termion::style::NoUnderline)?,
         };
+        match self.inverse {
+            Some(true) => write!(fmt, "{}", termion::style::Invert)?,
+            _ => write!(fmt, "{}", termion::style::NoInvert)?,
+        };
+        match self.strikethrough {
+            Some(true) => write!(fmt, "{}", termion::style::CrossedOut)?,
+            _ => write!(fmt, "{}", termion::style::NoCrossedOut)?,
+        };
+        Ok(())
+    }
+}
+
+/// Minimal sequence of SGR escapes to switch the terminal
+/// from one style to another, without a full reset.
+/// Colors are downsampled according to `mode`.
+pub struct StyleTransition<'a> {
+    pub from: &'a TerminalStyle,
+    pub to: &'a TerminalStyle,
+    pub mode: ColorMode,
+}
+
+impl<'a> StyleTransition<'a> {
+    pub fn new(from: &'a TerminalStyle, to: &'a TerminalStyle, mode: ColorMode) -> Self {
+        StyleTransition { from, to, mode }
+    }
+}
+
+impl<'a> std::fmt::Display for StyleTransition<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.from.fg_color != self.to.fg_color {
+            self.mode.write_fg(fmt, self.to.fg_color)?;
+        }
+        if self.from.bg_color != self.to.bg_color {
+            self.mode.write_bg(fmt, self.to.bg_color)?;
+        }
+        if self.from.underline_color != self.to.underline_color {
+            self.mode.write_underline_color(fmt, self.to.underline_color)?;
+        }
+        if (self.from.is_bold() && !self.to.is_bold()) || (self.from.is_dim() && !self.to.is_dim()) {
+            // there is no separate reset for bold and dim,
+            // SGR 22 turns off both
+            write!(fmt, "{}", termion::style::NoFaint)?;
+            if self.to.is_bold() {
+                write!(fmt, "{}", termion::style::Bold)?;
+            }
+            if self.to.is_dim() {
+                write!(fmt, "{}", termion::style::Faint)?;
+            }
+        } else {
+            if !self.from.is_bold() && self.to.is_bold() {
+                write!(fmt, "{}", termion::style::Bold)?;
+            }
+            if !self.from.is_dim() && self.to.is_dim() {
+                write!(fmt, "{}", termion::style::Faint)?;
+            }
+        }
+        if self.from.italic.unwrap_or(false) != self.to.italic.unwrap_or(false) {
+            match self.to.italic {
+                Some(true) => write!(fmt, "{}", termion::style::Italic)?,
+                _ => write!(fmt, "{}", termion::style::NoItalic)?,
+            };
+        }
+        if self.from.get_underline() != self.to.get_underline() {
+            match self.to.get_underline() {
+                Some(u) => write!(fmt, "\x1b[{}m", u.sgr())?,
+                None => write!(fmt, "{}", termion::style::NoUnderline)?,
+            };
+        }
+        if self.from.inverse.unwrap_or(false) != self.to.inverse.unwrap_or(false) {
+            match self.to.inverse {
+                Some(true) => write!(fmt, "{}", termion::style::Invert)?,
+                _ => write!(fmt, "{}", termion::style::NoInvert)?,
+            };
+        }
+        if self.from.strikethrough.unwrap_or(false) != self.to.strikethrough.unwrap_or(false) {
+            match self.to.strikethrough {
+                Some(true) => write!(fmt, "{}", termion::style::CrossedOut)?,
+                _ => write!(fmt, "{}", termion::style::NoCrossedOut)?,
+            };
+        }
         Ok(())
     }
 }
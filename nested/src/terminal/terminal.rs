@@ -5,11 +5,14 @@ use {
             Observer, OuterViewPort,
             grid::*,
             index::*,
-        }
+            singleton::*,
+        },
+        buffer::singleton::*,
     },
-    super::{TerminalStyle, TerminalView},
+    super::{CellWidth, ColorMode, ScreenBuffer, StyleTransition, TerminalCursorShape, TerminalStyle, TerminalView},
     async_std::{stream::StreamExt, task},
     cgmath::{Point2, Vector2},
+    serde::{Deserialize, Serialize},
     signal_hook,
     signal_hook_async_std::Signals,
     std::sync::RwLock,
@@ -24,15 +27,22 @@ use {
     },
 };
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub enum TerminalEvent {
     Resize(Vector2<i16>),
     Input(termion::event::Event),
 }
 
+/// screen position & shape of the active editing cursor
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TerminalCursor {
+    pub pos: Point2<i16>,
+    pub shape: TerminalCursorShape,
+}
+
 pub struct Terminal {
     writer: Arc<TermOutWriter>,
-    _observer: Arc<RwLock<TermOutObserver>>,
+    observer: Arc<RwLock<TermOutObserver>>,
 
     events: ChannelReceiver<Vec<TerminalEvent>>,
     _signal_handle: signal_hook_async_std::Handle,
@@ -46,6 +56,10 @@ impl Terminal {
             out: RwLock::new(MouseTerminal::from(stdout().into_raw_mode().unwrap())),
             dirty_pos_rx,
             view: port.get_view_arc(),
+            front: RwLock::new(ScreenBuffer::new(Vector2::new(0, 0))),
+            back: RwLock::new(ScreenBuffer::new(Vector2::new(0, 0))),
+            cursor: RwLock::new(SingletonBuffer::new(None)),
+            color_mode: RwLock::new(ColorMode::detect()),
         });
 
         let observer = Arc::new(RwLock::new(TermOutObserver {
@@ -87,16 +101,40 @@ impl Terminal {
 
         Terminal {
             writer,
-            _observer: observer,
+            observer,
             events: event_rx,
             _signal_handle: handle,
         }
     }
 
+    /// repaint the whole screen, e.g. after the theme changed
+    pub fn redraw(&self) {
+        self.observer.write().unwrap().send_area(IndexArea::Full);
+    }
+
+    pub fn get_color_mode(&self) -> ColorMode {
+        *self.writer.color_mode.read().unwrap()
+    }
+
+    /// override the detected color capabilities and repaint
+    pub fn set_color_mode(&self, mode: ColorMode) {
+        *self.writer.color_mode.write().unwrap() = mode;
+
+        // invalidate the front buffer so that every cell gets rewritten
+        self.writer.front.write().unwrap().resize(Vector2::new(0, 0));
+        self.redraw();
+    }
+
     pub fn get_writer(&self) -> Arc<TermOutWriter> {
         self.writer.clone()
     }
 
+    /// screen position of the active editing cursor,
+    /// i.e. of the atom marked with `TerminalAtom::with_cursor()`
+    pub fn get_cursor_port(&self) -> OuterViewPort<dyn SingletonView<Item = Option<TerminalCursor>>> {
+        self.writer.cursor.read().unwrap().get_port()
+    }
+
     pub async fn next_event(&mut self) -> TerminalEvent {
         self.events.next().await.unwrap()
     }
@@ -146,16 +184,35 @@ impl Observer<dyn TerminalView> for TermOutObserver {
     }
 }
 
+/// gap of unchanged cells up to which two changed runs
+/// on the same line are merged into one write
+const MAX_RUN_GAP: i16 = 4;
+
 pub struct TermOutWriter {
     out: RwLock<MouseTerminal<termion::raw::RawTerminal<std::io::Stdout>>>,
     dirty_pos_rx: ChannelReceiver<HashSet<Point2<i16>>>,
     view: Arc<RwLock<Option<Arc<dyn TerminalView>>>>,
+
+    /// what is currently visible on the terminal
+    front: RwLock<ScreenBuffer>,
+
+    /// the next frame, as read from the view
+    back: RwLock<ScreenBuffer>,
+
+    /// position of the hardware cursor
+    cursor: RwLock<SingletonBuffer<Option<TerminalCursor>>>,
+
+    /// how colors get downsampled when written out
+    color_mode: RwLock<ColorMode>,
 }
 
 impl TermOutWriter {
     fn reset(&self) {
         let mut out = self.out.write().unwrap();
-        write!(out, "{}", termion::clear::All).ok();
+        write!(out, "{}{}{}", termion::style::Reset, termion::clear::All, termion::cursor::Hide).ok();
+        self.front.write().unwrap().clear();
+        self.back.write().unwrap().clear();
+        self.cursor.write().unwrap().set(None);
     }
 }
 
@@ -175,48 +232,123 @@ impl TermOutWriter {
 
         // draw atoms until view port is destroyed
         while let Some(dirty_pos) = self.dirty_pos_rx.recv().await {
-            let (w, _h) = termion::terminal_size().unwrap();
+            let (w, h) = termion::terminal_size().unwrap();
+            let size = Vector2::new(w as i16, h as i16);
 
             if let Some(view) = self.view.read().unwrap().as_ref() {
                 let mut out = self.out.write().unwrap();
+                let mut front = self.front.write().unwrap();
+                let mut back = self.back.write().unwrap();
+
+                let dirty_pos: Vec<Point2<i16>> =
+                    if front.size() != size {
+                        // terminal got resized, redraw everything
+                        front.resize(size);
+                        back.resize(size);
+
+                        write!(out, "{}{}", termion::style::Reset, termion::clear::All)?;
+                        cur_style = TerminalStyle::default();
+                        cur_pos = Point2::new(-1, -1);
 
-                let d = dirty_pos
-                    .into_iter()
-                    .filter(|p| p.x >= 0 && p.y >= 0 && p.x < w as i16 && p.y < w as i16); //.collect::<Vec<_>>();
-                                                                                           /*
-                                                                                                           d.sort_by(|a,b| {
-                                                                                                               if a.y < b.y {
-                                                                                                                   std::cmp::Ordering::Less
-                                                                                                               } else if a.y == b.y {
-                                                                                                                   a.x.cmp(&b.x)
-                                                                                                               } else {
-                                                                                                                   std::cmp::Ordering::Greater
-                                                                                                               }
-                                                                                                           });
-                                                                                           */
-                for pos in d {
-                    if pos != cur_pos {
+                        GridWindowIterator::from(Point2::new(0, 0)..Point2::new(size.x, size.y)).collect()
+                    } else {
+                        dirty_pos.into_iter().collect()
+                    };
+
+                let color_mode = *self.color_mode.read().unwrap();
+                let old_cursor = self.cursor.read().unwrap().get();
+
+                for pos in dirty_pos.iter() {
+                    back.set(pos, view.get(pos));
+                }
+
+                let new_cursor = back.cursor_pos().and_then(|pos| {
+                    Some(TerminalCursor { pos, shape: back.get(&pos)?.cursor? })
+                });
+
+                let changed = back.diff(&front, dirty_pos);
+
+                for (start, len) in ScreenBuffer::runs(&changed, MAX_RUN_GAP) {
+                    if start != cur_pos {
                         write!(
                             out,
                             "{}",
-                            termion::cursor::Goto(pos.x as u16 + 1, pos.y as u16 + 1)
+                            termion::cursor::Goto(start.x as u16 + 1, start.y as u16 + 1)
                         )?;
                     }
 
-                    if let Some(atom) = view.get(&pos) {
-                        if cur_style != atom.style {
-                            cur_style = atom.style;
-                            write!(out, "{}", termion::style::Reset)?;
-                            write!(out, "{}", atom.style)?;
+                    let mut x = start.x;
+                    while x < start.x + len {
+                        let pos = Point2::new(x, start.y);
+                        let atom = back.get(&pos);
+                        let style = atom.map(|a| a.style).unwrap_or_default();
+
+                        if cur_style != style {
+                            write!(out, "{}", StyleTransition::new(&cur_style, &style, color_mode))?;
+                            cur_style = style;
                         }
 
-                        write!(out, "{}", atom.c.unwrap_or(' '))?;
-                    } else {
-                        write!(out, "{} ", termion::style::Reset)?;
-                        cur_style = TerminalStyle::default();
+                        let next = Point2::new(x + 1, start.y);
+                        let next_is_half = back.get(&next).map(|n| n.width == CellWidth::Continuation).unwrap_or(false);
+
+                        match atom {
+                            // wide atoms need their right half on the screen,
+                            // otherwise they are blanked like orphaned halves
+                            Some(a) if a.width == CellWidth::Double && next_is_half => {
+                                write!(out, "{}", a.c.unwrap_or(' '))?;
+                                for c in a.combining.iter() {
+                                    write!(out, "{}", c)?;
+                                }
+
+                                front.set(&pos, atom);
+                                front.set(&next, back.get(&next));
+                                x += 2;
+                            }
+
+                            // narrow atoms and orphaned halves
+                            Some(a) if a.width == CellWidth::Single => {
+                                write!(out, "{}", a.c.unwrap_or(' '))?;
+                                for c in a.combining.iter() {
+                                    write!(out, "{}", c)?;
+                                }
+                                front.set(&pos, atom);
+                                x += 1;
+                            }
+                            _ => {
+                                write!(out, " ")?;
+                                front.set(&pos, atom);
+                                x += 1;
+                            }
+                        }
+                    }
+
+                    cur_pos = Point2::new(x, start.y);
+                }
+
+                match new_cursor {
+                    Some(c) => {
+                        if c.pos != cur_pos {
+                            write!(out, "{}", termion::cursor::Goto(c.pos.x as u16 + 1, c.pos.y as u16 + 1))?;
+                            cur_pos = c.pos;
+                        }
+                        if old_cursor.map(|c| c.shape) != Some(c.shape) {
+                            match c.shape {
+                                TerminalCursorShape::Block => write!(out, "{}", termion::cursor::SteadyBlock)?,
+                                TerminalCursorShape::Underline => write!(out, "{}", termion::cursor::SteadyUnderline)?,
+                                TerminalCursorShape::Bar => write!(out, "{}", termion::cursor::SteadyBar)?,
+                            }
+                            write!(out, "{}", termion::cursor::Show)?;
+                        }
                     }
+                    None => {
+                        if old_cursor.is_some() {
+                            write!(out, "{}", termion::cursor::Hide)?;
+                        }
+                    }
+                }
 
-                    cur_pos = pos + Vector2::new(1, 0);
+                if new_cursor != old_cursor {
+                    self.cursor.write().unwrap().set(new_cursor);
                 }
 
                 out.flush()?;
@@ -225,7 +357,7 @@ impl TermOutWriter {
 
         // restore conventional terminal settings
         let mut out = self.out.write().unwrap();
-        write!(out, "{}", termion::cursor::Show)?;
+        write!(out, "{}{}{}", termion::style::Reset, termion::cursor::SteadyBlock, termion::cursor::Show)?;
         out.flush()?;
 
         std::io::Result::Ok(())
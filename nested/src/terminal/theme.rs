@@ -0,0 +1,151 @@
+use {
+    crate::terminal::TerminalStyle,
+    serde::{Deserialize, Serialize},
+    std::path::Path,
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Styles for the semantic roles of rendered elements.
+/// Renderers look up their colors here instead of hard-coding them,
+/// so the theme can be loaded from a file and switched at runtime
+/// (see `Context::set_theme()`).
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Theme {
+    /// label of error messages
+    pub error: TerminalStyle,
+    /// label of warning messages
+    pub warning: TerminalStyle,
+    /// label of todo messages
+    pub todo: TerminalStyle,
+    /// label of info messages
+    pub info: TerminalStyle,
+    /// text of diagnostic messages
+    pub message: TerminalStyle,
+    /// highlighted part inside a message text
+    pub message_highlight: TerminalStyle,
+    /// background of messages concerning the node under the cursor
+    pub message_active: TerminalStyle,
+
+    /// fixed labels of product editors
+    pub keyword: TerminalStyle,
+    /// type names of empty product fields
+    pub placeholder: TerminalStyle,
+    /// char literals
+    pub literal: TerminalStyle,
+    /// valid digits
+    pub number: TerminalStyle,
+    /// chars that are invalid at their position
+    pub invalid: TerminalStyle,
+
+    /// insert cursor
+    pub cursor: TerminalStyle,
+    /// selected items
+    pub selection: TerminalStyle,
+
+    /// separators in the cursor widget
+    pub punctuation: TerminalStyle,
+    /// indices of the cursor address
+    pub address: TerminalStyle,
+    /// cursor mode (INSERT / SELECT)
+    pub mode: TerminalStyle,
+    /// type of the node under the cursor
+    pub type_name: TerminalStyle,
+    /// disabled or empty status information
+    pub inactive: TerminalStyle,
+
+    /// title of the active tab
+    pub tab_active: TerminalStyle,
+    /// titles of all other tabs
+    pub tab_inactive: TerminalStyle,
+
+    /// background depending on the distance to the cursor,
+    /// `depth_bg[0]` is the selected node itself
+    pub depth_bg: Vec<TerminalStyle>,
+
+    /// foreground of the root node
+    pub root_fg: TerminalStyle,
+    /// foreground depending on the nesting depth, repeats cyclically
+    pub depth_fg: Vec<TerminalStyle>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            error: TerminalStyle::fg_color((200,0,0)).add(TerminalStyle::bold(true)),
+            warning: TerminalStyle::fg_color((200,200,0)).add(TerminalStyle::bold(true)),
+            todo: TerminalStyle::fg_color((180,180,250)).add(TerminalStyle::bold(true)),
+            info: TerminalStyle::fg_color((180,180,250)).add(TerminalStyle::bold(true)),
+            message: TerminalStyle::fg_color((180,180,180)),
+            message_highlight: TerminalStyle::fg_color((140,140,250)),
+            message_active: TerminalStyle::bg_color((40,40,40)),
+
+            keyword: TerminalStyle::italic(true),
+            placeholder: TerminalStyle::fg_color((215,140,95)),
+            literal: TerminalStyle::fg_color((220,200,20)),
+            number: TerminalStyle::fg_color((90,160,90)),
+            invalid: TerminalStyle::fg_color((200,40,40)),
+
+            cursor: TerminalStyle::fg_color((150,80,230)).add(TerminalStyle::bold(true)),
+            selection: TerminalStyle::bg_color((60,60,120)),
+
+            punctuation: TerminalStyle::fg_color((150,80,230)),
+            address: TerminalStyle::fg_color((0,100,20)),
+            mode: TerminalStyle::fg_color((200,200,20)),
+            type_name: TerminalStyle::fg_color((100,120,232)),
+            inactive: TerminalStyle::fg_color((120,120,120)),
+
+            tab_active: TerminalStyle::bg_color((150,80,230)).add(TerminalStyle::bold(true)),
+            tab_inactive: TerminalStyle::bg_color((44,44,44)).add(TerminalStyle::fg_color((160,160,160))),
+
+            depth_bg: vec![
+                TerminalStyle::bg_color((150,80,230)),
+                TerminalStyle::bg_color((66,66,66)),
+                TerminalStyle::bg_color((44,44,44)),
+                TerminalStyle::bg_color((33,33,33)),
+                TerminalStyle::bg_color((28,28,28)),
+                TerminalStyle::bg_color((21,21,21)),
+            ],
+
+            root_fg: TerminalStyle::fg_color((200,200,200)),
+            depth_fg: vec![
+                TerminalStyle::fg_color((128,106,97)),
+                TerminalStyle::fg_color((100,120,232)),
+                TerminalStyle::fg_color((180,100,96)),
+                TerminalStyle::fg_color((188,155,18)),
+                TerminalStyle::fg_color((135,182,134)),
+            ],
+        }
+    }
+}
+
+impl Theme {
+    /// read a theme from a JSON file,
+    /// roles missing in the file keep their default style
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Theme> {
+        let file = std::fs::File::open(path)?;
+        serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn bg_from_depth(&self, depth: usize) -> TerminalStyle {
+        self.depth_bg.get(depth).cloned().unwrap_or_default()
+    }
+
+    pub fn fg_from_depth(&self, depth: usize) -> TerminalStyle {
+        if depth == 0 {
+            self.root_fg
+        } else if self.depth_fg.len() > 0 {
+            self.depth_fg[depth % self.depth_fg.len()]
+        } else {
+            TerminalStyle::default()
+        }
+    }
+}
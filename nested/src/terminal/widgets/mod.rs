@@ -1,3 +1,62 @@
 
 pub mod ascii_box;
+pub mod monstera;
+pub mod plot;
+pub mod pty;
+pub mod split;
+pub mod status;
+pub mod tabs;
 
+pub use {
+    ascii_box::AsciiBox,
+    monstera::make_monstera,
+    plot::Plot,
+    pty::PtyWidget,
+    split::{SplitPane, SplitOrientation, PaneSize},
+    status::StatusLine,
+    tabs::TabbedPane,
+};
+
+use {
+    crate::terminal::TerminalEvent,
+    termion::event::{Event, MouseEvent},
+    cgmath::{Point2, Vector2},
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// zero-based position of a mouse event
+pub fn mouse_pos(event: &TerminalEvent) -> Option<Point2<i16>> {
+    match event {
+        TerminalEvent::Input(Event::Mouse(MouseEvent::Press(_, x, y))) |
+        TerminalEvent::Input(Event::Mouse(MouseEvent::Release(x, y))) |
+        TerminalEvent::Input(Event::Mouse(MouseEvent::Hold(x, y))) =>
+            Some(Point2::new(*x as i16 - 1, *y as i16 - 1)),
+        _ => None
+    }
+}
+
+/// move mouse events into the coordinate system
+/// of a child view placed at `offset`
+pub fn translate_mouse(event: &TerminalEvent, offset: Vector2<i16>) -> TerminalEvent {
+    let shift = |x: &u16, y: &u16| (
+        std::cmp::max(1, *x as i16 - offset.x) as u16,
+        std::cmp::max(1, *y as i16 - offset.y) as u16
+    );
+
+    match event {
+        TerminalEvent::Input(Event::Mouse(MouseEvent::Press(b, x, y))) => {
+            let (x, y) = shift(x, y);
+            TerminalEvent::Input(Event::Mouse(MouseEvent::Press(*b, x, y)))
+        }
+        TerminalEvent::Input(Event::Mouse(MouseEvent::Release(x, y))) => {
+            let (x, y) = shift(x, y);
+            TerminalEvent::Input(Event::Mouse(MouseEvent::Release(x, y)))
+        }
+        TerminalEvent::Input(Event::Mouse(MouseEvent::Hold(x, y))) => {
+            let (x, y) = shift(x, y);
+            TerminalEvent::Input(Event::Mouse(MouseEvent::Hold(x, y)))
+        }
+        event => event.clone()
+    }
+}
@@ -1,15 +1,15 @@
 use {
-    cgmath::Point2,
-    nested::{
-        core::{OuterViewPort, ViewPort},
+    r3vi::{
+        view::{OuterViewPort, sequence::*},
+        buffer::vec::*,
+    },
+    crate::{
         terminal::{make_label, TerminalStyle, TerminalView},
-        vec::VecBuffer,
     },
 };
 
 pub fn make_monstera() -> OuterViewPort<dyn TerminalView> {
-    let monstera_lines_port = ViewPort::new();
-    let _monstera_lines = VecBuffer::with_data(
+    VecBuffer::with_data(
         vec![
             make_label("                   |"),
             make_label("                   |"),
@@ -34,18 +34,11 @@ pub fn make_monstera() -> OuterViewPort<dyn TerminalView> {
             make_label("             \\_ .  |   . _/"),
             make_label("                 \\ | /"),
             make_label("                   ."),
-        ],
-        monstera_lines_port.inner(),
-    );
-
-    monstera_lines_port
-        .outer()
+        ]
+    )
+        .get_port()
         .to_sequence()
-        .to_index()
-        .map_key(
-            |idx| Point2::new(0 as i16, *idx as i16),
-            |pt| if pt.x == 0 { Some(pt.y as usize) } else { None },
-        )
+        .to_grid_vertical()
         .flatten()
         .map_item(|_p, at| at.add_style_back(TerminalStyle::fg_color((0, 100, 10))))
 }
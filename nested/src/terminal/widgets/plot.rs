@@ -1,10 +1,14 @@
 use {
     cgmath::Point2,
-    nested::{
-        core::{InnerViewPort, Observer, ObserverBroadcast, OuterViewPort, View},
-        sequence::{SequenceView},
-        index::{IndexArea, IndexView},
-        projection::ProjectionHelper,
+    r3vi::{
+        view::{
+            InnerViewPort, Observer, ObserverBroadcast, OuterViewPort, View,
+            index::*,
+            sequence::*,
+        },
+        projection::projection_helper::*,
+    },
+    crate::{
         terminal::{TerminalAtom, TerminalView},
     },
     std::sync::{Arc, RwLock},
@@ -0,0 +1,283 @@
+use {
+    r3vi::view::{ViewPort, OuterViewPort},
+    crate::terminal::{
+        TerminalEditor, TerminalEditorResult, TerminalEvent, TerminalView,
+        ansi_parser::{EmulatorView, TerminalModes},
+    },
+    termion::event::{Event, Key, MouseButton, MouseEvent},
+    cgmath::Vector2,
+    std::{
+        fs::File,
+        io::{Read, Write},
+        os::unix::{io::{AsRawFd, FromRawFd}, process::CommandExt},
+        process::{Child, Command, ExitStatus, Stdio},
+        sync::{Arc, RwLock},
+    },
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Runs a command on a pseudo-terminal and shows its screen.
+/// Output of the child goes through the terminal emulator into the view,
+/// keyboard & mouse events are encoded and written back to the child.
+pub struct PtyWidget {
+    master: File,
+    child: Child,
+    size: Vector2<i16>,
+
+    emulator: Arc<RwLock<EmulatorView>>,
+    port: ViewPort<dyn TerminalView>,
+}
+
+fn winsize(size: Vector2<i16>) -> libc::winsize {
+    libc::winsize {
+        ws_row: std::cmp::max(1, size.y) as u16,
+        ws_col: std::cmp::max(1, size.x) as u16,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+impl PtyWidget {
+    /// spawn `cmd` on a new pseudo-terminal of the given size
+    pub fn new(mut cmd: Command, size: Vector2<i16>) -> std::io::Result<Self> {
+        let mut master: libc::c_int = -1;
+        let mut slave: libc::c_int = -1;
+        let mut ws = winsize(size);
+
+        if unsafe {
+            libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), &mut ws)
+        } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let master = unsafe { File::from_raw_fd(master) };
+        let slave = unsafe { File::from_raw_fd(slave) };
+
+        // keep the pty out of the children, which would otherwise
+        // hold the master open or get a stray copy of the slave
+        for fd in [master.as_raw_fd(), slave.as_raw_fd()].iter() {
+            if unsafe { libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) } < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+
+        cmd.env("TERM", "xterm-256color")
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+
+        unsafe {
+            cmd.pre_exec(|| {
+                // become session leader and make the pty
+                // (our stdin by now) the controlling terminal
+                if libc::setsid() < 0 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let child = cmd.spawn()?;
+        // the parent's copies of the slave fd are closed along with `cmd`
+        drop(cmd);
+
+        let port = ViewPort::new();
+        let emulator = Arc::new(RwLock::new(EmulatorView::new(size, port.inner())));
+
+        std::thread::spawn({
+            let emulator = emulator.clone();
+            let mut reader = master.try_clone()?;
+            let mut writer = master.try_clone()?;
+
+            move || {
+                let mut buf = [0; 4096];
+                loop {
+                    match reader.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => {
+                            let responses = {
+                                let mut emulator = emulator.write().unwrap();
+                                emulator.process(&buf[..n]);
+                                emulator.take_responses()
+                            };
+
+                            if !responses.is_empty() {
+                                writer.write_all(&responses).ok();
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(PtyWidget {
+            master,
+            child,
+            size,
+            emulator,
+            port,
+        })
+    }
+
+    /// run `program` with `args`
+    pub fn spawn(program: &str, args: &[&str], size: Vector2<i16>) -> std::io::Result<Self> {
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        PtyWidget::new(cmd, size)
+    }
+
+    pub fn resize(&mut self, size: Vector2<i16>) {
+        if self.size != size {
+            self.size = size;
+            self.emulator.write().unwrap().resize(size);
+
+            let ws = winsize(size);
+            unsafe {
+                libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ as _, &ws);
+            }
+        }
+    }
+
+    /// send raw bytes to the child
+    pub fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.master.write_all(bytes)?;
+        self.master.flush()
+    }
+
+    pub fn get_title(&self) -> String {
+        self.emulator.read().unwrap().get_emulator().get_title().into()
+    }
+
+    /// exit status, if the child has terminated
+    pub fn try_wait(&mut self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().flatten()
+    }
+
+    pub fn kill(&mut self) -> std::io::Result<()> {
+        self.child.kill()
+    }
+
+    fn get_modes(&self) -> TerminalModes {
+        self.emulator.read().unwrap().get_emulator().get_modes()
+    }
+}
+
+impl Drop for PtyWidget {
+    fn drop(&mut self) {
+        if self.try_wait().is_none() {
+            self.child.kill().ok();
+            self.child.wait().ok();
+        }
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// byte sequence an xterm would send for `key`
+fn encode_key(key: &Key, modes: &TerminalModes) -> Vec<u8> {
+    let cursor = |c: char| {
+        if modes.application_cursor {
+            format!("\x1bO{}", c)
+        } else {
+            format!("\x1b[{}", c)
+        }.into_bytes()
+    };
+
+    match key {
+        Key::Char('\n') => vec![ b'\r' ],
+        Key::Char(c) => c.to_string().into_bytes(),
+        Key::Alt(c) => format!("\x1b{}", c).into_bytes(),
+        Key::Ctrl(c) => vec![ (*c as u8) & 0x1f ],
+        Key::Backspace => vec![ 0x7f ],
+        Key::Esc => vec![ 0x1b ],
+        Key::Null => vec![ 0 ],
+        Key::BackTab => b"\x1b[Z".to_vec(),
+
+        Key::Up => cursor('A'),
+        Key::Down => cursor('B'),
+        Key::Right => cursor('C'),
+        Key::Left => cursor('D'),
+        Key::Home => cursor('H'),
+        Key::End => cursor('F'),
+
+        Key::Insert => b"\x1b[2~".to_vec(),
+        Key::Delete => b"\x1b[3~".to_vec(),
+        Key::PageUp => b"\x1b[5~".to_vec(),
+        Key::PageDown => b"\x1b[6~".to_vec(),
+
+        Key::F(n @ 1 ..= 4) => format!("\x1bO{}", (b'P' + n - 1) as char).into_bytes(),
+        Key::F(n) => {
+            let code = match n {
+                5 => 15, 6 => 17, 7 => 18, 8 => 19, 9 => 20,
+                10 => 21, 11 => 23, 12 => 24,
+                _ => return vec![],
+            };
+            format!("\x1b[{}~", code).into_bytes()
+        }
+
+        _ => vec![]
+    }
+}
+
+/// mouse report, if the child enabled mouse tracking
+fn encode_mouse(event: &MouseEvent, modes: &TerminalModes) -> Vec<u8> {
+    if !modes.mouse_tracking {
+        return vec![];
+    }
+
+    let (button, x, y, release) = match event {
+        MouseEvent::Press(b, x, y) => (
+            match b {
+                MouseButton::Left => 0,
+                MouseButton::Middle => 1,
+                MouseButton::Right => 2,
+                MouseButton::WheelUp => 64,
+                MouseButton::WheelDown => 65,
+            },
+            *x, *y, false
+        ),
+        MouseEvent::Release(x, y) => (3, *x, *y, true),
+        MouseEvent::Hold(x, y) => (32, *x, *y, false),
+    };
+
+    if modes.sgr_mouse {
+        let (button, c) = if release { (0, 'm') } else { (button, 'M') };
+        format!("\x1b[<{};{};{}{}", button, x, y, c).into_bytes()
+    } else {
+        // legacy encoding can not address more than 223 columns
+        vec![
+            0x1b, b'[', b'M',
+            32 + button as u8,
+            32 + std::cmp::min(x, 223) as u8,
+            32 + std::cmp::min(y, 223) as u8,
+        ]
+    }
+}
+
+impl TerminalEditor for PtyWidget {
+    fn get_term_view(&self) -> OuterViewPort<dyn TerminalView> {
+        self.port.outer()
+    }
+
+    fn handle_terminal_event(&mut self, event: &TerminalEvent) -> TerminalEditorResult {
+        let bytes = match event {
+            TerminalEvent::Resize(size) => {
+                self.resize(*size);
+                return TerminalEditorResult::Continue;
+            }
+            TerminalEvent::Input(Event::Key(key)) => encode_key(key, &self.get_modes()),
+            TerminalEvent::Input(Event::Mouse(mouse)) => encode_mouse(mouse, &self.get_modes()),
+            TerminalEvent::Input(Event::Unsupported(bytes)) => bytes.clone(),
+        };
+
+        if self.try_wait().is_some() {
+            TerminalEditorResult::Exit
+        } else {
+            if !bytes.is_empty() {
+                self.write(&bytes).ok();
+            }
+            TerminalEditorResult::Continue
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use {
+    r3vi::{
+        view::{ViewPort, OuterViewPort, sequence::*},
+        buffer::vec::*,
+    },
+    crate::{
+        type_system::ReprTree,
+        terminal::{
+            TerminalAtom, TerminalCompositor, TerminalEditor, TerminalEditorResult,
+            TerminalEvent, TerminalView, LayerId,
+            widgets::{mouse_pos, translate_mouse},
+        },
+        tree::{NestedNode, TreeNavResult},
+        commander::ObjCommander,
+    },
+    termion::event::{Event, MouseEvent},
+    cgmath::{Point2, Vector2},
+    std::sync::{Arc, RwLock},
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplitOrientation {
+    /// panes side by side, from left to right
+    Horizontal,
+
+    /// panes stacked from top to bottom
+    Vertical,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PaneSize {
+    /// number of cells along the split axis
+    Fixed(i16),
+
+    /// share of the space left over by fixed panes
+    Proportional(u16),
+}
+
+struct Pane {
+    size: PaneSize,
+    node: NestedNode,
+    layer: LayerId,
+    offset: Vector2<i16>,
+    extent: Vector2<i16>,
+}
+
+/// Places several nodes next to each other, separated by a line.
+/// Terminal events and commands are routed to the focused pane,
+/// clicking into a pane focuses it.
+pub struct SplitPane {
+    orientation: SplitOrientation,
+    extent: Vector2<i16>,
+
+    panes: Vec<Pane>,
+    separators: Vec<LayerId>,
+    focus: Option<usize>,
+
+    compositor: Arc<RwLock<TerminalCompositor>>,
+    port: ViewPort<dyn TerminalView>,
+}
+
+impl SplitPane {
+    pub fn new(orientation: SplitOrientation, extent: Vector2<i16>) -> Self {
+        let port = ViewPort::new();
+        let compositor = TerminalCompositor::new(port.inner());
+
+        SplitPane {
+            orientation,
+            extent,
+            panes: Vec::new(),
+            separators: Vec::new(),
+            focus: None,
+            compositor,
+            port
+        }
+    }
+
+    pub fn with_pane(mut self, size: PaneSize, node: NestedNode) -> Self {
+        self.add_pane(size, node);
+        self
+    }
+
+    /// append a pane and return its index
+    pub fn add_pane(&mut self, size: PaneSize, node: NestedNode) -> usize {
+        let layer = {
+            let mut comp = self.compositor.write().unwrap();
+            let layer = comp.push(node.get_view());
+            comp.set_opaque(layer, true);
+            layer
+        };
+
+        self.panes.push(Pane {
+            size,
+            node,
+            layer,
+            offset: Vector2::new(0, 0),
+            extent: Vector2::new(0, 0),
+        });
+
+        if self.focus.is_none() {
+            self.focus = Some(0);
+        }
+
+        self.update_layout();
+        self.panes.len() - 1
+    }
+
+    pub fn remove_pane(&mut self, idx: usize) -> Option<NestedNode> {
+        if idx < self.panes.len() {
+            let pane = self.panes.remove(idx);
+            self.compositor.write().unwrap().remove(pane.layer);
+
+            self.focus = match self.focus {
+                _ if self.panes.is_empty() => None,
+                Some(f) if f > idx || f == self.panes.len() => Some(f - 1),
+                f => f
+            };
+
+            self.update_layout();
+            Some(pane.node)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_size(&mut self, idx: usize, size: PaneSize) {
+        if let Some(pane) = self.panes.get_mut(idx) {
+            pane.size = size;
+            self.update_layout();
+        }
+    }
+
+    pub fn resize(&mut self, extent: Vector2<i16>) {
+        if self.extent != extent {
+            self.extent = extent;
+            self.update_layout();
+        }
+    }
+
+    pub fn get_focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, idx: usize) {
+        if idx < self.panes.len() {
+            self.focus = Some(idx);
+        }
+    }
+
+    pub fn focus_next(&mut self) {
+        if let Some(f) = self.focus {
+            self.focus = Some((f + 1) % self.panes.len());
+        }
+    }
+
+    pub fn focus_prev(&mut self) {
+        if let Some(f) = self.focus {
+            self.focus = Some((f + self.panes.len() - 1) % self.panes.len());
+        }
+    }
+
+    pub fn get_focused(&self) -> Option<NestedNode> {
+        Some(self.panes.get(self.focus?)?.node.clone())
+    }
+
+    /// index of the pane covering `pos`
+    pub fn pane_at(&self, pos: &Point2<i16>) -> Option<usize> {
+        self.panes.iter().position(|pane| {
+            let rel = *pos - pane.offset;
+            rel.x >= 0 && rel.y >= 0 && rel.x < pane.extent.x && rel.y < pane.extent.y
+        })
+    }
+
+    fn axis_len(&self, v: Vector2<i16>) -> i16 {
+        match self.orientation {
+            SplitOrientation::Horizontal => v.x,
+            SplitOrientation::Vertical => v.y,
+        }
+    }
+
+    fn along_axis(&self, main: i16, cross: i16) -> Vector2<i16> {
+        match self.orientation {
+            SplitOrientation::Horizontal => Vector2::new(main, cross),
+            SplitOrientation::Vertical => Vector2::new(cross, main),
+        }
+    }
+
+    /// sizes of all panes along the split axis
+    fn pane_sizes(&self) -> Vec<i16> {
+        let n_separators = std::cmp::max(1, self.panes.len() as i16) - 1;
+        let available = std::cmp::max(0, self.axis_len(self.extent) - n_separators);
+
+        let mut fixed = 0;
+        let mut weights = 0;
+        for pane in self.panes.iter() {
+            match pane.size {
+                PaneSize::Fixed(n) => fixed += n,
+                PaneSize::Proportional(w) => weights += w as i32,
+            }
+        }
+
+        let mut remaining = std::cmp::max(0, available - fixed) as i32;
+        let mut remaining_weights = weights;
+        let mut left = available;
+
+        self.panes.iter().map(|pane| {
+            let size = match pane.size {
+                PaneSize::Fixed(n) => n,
+                PaneSize::Proportional(w) => {
+                    // the last proportional pane takes the rounding remainder
+                    let size = if remaining_weights > 0 { remaining * w as i32 / remaining_weights } else { 0 };
+                    remaining -= size;
+                    remaining_weights -= w as i32;
+                    size as i16
+                }
+            };
+            let size = std::cmp::max(0, std::cmp::min(size, left));
+            left -= size;
+            size
+        }).collect()
+    }
+
+    fn update_layout(&mut self) {
+        let sizes = self.pane_sizes();
+        let cross = match self.orientation {
+            SplitOrientation::Horizontal => self.extent.y,
+            SplitOrientation::Vertical => self.extent.x,
+        };
+
+        let mut comp = self.compositor.write().unwrap();
+        for layer in self.separators.drain(..) {
+            comp.remove(layer);
+        }
+
+        let mut pos = 0;
+        for (idx, size) in sizes.into_iter().enumerate() {
+            let offset = self.along_axis(pos, 0);
+            let extent = self.along_axis(size, cross);
+
+            let pane = &mut self.panes[idx];
+            pane.offset = offset;
+            pane.extent = extent;
+
+            comp.set_offset(pane.layer, offset);
+            comp.set_clip(pane.layer, Some(
+                Point2::new(0, 0) + offset ..= Point2::new(-1, -1) + offset + extent
+            ));
+
+            pos += size;
+
+            if idx + 1 < self.panes.len() {
+                let sep = comp.push(make_separator(self.orientation, cross));
+                comp.set_offset(sep, self.along_axis(pos, 0));
+                self.separators.push(sep);
+                pos += 1;
+            }
+        }
+    }
+}
+
+fn make_separator(orientation: SplitOrientation, len: i16) -> OuterViewPort<dyn TerminalView> {
+    let c = match orientation {
+        SplitOrientation::Horizontal => '│',
+        SplitOrientation::Vertical => '─',
+    };
+
+    let line = VecBuffer::with_data(vec![ TerminalAtom::from(c); std::cmp::max(0, len) as usize ])
+        .get_port()
+        .to_sequence()
+        .to_index();
+
+    match orientation {
+        // vertical line between horizontally split panes
+        SplitOrientation::Horizontal => line.map_key(
+            |idx| Point2::new(0, *idx as i16),
+            |pt| if pt.x == 0 { Some(pt.y as usize) } else { None },
+        ),
+        SplitOrientation::Vertical => line.map_key(
+            |idx| Point2::new(*idx as i16, 0),
+            |pt| if pt.y == 0 { Some(pt.x as usize) } else { None },
+        ),
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+impl TerminalEditor for SplitPane {
+    fn get_term_view(&self) -> OuterViewPort<dyn TerminalView> {
+        self.port.outer()
+    }
+
+    fn handle_terminal_event(&mut self, event: &TerminalEvent) -> TerminalEditorResult {
+        if let TerminalEvent::Input(Event::Mouse(MouseEvent::Press(_, _, _))) = event {
+            if let Some(idx) = mouse_pos(event).and_then(|pos| self.pane_at(&pos)) {
+                self.focus = Some(idx);
+            }
+        }
+
+        if let Some(idx) = self.focus {
+            let pane = &mut self.panes[idx];
+            pane.node.handle_terminal_event(&translate_mouse(event, pane.offset))
+        } else {
+            TerminalEditorResult::Continue
+        }
+    }
+}
+
+impl ObjCommander for SplitPane {
+    fn send_cmd_obj(&mut self, cmd_obj: Arc<RwLock<ReprTree>>) -> TreeNavResult {
+        if let Some(idx) = self.focus {
+            self.panes[idx].node.send_cmd_obj(cmd_obj)
+        } else {
+            TreeNavResult::Exit
+        }
+    }
+}
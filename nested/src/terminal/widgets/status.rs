@@ -0,0 +1,247 @@
+use {
+    r3vi::{
+        view::{
+            channel::{set_channel, ChannelSender},
+            Observer, OuterViewPort,
+            singleton::*,
+            sequence::*,
+        },
+        buffer::{singleton::*, vec::*},
+    },
+    crate::{
+        editors::{
+            list::{ListCursorMode, ListEditor},
+            product::ProductEditor,
+            typeterm::TypeTermEditor,
+        },
+        terminal::{TerminalView, TerminalProjections, make_label},
+        tree::{NestedNode, TreeNav},
+        diagnostics::{Diagnostics, Message, MessageKind},
+    },
+    async_std::{stream::StreamExt, task},
+    std::{
+        collections::HashSet,
+        sync::{Arc, RwLock},
+        time::Duration,
+    },
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Summary of the editor state for the bottom of the screen:
+/// cursor address & mode, type of the node under the cursor,
+/// number of errors and warnings and a transient message.
+///
+/// Type and counts are recomputed in a background task,
+/// since they have to read editors which may be locked
+/// while their cursor or diagnostics notify.
+pub struct StatusLine {
+    node_type: SingletonBuffer<String>,
+    n_errors: SingletonBuffer<usize>,
+    n_warnings: SingletonBuffer<usize>,
+    message: SingletonBuffer<Option<String>>,
+
+    view: OuterViewPort<dyn TerminalView>,
+    _observer: Arc<RwLock<StatusObserver>>,
+}
+
+struct StatusObserver {
+    tx: ChannelSender<HashSet<()>>,
+}
+
+impl Observer<dyn SequenceView<Item = isize>> for StatusObserver {
+    fn reset(&mut self, _view: Option<Arc<dyn SequenceView<Item = isize>>>) {
+        self.tx.send(());
+    }
+
+    fn notify(&mut self, _idx: &usize) {
+        self.tx.send(());
+    }
+}
+
+impl Observer<dyn SingletonView<Item = ListCursorMode>> for StatusObserver {
+    fn reset(&mut self, _view: Option<Arc<dyn SingletonView<Item = ListCursorMode>>>) {
+        self.tx.send(());
+    }
+
+    fn notify(&mut self, _msg: &()) {
+        self.tx.send(());
+    }
+}
+
+impl Observer<dyn SequenceView<Item = Message>> for StatusObserver {
+    fn reset(&mut self, _view: Option<Arc<dyn SequenceView<Item = Message>>>) {
+        self.tx.send(());
+    }
+
+    fn notify(&mut self, _idx: &usize) {
+        self.tx.send(());
+    }
+}
+
+impl StatusLine {
+    pub fn new(root: NestedNode) -> Self {
+        let node_type = SingletonBuffer::new(String::new());
+        let n_errors = SingletonBuffer::new(0);
+        let n_warnings = SingletonBuffer::new(0);
+        let message = SingletonBuffer::new(None);
+
+        let theme = root.ctx.read().unwrap().get_theme();
+
+        let (tx, mut rx) = set_channel();
+        let observer = Arc::new(RwLock::new(StatusObserver { tx }));
+
+        let msg_port = root.get_msg_port();
+        root.get_addr_view().add_observer(observer.clone());
+        root.get_mode_view().add_observer(observer.clone());
+        msg_port.add_observer(observer.clone());
+
+        let cursor_widget = root.get_cursor_widget(theme.clone());
+
+        task::spawn({
+            let mut node_type = node_type.clone();
+            let mut n_errors = n_errors.clone();
+            let mut n_warnings = n_warnings.clone();
+            let msgs = msg_port.get_view();
+
+            async move {
+                while let Some(_) = rx.next().await {
+                    let ctx = root.ctx.clone();
+                    let t = cursor_node(&root).get_type();
+                    node_type.set(ctx.read().unwrap().type_term_to_str(&t));
+
+                    let count = |kind| (0 .. msgs.len().unwrap_or(0))
+                        .filter(|i| msgs.get(i).map(|m| m.kind == kind).unwrap_or(false))
+                        .count();
+                    n_errors.set(count(MessageKind::Error));
+                    n_warnings.set(count(MessageKind::Warning));
+                }
+            }
+        });
+
+        let view = VecBuffer::with_data(
+            vec![
+                cursor_widget,
+                make_label(" "),
+                node_type.get_port()
+                    .map({
+                        let theme = theme.clone();
+                        move |t| make_label(t).with_theme_style(theme.clone(), |t| t.type_name)
+                    })
+                    .to_grid()
+                    .flatten(),
+                make_label(" "),
+                n_errors.get_port()
+                    .map({
+                        let theme = theme.clone();
+                        move |n| make_label(&format!("E:{}", n))
+                            .with_theme_style(theme.clone(), if *n > 0 { |t| t.error } else { |t| t.inactive })
+                    })
+                    .to_grid()
+                    .flatten(),
+                make_label(" "),
+                n_warnings.get_port()
+                    .map({
+                        let theme = theme.clone();
+                        move |n| make_label(&format!("W:{}", n))
+                            .with_theme_style(theme.clone(), if *n > 0 { |t| t.warning } else { |t| t.inactive })
+                    })
+                    .to_grid()
+                    .flatten(),
+                make_label(" "),
+                message.get_port()
+                    .map({
+                        let theme = theme.clone();
+                        move |msg| make_label(msg.as_ref().map(|s| s.as_str()).unwrap_or(""))
+                            .with_theme_style(theme.clone(), |t| t.message)
+                    })
+                    .to_grid()
+                    .flatten(),
+            ]
+        ).get_port()
+            .to_sequence()
+            .to_grid_horizontal()
+            .flatten();
+
+        StatusLine {
+            node_type,
+            n_errors,
+            n_warnings,
+            message,
+            view,
+            _observer: observer,
+        }
+    }
+
+    pub fn get_view(&self) -> OuterViewPort<dyn TerminalView> {
+        self.view.clone()
+    }
+
+    pub fn get_type_port(&self) -> OuterViewPort<dyn SingletonView<Item = String>> {
+        self.node_type.get_port()
+    }
+
+    pub fn get_error_count_port(&self) -> OuterViewPort<dyn SingletonView<Item = usize>> {
+        self.n_errors.get_port()
+    }
+
+    pub fn get_warning_count_port(&self) -> OuterViewPort<dyn SingletonView<Item = usize>> {
+        self.n_warnings.get_port()
+    }
+
+    /// show `msg` until it is replaced or `timeout` has passed
+    pub fn show_message(&mut self, msg: &str, timeout: Duration) {
+        self.message.set(Some(msg.into()));
+
+        let mut message = self.message.clone();
+        let msg = String::from(msg);
+        task::spawn(async move {
+            task::sleep(timeout).await;
+            if message.get().as_ref() == Some(&msg) {
+                message.set(None);
+            }
+        });
+    }
+
+    pub fn clear_message(&mut self) {
+        self.message.set(None);
+    }
+}
+
+/// the node which contains the cursor as direct child,
+/// or the selected node itself
+fn cursor_node(root: &NestedNode) -> NestedNode {
+    let cursor = root.get_cursor();
+    let depth = match cursor.leaf_mode {
+        ListCursorMode::Insert => cursor.tree_addr.len().saturating_sub(1),
+        ListCursorMode::Select => cursor.tree_addr.len(),
+    };
+
+    let mut node = root.clone();
+    for idx in cursor.tree_addr.iter().take(depth) {
+        if let Some(child) = get_child(&node, *idx) {
+            node = child;
+        } else {
+            break;
+        }
+    }
+
+    node
+}
+
+fn get_child(node: &NestedNode, idx: isize) -> Option<NestedNode> {
+    if let Some(list) = node.get_edit::<ListEditor>() {
+        let list = list.read().unwrap();
+        if list.data.len() > 0 {
+            let idx = crate::utils::modulo(idx, list.data.len() as isize) as usize;
+            return Some(list.data.get(idx).read().unwrap().clone());
+        }
+    } else if let Some(product) = node.get_edit::<ProductEditor>() {
+        return product.read().unwrap().get_editor(idx);
+    } else if let Some(typeterm) = node.get_edit::<TypeTermEditor>() {
+        let cur_node = typeterm.read().unwrap().get_cur_node();
+        return get_child(&cur_node, idx);
+    }
+
+    None
+}
@@ -0,0 +1,229 @@
+use {
+    r3vi::{
+        view::{ViewPort, OuterViewPort, sequence::*},
+        buffer::vec::*,
+        projection::decorate_sequence::*,
+    },
+    crate::{
+        type_system::ReprTree,
+        terminal::{
+            TerminalCompositor, TerminalEditor, TerminalEditorResult,
+            TerminalEvent, TerminalView, TerminalProjections, Theme, LayerId,
+            make_label, display_width,
+            widgets::{mouse_pos, translate_mouse},
+        },
+        tree::{NestedNode, TreeNavResult},
+        commander::ObjCommander,
+    },
+    termion::event::{Event, MouseEvent},
+    cgmath::{Point2, Vector2},
+    std::sync::{Arc, RwLock},
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Shows one of several nodes at a time below a row of tab titles.
+/// Terminal events and commands are routed to the active tab,
+/// clicking on a title activates its tab.
+pub struct TabbedPane {
+    extent: Vector2<i16>,
+
+    tabs: Vec<(String, NestedNode)>,
+    active: Option<usize>,
+
+    titles: VecBuffer<OuterViewPort<dyn TerminalView>>,
+    content_layer: Option<LayerId>,
+
+    compositor: Arc<RwLock<TerminalCompositor>>,
+    port: ViewPort<dyn TerminalView>,
+}
+
+impl TabbedPane {
+    pub fn new(extent: Vector2<i16>) -> Self {
+        let port = ViewPort::new();
+        let compositor = TerminalCompositor::new(port.inner());
+        let titles = VecBuffer::new();
+
+        compositor.write().unwrap().push(
+            titles.get_port()
+                .to_sequence()
+                .separate(make_label(" "))
+                .to_grid_horizontal()
+                .flatten()
+        );
+
+        TabbedPane {
+            extent,
+            tabs: Vec::new(),
+            active: None,
+            titles,
+            content_layer: None,
+            compositor,
+            port
+        }
+    }
+
+    pub fn with_tab(mut self, title: &str, node: NestedNode) -> Self {
+        self.add_tab(title, node);
+        self
+    }
+
+    /// append a tab and return its index
+    pub fn add_tab(&mut self, title: &str, node: NestedNode) -> usize {
+        let idx = self.tabs.len();
+        self.tabs.push((title.into(), node));
+        self.titles.push(make_title(title, false, &node_theme(&self.tabs[idx].1)));
+
+        if self.active.is_none() {
+            self.activate(idx);
+        }
+
+        idx
+    }
+
+    pub fn remove_tab(&mut self, idx: usize) -> Option<NestedNode> {
+        if idx < self.tabs.len() {
+            let (_title, node) = self.tabs.remove(idx);
+            self.titles.remove(idx);
+
+            match self.active {
+                Some(a) if a == idx => {
+                    self.active = None;
+                    if let Some(layer) = self.content_layer.take() {
+                        self.compositor.write().unwrap().remove(layer);
+                    }
+                    if !self.tabs.is_empty() {
+                        self.activate(std::cmp::min(idx, self.tabs.len() - 1));
+                    }
+                }
+                Some(a) if a > idx => {
+                    self.active = Some(a - 1);
+                }
+                _ => {}
+            }
+
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn activate(&mut self, idx: usize) {
+        if idx >= self.tabs.len() || self.active == Some(idx) {
+            return;
+        }
+
+        if let Some(old) = self.active {
+            *self.titles.get_mut(old) = make_title(&self.tabs[old].0, false, &node_theme(&self.tabs[old].1));
+        }
+        *self.titles.get_mut(idx) = make_title(&self.tabs[idx].0, true, &node_theme(&self.tabs[idx].1));
+        self.active = Some(idx);
+
+        let mut comp = self.compositor.write().unwrap();
+        if let Some(layer) = self.content_layer.take() {
+            comp.remove(layer);
+        }
+
+        let layer = comp.push(self.tabs[idx].1.get_view());
+        comp.set_opaque(layer, true);
+        self.content_layer = Some(layer);
+
+        drop(comp);
+        self.update_layout();
+    }
+
+    pub fn next_tab(&mut self) {
+        if let Some(a) = self.active {
+            self.activate((a + 1) % self.tabs.len());
+        }
+    }
+
+    pub fn prev_tab(&mut self) {
+        if let Some(a) = self.active {
+            self.activate((a + self.tabs.len() - 1) % self.tabs.len());
+        }
+    }
+
+    pub fn get_active(&self) -> Option<NestedNode> {
+        Some(self.tabs.get(self.active?)?.1.clone())
+    }
+
+    pub fn resize(&mut self, extent: Vector2<i16>) {
+        if self.extent != extent {
+            self.extent = extent;
+            self.update_layout();
+        }
+    }
+
+    /// index of the tab whose title covers column `x`
+    fn tab_at(&self, x: i16) -> Option<usize> {
+        let mut begin = 0;
+        for (idx, (title, _)) in self.tabs.iter().enumerate() {
+            let end = begin + display_width(title) as i16 + 2;
+            if x >= begin && x < end {
+                return Some(idx);
+            }
+            begin = end + 1;
+        }
+        None
+    }
+
+    fn update_layout(&mut self) {
+        if let Some(layer) = self.content_layer {
+            let mut comp = self.compositor.write().unwrap();
+            comp.set_offset(layer, Vector2::new(0, 1));
+            comp.set_clip(layer, Some(
+                Point2::new(0, 1) ..= Point2::new(self.extent.x - 1, self.extent.y - 1)
+            ));
+        }
+    }
+}
+
+fn node_theme(node: &NestedNode) -> Arc<RwLock<Theme>> {
+    node.ctx.read().unwrap().get_theme()
+}
+
+fn make_title(title: &str, active: bool, theme: &Arc<RwLock<Theme>>) -> OuterViewPort<dyn TerminalView> {
+    make_label(&format!(" {} ", title))
+        .with_theme_style(
+            theme.clone(),
+            if active { |t| t.tab_active } else { |t| t.tab_inactive }
+        )
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+impl TerminalEditor for TabbedPane {
+    fn get_term_view(&self) -> OuterViewPort<dyn TerminalView> {
+        self.port.outer()
+    }
+
+    fn handle_terminal_event(&mut self, event: &TerminalEvent) -> TerminalEditorResult {
+        if let Some(pos) = mouse_pos(event) {
+            if pos.y == 0 {
+                if let TerminalEvent::Input(Event::Mouse(MouseEvent::Press(_, _, _))) = event {
+                    if let Some(idx) = self.tab_at(pos.x) {
+                        self.activate(idx);
+                    }
+                }
+                return TerminalEditorResult::Continue;
+            }
+        }
+
+        if let Some(idx) = self.active {
+            self.tabs[idx].1.handle_terminal_event(&translate_mouse(event, Vector2::new(0, 1)))
+        } else {
+            TerminalEditorResult::Continue
+        }
+    }
+}
+
+impl ObjCommander for TabbedPane {
+    fn send_cmd_obj(&mut self, cmd_obj: Arc<RwLock<ReprTree>>) -> TreeNavResult {
+        if let Some(idx) = self.active {
+            self.tabs[idx].1.send_cmd_obj(cmd_obj)
+        } else {
+            TreeNavResult::Exit
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use {
+    crate::tree::TreeCursor,
+    cgmath::Point2,
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// maps a point on the terminal view of a node back to a tree cursor
+pub trait HitTest {
+    /// `pos` is relative to the origin of the node's view
+    fn hit(&self, _pos: &Point2<i16>) -> Option<TreeCursor> {
+        None
+    }
+}
@@ -1,14 +1,18 @@
 pub mod addr;
 pub mod cursor;
+pub mod hit;
 pub mod nav;
 pub mod node;
 pub mod treetype;
+pub mod watch;
 
 pub use {
     addr::TreeAddr,
     cursor::TreeCursor,
+    hit::HitTest,
     nav::{TreeNav, TreeNavResult, TreeHeightOp},
     treetype::{TreeType},
-    node::NestedNode
+    node::NestedNode,
+    watch::WatchController
 };
 
@@ -16,9 +16,10 @@ use {
     crate::{
         editors::list::ListCursorMode,
         tree::TreeCursor,
-        terminal::{TerminalView, TerminalProjections, make_label}
+        terminal::{TerminalView, TerminalProjections, Theme, make_label}
     },
     cgmath::Vector2,
+    std::sync::{Arc, RwLock},
 };
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
@@ -58,6 +59,12 @@ pub trait TreeNav {
         TreeNavResult::Exit
     }
 
+    /// select everything between the current cursor and `head`,
+    /// the cursor itself stays at the anchor
+    fn select_to(&mut self, _head: TreeCursor) -> TreeNavResult {
+        TreeNavResult::Exit
+    }
+
     /* HULL
     */
     fn set_addr(&mut self, addr: isize) -> TreeNavResult {
@@ -150,29 +157,35 @@ pub trait TreeNav {
         }
     }
 
-    fn get_cursor_widget(&self) -> OuterViewPort<dyn TerminalView> {
+    fn get_cursor_widget(&self, theme: Arc<RwLock<Theme>>) -> OuterViewPort<dyn TerminalView> {
         VecBuffer::with_data(
             vec![
-                make_label("@").with_fg_color((150, 80,230)),
+                make_label("@").with_theme_style(theme.clone(), |t| t.punctuation),
                 self.get_addr_view()
-                    .map(|i|
-                        make_label(&format!("{}", i)).with_fg_color((0, 100, 20)))
-                    .separate(make_label(".").with_fg_color((150, 80,230)))
+                    .map({
+                        let theme = theme.clone();
+                        move |i|
+                        make_label(&format!("{}", i)).with_theme_style(theme.clone(), |t| t.address)
+                    })
+                    .separate(make_label(".").with_theme_style(theme.clone(), |t| t.punctuation))
                     .to_grid_horizontal()
                     .flatten(),
-                make_label(":").with_fg_color((150, 80,230)),
+                make_label(":").with_theme_style(theme.clone(), |t| t.punctuation),
                 self.get_mode_view()
-                    .map(|mode| {
-                        make_label(
-                            match mode {
-                                ListCursorMode::Insert => "INSERT",
-                                ListCursorMode::Select => "SELECT"
-                            })
-                            .with_fg_color((200, 200, 20))
+                    .map({
+                        let theme = theme.clone();
+                        move |mode| {
+                            make_label(
+                                match mode {
+                                    ListCursorMode::Insert => "INSERT",
+                                    ListCursorMode::Select => "SELECT"
+                                })
+                                .with_theme_style(theme.clone(), |t| t.mode)
+                        }
                     })
                     .to_grid()
                     .flatten(),
-                make_label(":").with_fg_color((150, 80,230))
+                make_label(":").with_theme_style(theme.clone(), |t| t.punctuation)
             ]
         ).get_port()
             .to_sequence()
@@ -10,10 +10,11 @@ use {
         type_system::{ReprTree, Context},
         terminal::{TerminalView, TerminalEvent, TerminalEditor, TerminalEditorResult, TerminalAtom},
         diagnostics::{Diagnostics, Message},
-        tree::{TreeNav, TreeCursor, TreeNavResult, TreeHeightOp},
+        tree::{TreeNav, TreeCursor, TreeNavResult, TreeHeightOp, HitTest},
         editors::list::{ListCursorMode},
         commander::ObjCommander,
-    }
+    },
+    termion::event::{Event, MouseEvent, MouseButton},
 };
 
 /* TODO: refactoring proposal
@@ -99,6 +100,11 @@ pub struct NestedNode {
     pub tree_nav: SingletonBuffer<
                       Option< Arc<RwLock<dyn TreeNav + Send + Sync>> >
                   >,
+
+    /// maps screen positions to cursors
+    pub hit: SingletonBuffer<
+                 Option< Arc<RwLock<dyn HitTest + Send + Sync>> >
+             >,
 }
 
 impl NestedNode {
@@ -113,7 +119,8 @@ impl NestedNode {
             spillbuf: Arc::new(RwLock::new(Vec::new())),
             cmd: SingletonBuffer::new(None),
             close_char: SingletonBuffer::new(None),
-            tree_nav: SingletonBuffer::new(None)
+            tree_nav: SingletonBuffer::new(None),
+            hit: SingletonBuffer::new(None)
         }
     }
 
@@ -182,6 +189,11 @@ impl NestedNode {
         self
     }
 
+    pub fn set_hit(mut self, hit: Arc<RwLock<dyn HitTest + Send + Sync>>) -> Self {
+        self.hit.set(Some(hit));
+        self
+    }
+
     //\\//\\
 
     pub fn get_diag(&self) -> OuterViewPort<dyn SequenceView<Item = Message>> {
@@ -252,6 +264,10 @@ impl TerminalEditor for NestedNode {
     }
 
     fn handle_terminal_event(&mut self, event: &TerminalEvent) -> TerminalEditorResult {
+        if let TerminalEvent::Input(Event::Mouse(mouse_event)) = event {
+            return self.handle_mouse_event(mouse_event);
+        }
+
         let buf = SingletonBuffer::new(event.clone());
 
         if let Some(cmd) = self.cmd.get() {
@@ -266,6 +282,34 @@ impl TerminalEditor for NestedNode {
     }
 }
 
+impl NestedNode {
+    /// click positions the cursor, dragging selects a range.
+    /// Coordinates are relative to the origin of this node's view.
+    pub fn handle_mouse_event(&mut self, event: &MouseEvent) -> TerminalEditorResult {
+        match event {
+            MouseEvent::Press(MouseButton::Left, x, y) => {
+                if let Some(c) = self.hit(&Point2::new(*x as i16 - 1, *y as i16 - 1)) {
+                    self.goto(c);
+                }
+            }
+            MouseEvent::Hold(x, y) => {
+                if let Some(c) = self.hit(&Point2::new(*x as i16 - 1, *y as i16 - 1)) {
+                    self.select_to(c);
+                }
+            }
+            _ => {}
+        }
+
+        TerminalEditorResult::Continue
+    }
+}
+
+impl HitTest for NestedNode {
+    fn hit(&self, pos: &Point2<i16>) -> Option<TreeCursor> {
+        self.hit.get()?.read().unwrap().hit(pos)
+    }
+}
+
 impl TreeNav for NestedNode {
     fn get_cursor(&self) -> TreeCursor {
         if let Some(tn) = self.tree_nav.get() {
@@ -322,6 +366,14 @@ impl TreeNav for NestedNode {
             TreeNavResult::Exit
         }
     }
+
+    fn select_to(&mut self, head: TreeCursor) -> TreeNavResult {
+        if let Some(tn) = self.tree_nav.get() {
+            tn.write().unwrap().select_to(head)
+        } else {
+            TreeNavResult::Exit
+        }
+    }
 }
 
 impl ObjCommander for NestedNode {
@@ -0,0 +1,73 @@
+use {
+    r3vi::{
+        view::{sequence::*},
+        buffer::{singleton::*, vec::*}
+    },
+    crate::{
+        type_system::{ReprTree},
+        diagnostics::{Message},
+        tree::{NestedNode, TreeNavResult},
+        commander::ObjCommander
+    },
+    std::sync::{Arc, RwLock}
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Forwards all commands to a node and calls `update` after each of them,
+/// so that representations derived from the node and
+/// diagnostics about it stay up to date while editing.
+/// Does not notice changes made to the node without going through
+/// its commander; `update` has to be called explicitly then.
+pub struct WatchController {
+    node: NestedNode,
+    msg: VecBuffer<Message>,
+    update: Box<dyn FnMut(&NestedNode, &mut VecBuffer<Message>) + Send + Sync>,
+}
+
+impl WatchController {
+    /// replaces the commander of `node` and adds the messages
+    /// pushed by `update` to its diagnostics
+    pub fn for_node(
+        node: &mut NestedNode,
+        update: impl FnMut(&NestedNode, &mut VecBuffer<Message>) + Send + Sync + 'static
+    ) -> Arc<RwLock<WatchController>> {
+        let msg = VecBuffer::new();
+        let mut watch = WatchController {
+            node: node.clone(),
+            msg: msg.clone(),
+            update: Box::new(update),
+        };
+        watch.update();
+        let watch = Arc::new(RwLock::new(watch));
+
+        node.diag = Some(
+            VecBuffer::with_data(vec![
+                node.get_diag(),
+                msg.get_port().to_sequence()
+            ])
+                .get_port()
+                .to_sequence()
+                .flatten()
+        );
+
+        // the watched node keeps its own commander
+        node.cmd = SingletonBuffer::new(None);
+        node.cmd.set(Some(watch.clone()));
+
+        watch
+    }
+
+    pub fn update(&mut self) {
+        self.msg.clear();
+        (self.update)(&self.node, &mut self.msg);
+    }
+}
+
+impl ObjCommander for WatchController {
+    fn send_cmd_obj(&mut self, cmd_obj: Arc<RwLock<ReprTree>>) -> TreeNavResult {
+        let result = self.node.send_cmd_obj(cmd_obj);
+        self.update();
+        result
+    }
+}
@@ -3,6 +3,7 @@ use {
     laddertypes::{TypeDict, TypeTerm, TypeID},
     crate::{
         type_system::{ReprTree},
+        terminal::Theme,
         tree::NestedNode
     },
     std::{
@@ -103,6 +104,9 @@ pub struct Context {
     pub list_types: Vec< TypeID >,
     pub meta_chars: Vec< char >,
 
+    /// styles used by all renderers, shared with the parent context
+    theme: Arc<RwLock<Theme>>,
+
     /// graph constructors
     /// TODO: move into separate struct MorphismMap or something
     morphisms: HashMap<
@@ -130,7 +134,10 @@ impl Default for Context {
         crate::editors::list::init_ctx( &mut ctx );
         crate::editors::char::init_ctx( &mut ctx );
         crate::editors::integer::init_ctx( &mut ctx );
+        crate::editors::number::init_ctx( &mut ctx );
+        crate::editors::temporal::init_ctx( &mut ctx );
         crate::editors::typeterm::init_ctx( &mut ctx );
+        crate::type_system::interface::init_ctx( &mut ctx );
 
         ctx
     }
@@ -153,6 +160,10 @@ impl Context {
                 Some(p) => p.read().unwrap().meta_chars.clone(),
                 None => Vec::new()
             },
+            theme: match parent.as_ref() {
+                Some(p) => p.read().unwrap().theme.clone(),
+                None => Arc::new(RwLock::new(Theme::default()))
+            },
             parent,
         }
     }
@@ -161,6 +172,17 @@ impl Context {
         Context::with_parent(None)
     }
 
+    pub fn get_theme(&self) -> Arc<RwLock<Theme>> {
+        self.theme.clone()
+    }
+
+    /// replace the theme of this context and all contexts sharing it.
+    /// Views read the theme when they are rendered,
+    /// so the terminal needs a full redraw afterwards (`Terminal::redraw()`).
+    pub fn set_theme(&self, theme: Theme) {
+        *self.theme.write().unwrap() = theme;
+    }
+
     pub fn depth(&self) -> usize {
         if let Some(parent) = self.parent.as_ref() {
             parent.read().unwrap().depth() + 1
@@ -0,0 +1,196 @@
+use {
+    laddertypes::{TypeID, TypeTerm},
+    crate::type_system::Context,
+    serde::{Deserialize, Serialize},
+    std::{path::Path, process::Command},
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Argument or standard stream of a program
+/// together with its representation ladder.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct PortDescription {
+    pub name: String,
+
+    /// rungs of the ladder type, from the abstract type
+    /// down to the encoding, e.g. `[ "( ℕ )", "( MachineInt )" ]`.
+    /// `$name` refers to the value of the argument `name`.
+    pub ladder: Vec<String>,
+}
+
+impl PortDescription {
+    pub fn new(name: &str, ladder: &[&str]) -> Self {
+        PortDescription {
+            name: name.into(),
+            ladder: ladder.iter().map(|rung| String::from(*rung)).collect(),
+        }
+    }
+
+    /// parse the ladder, substituting argument references
+    /// with the given `(name, value)` pairs
+    pub fn ladder_type(&self, ctx: &Context, args: &[(String, String)]) -> TypeTerm {
+        TypeTerm::Ladder(self.rungs(ctx, args))
+    }
+
+    /// parsed rungs of the ladder, argument references with a
+    /// value in `args` are replaced by the parsed value,
+    /// the others become type variables of the same name
+    pub fn rungs(&self, ctx: &Context, args: &[(String, String)]) -> Vec<TypeTerm> {
+        let args: Vec<(u64, TypeTerm)> = args.iter()
+            .map(|(name, value)| (var_id(ctx, name), parse_rung(ctx, value)))
+            .collect();
+
+        self.ladder.iter()
+            .map(|rung| substitute(&parse_rung(ctx, rung), &|v| {
+                args.iter().find(|(id, _)| *id == v).map(|(_, t)| t.clone())
+            }))
+            .collect()
+    }
+}
+
+/// id of the type variable `$name`
+pub fn var_id(ctx: &Context, name: &str) -> u64 {
+    let mut dict = ctx.type_dict.write().unwrap();
+    match dict.get_typeid(&name.into()) {
+        Some(TypeID::Var(id)) => id,
+        _ => match dict.add_varname(name.into()) {
+            TypeID::Var(id) => id,
+            TypeID::Fun(_) => unreachable!(),
+        }
+    }
+}
+
+/// Parses one rung, where `( .. )` is an application
+/// and `$name` a type variable. Typenames are resolved
+/// through `ctx`, so synonyms end up in the same term.
+/// Unbalanced parentheses are closed at the end.
+fn parse_rung(ctx: &Context, rung: &str) -> TypeTerm {
+    let tokens = rung.replace('(', " ( ").replace(')', " ) ");
+    let mut stack = vec![ Vec::new() ];
+
+    for tok in tokens.split_whitespace() {
+        match tok {
+            "(" => stack.push(Vec::new()),
+            ")" if stack.len() > 1 => {
+                let args = stack.pop().unwrap();
+                stack.last_mut().unwrap().push(TypeTerm::App(args));
+            }
+            ")" => {}
+            _ => {
+                let term = match tok.strip_prefix('$') {
+                    Some(name) => TypeTerm::TypeID(TypeID::Var(var_id(ctx, name))),
+                    None => ctx.type_term_from_str(tok).unwrap_or_else(|_| {
+                        TypeTerm::TypeID(ctx.type_dict.write().unwrap().add_typename(tok.into()))
+                    }),
+                };
+                stack.last_mut().unwrap().push(term);
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        let args = stack.pop().unwrap();
+        stack.last_mut().unwrap().push(TypeTerm::App(args));
+    }
+
+    let mut terms = stack.pop().unwrap();
+    match terms.len() {
+        1 => terms.pop().unwrap(),
+        _ => TypeTerm::App(terms),
+    }
+}
+
+/// replace every variable for which `value` returns a term
+pub fn substitute(term: &TypeTerm, value: &impl Fn(u64) -> Option<TypeTerm>) -> TypeTerm {
+    match term {
+        TypeTerm::TypeID(TypeID::Var(v)) => value(*v).unwrap_or(term.clone()),
+        TypeTerm::App(args) => TypeTerm::App(args.iter().map(|t| substitute(t, value)).collect()),
+        TypeTerm::Ladder(rungs) => TypeTerm::Ladder(rungs.iter().map(|t| substitute(t, value)).collect()),
+        _ => term.clone(),
+    }
+}
+
+/// Interface of a pipeline program:
+/// command line arguments `$1, $2, ..`, stdin (`>0`) and stdout (`<1`).
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ProgramInterface {
+    pub name: String,
+    pub description: String,
+    pub args: Vec<PortDescription>,
+    pub stdin: Option<PortDescription>,
+    pub stdout: Option<PortDescription>,
+}
+
+impl ProgramInterface {
+    pub fn new(name: &str, description: &str) -> Self {
+        ProgramInterface {
+            name: name.into(),
+            description: description.into(),
+            args: Vec::new(),
+            stdin: None,
+            stdout: None,
+        }
+    }
+
+    pub fn with_arg(mut self, name: &str, ladder: &[&str]) -> Self {
+        self.args.push(PortDescription::new(name, ladder));
+        self
+    }
+
+    pub fn with_stdin(mut self, name: &str, ladder: &[&str]) -> Self {
+        self.stdin = Some(PortDescription::new(name, ladder));
+        self
+    }
+
+    pub fn with_stdout(mut self, name: &str, ladder: &[&str]) -> Self {
+        self.stdout = Some(PortDescription::new(name, ladder));
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("interface is serializable")
+    }
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    /// To be called first thing in `main()`:
+    /// when invoked with `--interface`, print the interface
+    /// as JSON to stdout and exit, otherwise show it between
+    /// magic headers on stderr.
+    pub fn announce(&self) {
+        if std::env::args().skip(1).any(|arg| arg == "--interface") {
+            println!("{}", self.to_json());
+            std::process::exit(0);
+        }
+
+        crate::magic_header();
+        eprintln!("{}", self.description);
+        crate::magic_header();
+        eprintln!("{}", self.to_json());
+        crate::magic_header();
+    }
+
+    /// ask `program` for its interface
+    pub fn query(program: &Path) -> std::io::Result<Self> {
+        let output = Command::new(program).arg("--interface").output()?;
+        ProgramInterface::from_json(&String::from_utf8_lossy(&output.stdout))
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// typenames occurring in the interfaces of pipeline programs
+pub fn init_ctx(ctx: &mut Context) {
+    ctx.add_typename("MachineWord");
+    ctx.add_typename("MachineSyllab");
+    ctx.add_typename("Stream");
+    ctx.add_typename("UTF-8-Char");
+    ctx.add_typename("Json");
+    ctx.add_typename("DiffStream");
+    ctx.add_typename("Vec");
+    ctx.add_synonym("PositionalInt", "PosInt");
+}
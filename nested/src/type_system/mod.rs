@@ -1,8 +1,12 @@
 pub mod context;
+pub mod interface;
+pub mod pipeline;
 pub mod repr_tree;
 
 pub use {
     context::{Context, MorphismMode, MorphismType, MorphismTypePattern},
+    interface::{PortDescription, ProgramInterface},
+    pipeline::{Pipeline, PipelineError, Stage},
     repr_tree::ReprTree
 };
 
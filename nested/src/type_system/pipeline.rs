@@ -0,0 +1,341 @@
+use {
+    crate::type_system::{
+        interface::{substitute, var_id},
+        Context, PortDescription, ProgramInterface,
+    },
+    laddertypes::{TypeID, TypeTerm},
+    std::{
+        collections::{HashMap, VecDeque},
+        io::{Read, Write},
+        path::{Path, PathBuf},
+        os::unix::process::ExitStatusExt,
+        process::{Child, Command, ExitStatus, Stdio},
+        sync::{Arc, RwLock},
+    },
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// maximal number of converters inserted between two stages
+const MAX_CONVERSION_DEPTH: usize = 3;
+
+#[derive(Debug)]
+pub enum PipelineError {
+    Io(std::io::Error),
+
+    /// the program does not declare the stream it is connected to
+    MissingPort { stage: usize, port: &'static str },
+
+    /// argument without value, or value without argument
+    BadArgs { stage: usize, expected: usize, got: usize },
+
+    /// no chain of converters connects the output of
+    /// stage `stage` with the input of the next one
+    NoConversion { stage: usize, from: Vec<String>, to: Vec<String> },
+
+    /// a program exited unsuccessfully, `stage` counts
+    /// the resolved stages, i.e. including converters
+    StageFailed { stage: usize, status: ExitStatus },
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PipelineError::Io(err) => write!(fmt, "{}", err),
+            PipelineError::MissingPort { stage, port } => write!(fmt, "stage {} has no {}", stage, port),
+            PipelineError::BadArgs { stage, expected, got } => write!(
+                fmt,
+                "stage {} takes {} arguments, got {}",
+                stage, expected, got
+            ),
+            PipelineError::NoConversion { stage, from, to } => write!(
+                fmt,
+                "can not connect stage {} to stage {}:\n  {}\n  ≠\n  {}",
+                stage, stage + 1, from.join(" "), to.join(" ")
+            ),
+            PipelineError::StageFailed { stage, status } => write!(fmt, "stage {} failed: {}", stage, status),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+impl From<std::io::Error> for PipelineError {
+    fn from(err: std::io::Error) -> Self {
+        PipelineError::Io(err)
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Program with bound arguments
+#[derive(Clone, Debug)]
+pub struct Stage {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub interface: ProgramInterface,
+}
+
+impl Stage {
+    /// query the interface of `program`
+    pub fn new(program: impl AsRef<Path>, args: &[&str]) -> std::io::Result<Self> {
+        Ok(Stage {
+            program: program.as_ref().into(),
+            args: args.iter().map(|a| String::from(*a)).collect(),
+            interface: ProgramInterface::query(program.as_ref())?,
+        })
+    }
+
+    /// `(name, value)` of every argument
+    pub fn bindings(&self) -> Vec<(String, String)> {
+        self.interface.args.iter()
+            .map(|arg| arg.name.clone())
+            .zip(self.args.iter().cloned())
+            .collect()
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(self.args.iter());
+        cmd
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Rung of a ladder, variables are bound by unification.
+type Rung = TypeTerm;
+
+fn ladder(ctx: &Context, port: &PortDescription, bindings: &[(String, String)]) -> Vec<Rung> {
+    port.rungs(ctx, bindings)
+}
+
+fn unify(pattern: &TypeTerm, concrete: &TypeTerm, vars: &mut HashMap<u64, TypeTerm>) -> bool {
+    match (pattern, concrete) {
+        (TypeTerm::TypeID(TypeID::Var(v)), _) => match vars.get(v) {
+            Some(value) => value == concrete,
+            None => {
+                vars.insert(*v, concrete.clone());
+                true
+            }
+        },
+        (TypeTerm::App(p), TypeTerm::App(c)) | (TypeTerm::Ladder(p), TypeTerm::Ladder(c)) => {
+            p.len() == c.len() && p.iter().zip(c.iter()).all(|(p, c)| unify(p, c, vars))
+        }
+        _ => pattern == concrete,
+    }
+}
+
+/// bind the variables of `pattern` so that it equals `concrete`,
+/// `vars` is left untouched if that is impossible
+fn unify_rung(pattern: &Rung, concrete: &Rung, vars: &mut HashMap<u64, TypeTerm>) -> bool {
+    let mut new_vars = vars.clone();
+    if unify(pattern, concrete, &mut new_vars) {
+        *vars = new_vars;
+        true
+    } else {
+        false
+    }
+}
+
+/// Length of the common suffix of two ladders,
+/// binding variables of `pattern` on the way.
+fn common_suffix(pattern: &[Rung], concrete: &[Rung], vars: &mut HashMap<u64, TypeTerm>) -> usize {
+    let mut n = 0;
+    while n < pattern.len() && n < concrete.len()
+        && unify_rung(&pattern[pattern.len() - 1 - n], &concrete[concrete.len() - 1 - n], vars)
+    {
+        n += 1;
+    }
+    n
+}
+
+/// A value of ladder type `concrete` can be read as `pattern`,
+/// if `pattern` is a suffix of `concrete`, i.e. if both agree
+/// from the top level of `pattern` down to the encoding.
+fn compatible(pattern: &[Rung], concrete: &[Rung], vars: &mut HashMap<u64, TypeTerm>) -> bool {
+    !pattern.is_empty() && common_suffix(pattern, concrete, vars) == pattern.len()
+}
+
+fn substitute_all(ladder: &[Rung], vars: &HashMap<u64, TypeTerm>) -> Vec<Rung> {
+    ladder.iter()
+        .map(|rung| substitute(rung, &|v| vars.get(&v).cloned()))
+        .collect()
+}
+
+fn is_bound(term: &TypeTerm) -> bool {
+    match term {
+        TypeTerm::TypeID(TypeID::Var(_)) => false,
+        TypeTerm::App(args) | TypeTerm::Ladder(args) => args.iter().all(is_bound),
+        _ => true,
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Runs a sequence of programs, each one reading the output
+/// of its predecessor. Adjacent stages must have compatible
+/// ladder types, where they don't, converter programs are inserted.
+pub struct Pipeline {
+    ctx: Arc<RwLock<Context>>,
+    converters: Vec<(PathBuf, ProgramInterface)>,
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    pub fn new(ctx: Arc<RwLock<Context>>) -> Self {
+        Pipeline {
+            ctx,
+            converters: Vec::new(),
+            stages: Vec::new(),
+        }
+    }
+
+    /// make `program` available for automatic conversions
+    pub fn add_converter(&mut self, program: impl AsRef<Path>) -> std::io::Result<()> {
+        let interface = ProgramInterface::query(program.as_ref())?;
+        self.converters.push((program.as_ref().into(), interface));
+        Ok(())
+    }
+
+    pub fn push(&mut self, stage: Stage) {
+        self.stages.push(stage);
+    }
+
+    /// the stages to run, including inserted converters
+    pub fn resolve(&self) -> Result<Vec<Stage>, PipelineError> {
+        let mut resolved = Vec::new();
+
+        for (i, stage) in self.stages.iter().enumerate() {
+            let expected = stage.interface.args.len();
+            if stage.args.len() != expected {
+                return Err(PipelineError::BadArgs { stage: i, expected, got: stage.args.len() });
+            }
+
+            if i > 0 {
+                let prev = &self.stages[i - 1];
+                let ctx = self.ctx.read().unwrap();
+
+                let src = prev.interface.stdout.as_ref()
+                    .ok_or(PipelineError::MissingPort { stage: i - 1, port: "stdout" })?;
+                let dst = stage.interface.stdin.as_ref()
+                    .ok_or(PipelineError::MissingPort { stage: i, port: "stdin" })?;
+
+                let src = ladder(&ctx, src, &prev.bindings());
+                let dst = ladder(&ctx, dst, &stage.bindings());
+
+                match self.find_conversion(&ctx, &src, &dst) {
+                    Some(converters) => resolved.extend(converters),
+                    None => {
+                        return Err(PipelineError::NoConversion {
+                            stage: i - 1,
+                            from: src.iter().map(|r| ctx.type_term_to_str(r)).collect(),
+                            to: dst.iter().map(|r| ctx.type_term_to_str(r)).collect(),
+                        })
+                    }
+                }
+            }
+
+            resolved.push(stage.clone());
+        }
+
+        Ok(resolved)
+    }
+
+    /// shortest chain of converters from `src` to `dst`
+    fn find_conversion(&self, ctx: &Context, src: &[Rung], dst: &[Rung]) -> Option<Vec<Stage>> {
+        let mut queue = VecDeque::new();
+        queue.push_back((src.to_vec(), Vec::new()));
+
+        while let Some((cur, chain)) = queue.pop_front() {
+            if compatible(dst, &cur, &mut HashMap::new()) {
+                return Some(chain);
+            }
+            if chain.len() >= MAX_CONVERSION_DEPTH {
+                continue;
+            }
+
+            for (program, interface) in self.converters.iter() {
+                if let (Some(input), Some(output)) = (interface.stdin.as_ref(), interface.stdout.as_ref()) {
+                    let input = ladder(ctx, input, &[]);
+                    let output = ladder(ctx, output, &[]);
+
+                    // bind input variables by the current representation,
+                    // remaining output variables by the destination
+                    let mut vars = HashMap::new();
+                    if !compatible(&input, &cur, &mut vars) {
+                        continue;
+                    }
+                    let output = substitute_all(&output, &vars);
+                    common_suffix(&output, dst, &mut vars);
+                    let output = substitute_all(&output, &vars);
+
+                    let args: Option<Vec<String>> = interface.args.iter()
+                        .map(|arg| vars.get(&var_id(ctx, &arg.name)).map(|t| ctx.type_term_to_str(t)))
+                        .collect();
+
+                    if let Some(args) = args {
+                        if output.iter().all(is_bound) && output != cur {
+                            let mut chain = chain.clone();
+                            chain.push(Stage {
+                                program: program.clone(),
+                                args,
+                                interface: interface.clone(),
+                            });
+                            queue.push_back((output, chain));
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// run all stages, feeding `input` to the first one,
+    /// and return the output of the last one.
+    /// Diagnostics of the programs go to our stderr.
+    pub fn run(&self, input: &[u8]) -> Result<Vec<u8>, PipelineError> {
+        let stages = self.resolve()?;
+        let mut children: Vec<Child> = Vec::new();
+
+        for stage in stages.iter() {
+            let mut cmd = stage.command();
+            cmd.stdout(Stdio::piped());
+            cmd.stderr(Stdio::inherit());
+            match children.last_mut() {
+                Some(prev) => { cmd.stdin(Stdio::from(prev.stdout.take().unwrap())); }
+                None => { cmd.stdin(Stdio::piped()); }
+            }
+            children.push(cmd.spawn()?);
+        }
+
+        let mut output = Vec::new();
+        if let Some(first) = children.first_mut() {
+            let mut stdin = first.stdin.take().unwrap();
+            let input = input.to_vec();
+            let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+            children.last_mut().unwrap()
+                .stdout.take().unwrap()
+                .read_to_end(&mut output)?;
+
+            writer.join().ok();
+        }
+
+        let mut statuses = Vec::new();
+        for mut child in children {
+            statuses.push(child.wait()?);
+        }
+
+        // a stage dies of SIGPIPE when its successor stops reading,
+        // that successor either failed itself or had all it needs
+        let failed = statuses.into_iter().enumerate()
+            .find(|(_, status)| !status.success() && status.signal() != Some(libc::SIGPIPE));
+
+        match failed {
+            Some((stage, status)) => Err(PipelineError::StageFailed { stage, status }),
+            None => Ok(output)
+        }
+    }
+}
@@ -66,6 +66,10 @@ impl ReprTree {
         self.branches.insert(repr.clone().read().unwrap().type_tag.clone(), repr.clone());
     }
 
+    pub fn remove_branch(&mut self, type_tag: &TypeTerm) -> Option<Arc<RwLock<ReprTree>>> {
+        self.branches.remove(type_tag)
+    }
+
     pub fn insert_leaf(
         &mut self,
         mut type_ladder: impl Iterator<Item = TypeTerm>,
@@ -127,6 +131,15 @@ impl ReprTree {
         Arc::new(RwLock::new(n))
     }
 
+    /// inverse of `ascend`: the only branch below the root
+    pub fn descend_single(&self) -> Option<Arc<RwLock<ReprTree>>> {
+        if self.branches.len() == 1 {
+            self.branches.values().next().cloned()
+        } else {
+            None
+        }
+    }
+
 /*
     pub fn add_iso_repr(
         &self,
@@ -0,0 +1,245 @@
+use {
+    r3vi::{
+        view::{
+            Observer, ObserverBroadcast, OuterViewPort, View, ViewPort,
+            singleton::*,
+            sequence::*,
+        },
+        projection::projection_helper::*
+    },
+    std::sync::{Arc, RwLock},
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// `f(a, b)`, evaluated on every read
+/// and notified whenever `a` or `b` changes
+pub fn zip_singletons<A, B, T>(
+    a: OuterViewPort<dyn SingletonView<Item = A>>,
+    b: OuterViewPort<dyn SingletonView<Item = B>>,
+    f: impl Fn(A, B) -> T + Send + Sync + 'static,
+) -> OuterViewPort<dyn SingletonView<Item = T>>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    let out_port = ViewPort::new();
+    let mut proj_helper = ProjectionHelper::new(out_port.update_hooks.clone());
+    let proj = Arc::new(RwLock::new(ZipSingletons {
+        a: proj_helper.new_singleton_arg(0, a, |s: &mut ZipSingletons<A, B, T>, _msg| s.cast.notify(&())),
+        b: proj_helper.new_singleton_arg(1, b, |s: &mut ZipSingletons<A, B, T>, _msg| s.cast.notify(&())),
+        f: Box::new(f),
+        cast: out_port.inner().get_broadcast(),
+        proj_helper,
+    }));
+
+    proj.write().unwrap().proj_helper.set_proj(&proj);
+    out_port.inner().set_view(Some(proj));
+    out_port.into_outer()
+}
+
+struct ZipSingletons<A, B, T>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    a: Arc<dyn SingletonView<Item = A>>,
+    b: Arc<dyn SingletonView<Item = B>>,
+    f: Box<dyn Fn(A, B) -> T + Send + Sync>,
+    cast: Arc<RwLock<ObserverBroadcast<dyn SingletonView<Item = T>>>>,
+    proj_helper: ProjectionHelper<usize, Self>,
+}
+
+impl<A, B, T> View for ZipSingletons<A, B, T>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    type Msg = ();
+}
+
+impl<A, B, T> SingletonView for ZipSingletons<A, B, T>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    type Item = T;
+
+    fn get(&self) -> T {
+        (self.f)(self.a.get(), self.b.get())
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Values of all items of a sequence of singletons,
+/// notified whenever the sequence or one of its items changes.
+/// Every item port is taken from the sequence once,
+/// when its index is notified.
+pub fn collect_singletons<T>(
+    items: OuterViewPort<dyn SequenceView<Item = OuterViewPort<dyn SingletonView<Item = T>>>>,
+) -> OuterViewPort<dyn SingletonView<Item = Vec<T>>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let out_port = ViewPort::new();
+    let mut proj_helper = ProjectionHelper::new(out_port.update_hooks.clone());
+    let proj = Arc::new(RwLock::new(CollectSingletons {
+        seq: proj_helper.new_sequence_arg(0, items, |s: &mut CollectSingletons<T>, idx| {
+            s.update_item(*idx);
+            s.cast.notify(&());
+        }),
+        items: Vec::new(),
+        cast: out_port.inner().get_broadcast(),
+        proj_helper,
+    }));
+
+    {
+        let mut p = proj.write().unwrap();
+        p.proj_helper.set_proj(&proj);
+        for idx in 0 .. p.seq.len().unwrap_or(0) {
+            p.update_item(idx);
+        }
+    }
+    out_port.inner().set_view(Some(proj));
+    out_port.into_outer()
+}
+
+struct CollectSingletons<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    seq: Arc<dyn SequenceView<Item = OuterViewPort<dyn SingletonView<Item = T>>>>,
+
+    /// views of the items, argument `idx + 1` of the projection
+    items: Vec<Option<Arc<dyn SingletonView<Item = T>>>>,
+
+    cast: Arc<RwLock<ObserverBroadcast<dyn SingletonView<Item = Vec<T>>>>>,
+    proj_helper: ProjectionHelper<usize, Self>,
+}
+
+impl<T> CollectSingletons<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// observe the port now at `idx`, or stop observing if there is none
+    fn update_item(&mut self, idx: usize) {
+        self.proj_helper.remove_arg(&(idx + 1));
+
+        let item = self.seq.get(&idx).map(|port| {
+            self.proj_helper.new_singleton_arg(idx + 1, port, |s: &mut Self, _msg| s.cast.notify(&()))
+        });
+
+        if idx >= self.items.len() {
+            self.items.resize(idx + 1, None);
+        }
+        self.items[idx] = item;
+
+        let len = self.seq.len().unwrap_or(0);
+        while self.items.len() > len {
+            self.items.pop();
+            self.proj_helper.remove_arg(&(self.items.len() + 1));
+        }
+    }
+}
+
+impl<T> View for CollectSingletons<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Msg = ();
+}
+
+impl<T> SingletonView for CollectSingletons<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Item = Vec<T>;
+
+    fn get(&self) -> Vec<T> {
+        self.items.iter()
+            .filter_map(|item| item.as_ref().map(|v| v.get()))
+            .collect()
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Items of a vector as sequence. Only the indices
+/// whose item differs from the previous vector are notified.
+pub fn vec_items<T>(
+    vec: OuterViewPort<dyn SingletonView<Item = Vec<T>>>,
+) -> OuterViewPort<dyn SequenceView<Item = T>>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    let out_port = ViewPort::new();
+    let mut proj_helper = ProjectionHelper::new(out_port.update_hooks.clone());
+    let proj = Arc::new(RwLock::new(VecItems {
+        vec: proj_helper.new_singleton_arg(0, vec, |s: &mut VecItems<T>, _msg| s.update()),
+        items: Vec::new(),
+        cast: out_port.inner().get_broadcast(),
+        proj_helper,
+    }));
+
+    {
+        let mut p = proj.write().unwrap();
+        p.proj_helper.set_proj(&proj);
+        p.items = p.vec.get();
+    }
+    out_port.inner().set_view(Some(proj));
+    out_port.into_outer()
+}
+
+struct VecItems<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    vec: Arc<dyn SingletonView<Item = Vec<T>>>,
+
+    /// last value of `vec`
+    items: Vec<T>,
+
+    cast: Arc<RwLock<ObserverBroadcast<dyn SequenceView<Item = T>>>>,
+    proj_helper: ProjectionHelper<usize, Self>,
+}
+
+impl<T> VecItems<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    fn update(&mut self) {
+        let old = std::mem::replace(&mut self.items, self.vec.get());
+        for idx in 0 .. old.len().max(self.items.len()) {
+            if old.get(idx) != self.items.get(idx) {
+                self.cast.notify(&idx);
+            }
+        }
+    }
+}
+
+impl<T> View for VecItems<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    type Msg = usize;
+}
+
+impl<T> SequenceView for VecItems<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    type Item = T;
+
+    fn len(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+
+    fn get(&self, idx: &usize) -> Option<T> {
+        self.items.get(*idx).cloned()
+    }
+}
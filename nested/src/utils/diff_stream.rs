@@ -0,0 +1,208 @@
+use {
+    r3vi::{
+        view::{Observer, OuterViewPort, sequence::*},
+        buffer::vec::*,
+    },
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    std::{
+        io::{Read, Write},
+        sync::{Arc, RwLock},
+    },
+};
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+/// Incremental update of a sequence.
+/// A diff stream is a bincode encoded sequence of these
+/// messages, starting with `Clear` followed by the initial items.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum SequenceDiff<T> {
+    Clear,
+    Insert { idx: usize, val: T },
+    Remove { idx: usize },
+    Update { idx: usize, val: T },
+}
+
+/// Observes a `SequenceView` and writes its changes as diff stream.
+/// Writing stops at the first error, see `error()`.
+pub struct DiffStreamWriter<T, W: Write> {
+    out: W,
+    view: Option<Arc<dyn SequenceView<Item = T>>>,
+
+    /// length of the sequence as known by the receiver
+    sent_len: usize,
+
+    error: Option<bincode::Error>,
+}
+
+impl<T: Serialize, W: Write> DiffStreamWriter<T, W> {
+    pub fn new(out: W) -> Self {
+        DiffStreamWriter {
+            out,
+            view: None,
+            sent_len: 0,
+            error: None,
+        }
+    }
+
+    /// the error that ended the stream, if any
+    pub fn error(&self) -> Option<&bincode::Error> {
+        self.error.as_ref()
+    }
+
+    /// run `f` unless the stream already failed, keeping its error
+    fn try_send(&mut self, f: impl FnOnce(&mut Self) -> bincode::Result<()>) {
+        if self.error.is_none() {
+            if let Err(err) = f(self) {
+                self.error = Some(err);
+            }
+        }
+    }
+
+    fn send(&mut self, diff: &SequenceDiff<T>) -> bincode::Result<()> {
+        bincode::serialize_into(&mut self.out, diff)
+    }
+
+    fn send_changes(&mut self, idx: usize) -> bincode::Result<()> {
+        if let Some(view) = self.view.clone() {
+            let len = view.len().unwrap_or(0);
+
+            // sequence got shorter
+            while self.sent_len > len {
+                self.sent_len -= 1;
+                self.send(&SequenceDiff::Remove { idx: self.sent_len })?;
+            }
+
+            if idx < self.sent_len {
+                if let Some(val) = view.get(&idx) {
+                    self.send(&SequenceDiff::Update { idx, val })?;
+                }
+            } else {
+                // sequence got longer, fill up to `idx`
+                while self.sent_len <= idx && self.sent_len < len {
+                    match view.get(&self.sent_len) {
+                        Some(val) => self.send(&SequenceDiff::Insert { idx: self.sent_len, val })?,
+                        None => break,
+                    }
+                    self.sent_len += 1;
+                }
+            }
+
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+
+    fn send_all(&mut self) -> bincode::Result<()> {
+        self.send(&SequenceDiff::Clear)?;
+        self.sent_len = 0;
+
+        let len = self.view.as_ref().and_then(|v| v.len()).unwrap_or(0);
+        if len > 0 {
+            self.send_changes(len - 1)?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+impl<T, W> Observer<dyn SequenceView<Item = T>> for DiffStreamWriter<T, W>
+where
+    T: Clone + Serialize + Send + Sync + 'static,
+    W: Write + Send + Sync,
+{
+    fn reset(&mut self, view: Option<Arc<dyn SequenceView<Item = T>>>) {
+        self.view = view;
+        self.try_send(|w| w.send_all());
+    }
+
+    fn notify(&mut self, idx: &usize) {
+        self.try_send(|w| w.send_changes(*idx));
+    }
+}
+
+//<<<<>>>><<>><><<>><<<*>>><<>><><<>><<<<>>>>
+
+pub trait SerializeDiffStream<T> {
+    /// Write all changes of the sequence to `out`.
+    /// The stream lasts as long as the returned observer is kept alive.
+    fn serialize_diff_stream<W: Write + Send + Sync + 'static>(
+        &self,
+        out: W,
+    ) -> Arc<RwLock<DiffStreamWriter<T, W>>>;
+}
+
+impl<T> SerializeDiffStream<T> for OuterViewPort<dyn SequenceView<Item = T>>
+where
+    T: Clone + Serialize + Send + Sync + 'static,
+{
+    fn serialize_diff_stream<W: Write + Send + Sync + 'static>(
+        &self,
+        out: W,
+    ) -> Arc<RwLock<DiffStreamWriter<T, W>>> {
+        let writer = Arc::new(RwLock::new(DiffStreamWriter::new(out)));
+        self.add_observer(writer.clone());
+        writer
+    }
+}
+
+pub trait ApplyDiffStream<T> {
+    /// Fails with `InvalidData` if the index is out of range.
+    fn apply_diff(&mut self, diff: SequenceDiff<T>) -> std::io::Result<()>;
+
+    /// Apply diffs read from `input` until the stream ends.
+    /// A malformed stream is reported as `InvalidData`, diffs up
+    /// to the offending one are applied.
+    fn apply_diff_stream<R: Read>(&mut self, mut input: R) -> bincode::Result<()>
+    where
+        T: DeserializeOwned,
+    {
+        loop {
+            match bincode::deserialize_from(&mut input) {
+                Ok(diff) => self.apply_diff(diff)?,
+                Err(err) => {
+                    return match *err {
+                        bincode::ErrorKind::Io(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(()),
+                        _ => Err(err),
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl<T> ApplyDiffStream<T> for VecBuffer<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn apply_diff(&mut self, diff: SequenceDiff<T>) -> std::io::Result<()> {
+        let len = self.len();
+        let check = |idx: usize, max: usize| {
+            if idx < max {
+                Ok(())
+            } else {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("diff index {} out of range for length {}", idx, len)
+                ))
+            }
+        };
+
+        match diff {
+            SequenceDiff::Clear => self.clear(),
+            SequenceDiff::Insert { idx, val } => {
+                check(idx, len + 1)?;
+                self.insert(idx, val);
+            }
+            SequenceDiff::Remove { idx } => {
+                check(idx, len)?;
+                self.remove(idx);
+            }
+            SequenceDiff::Update { idx, val } => {
+                check(idx, len)?;
+                *self.get_mut(idx) = val;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 pub mod bimap;
+pub mod combine;
+pub mod diff_stream;
 pub mod modulo;
-pub mod color;
 
 pub use modulo::modulo;
 pub use bimap::Bimap;
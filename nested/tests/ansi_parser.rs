@@ -0,0 +1,195 @@
+//! Runs the terminal emulator against escape sequences
+//! recorded in `tests/fixtures/vt/` and compares the resulting
+//! screen with the one of a reference terminal (see `record.sh`).
+
+use {
+    nested::terminal::ansi_parser::TerminalEmulator,
+    cgmath::{Point2, Vector2},
+    std::path::PathBuf,
+};
+
+struct Fixture {
+    input: Vec<u8>,
+    size: Vector2<i16>,
+    cursor: Point2<i16>,
+    lines: Vec<String>,
+}
+
+fn fixture_path(name: &str, ext: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/vt")
+        .join(format!("{}.{}", name, ext))
+}
+
+fn load_fixture(name: &str) -> Fixture {
+    let input = std::fs::read(fixture_path(name, "in")).unwrap();
+    let expected = std::fs::read_to_string(fixture_path(name, "expected")).unwrap();
+    let mut lines = expected.lines();
+
+    let size: Vec<i16> = lines.next().unwrap()
+        .split('x')
+        .map(|n| n.parse().unwrap())
+        .collect();
+    let cursor: Vec<i16> = lines.next().unwrap()
+        .split_whitespace()
+        .skip(1)
+        .map(|n| n.parse().unwrap())
+        .collect();
+
+    let size = Vector2::new(size[0], size[1]);
+    let mut lines: Vec<String> = lines.map(String::from).collect();
+    lines.resize(size.y as usize, String::new());
+
+    Fixture {
+        input,
+        size,
+        cursor: Point2::new(cursor[0], cursor[1]),
+        lines,
+    }
+}
+
+fn screen_lines(term: &TerminalEmulator) -> Vec<String> {
+    (0 .. term.size().y)
+        .map(|y| term.get_line(y).trim_end().to_string())
+        .collect()
+}
+
+fn run_fixture(name: &str) {
+    let fixture = load_fixture(name);
+
+    let mut term = TerminalEmulator::new(fixture.size);
+    term.process(&fixture.input);
+
+    assert_eq!(screen_lines(&term), fixture.lines, "screen of fixture `{}`", name);
+    assert_eq!(term.get_cursor_pos(), fixture.cursor, "cursor of fixture `{}`", name);
+}
+
+#[test]
+fn wrap() {
+    run_fixture("wrap");
+}
+
+#[test]
+fn erase() {
+    run_fixture("erase");
+}
+
+#[test]
+fn insert_delete() {
+    run_fixture("insert_delete");
+}
+
+#[test]
+fn scroll_region() {
+    run_fixture("scroll_region");
+}
+
+#[test]
+fn scroll_su_sd() {
+    run_fixture("scroll_su_sd");
+}
+
+#[test]
+fn alt_screen() {
+    run_fixture("alt_screen");
+}
+
+#[test]
+fn dec_modes() {
+    run_fixture("dec_modes");
+}
+
+#[test]
+fn tabs() {
+    run_fixture("tabs");
+}
+
+#[test]
+fn line_drawing() {
+    run_fixture("line_drawing");
+}
+
+#[test]
+fn wide_chars() {
+    run_fixture("wide_chars");
+}
+
+#[test]
+fn save_restore() {
+    run_fixture("save_restore");
+}
+
+/// parameters beyond the screen (and beyond `i16`) are clamped
+#[test]
+fn oversized_params() {
+    run_fixture("oversized_params");
+}
+
+#[test]
+fn vim_session() {
+    run_fixture("vim_session");
+}
+
+/// sequences may be split across reads at any byte
+#[test]
+fn byte_by_byte() {
+    let fixture = load_fixture("vim_session");
+
+    let mut term = TerminalEmulator::new(fixture.size);
+    for byte in fixture.input.iter() {
+        term.process(&[ *byte ]);
+    }
+
+    assert_eq!(screen_lines(&term), fixture.lines);
+    assert_eq!(term.get_cursor_pos(), fixture.cursor);
+}
+
+#[test]
+fn alternate_screen_mode() {
+    let mut term = TerminalEmulator::new(Vector2::new(10, 3));
+    term.process(b"shell");
+    term.process(b"\x1b[?1049h");
+    assert!(term.get_modes().alternate_screen);
+    assert_eq!(term.get_line(0).trim_end(), "");
+
+    term.process(b"\x1b[?1049l");
+    assert!(!term.get_modes().alternate_screen);
+    assert_eq!(term.get_line(0).trim_end(), "shell");
+}
+
+#[test]
+fn status_reports() {
+    let mut term = TerminalEmulator::new(Vector2::new(10, 5));
+    term.process(b"\x1b[3;4H\x1b[6n\x1b[5n");
+    assert_eq!(term.take_responses(), b"\x1b[3;4R\x1b[0n".to_vec());
+
+    term.process(b"\x1b[2;4r\x1bP$qr\x1b\\");
+    assert_eq!(term.take_responses(), b"\x1bP1$r2;4r\x1b\\".to_vec());
+    assert!(term.take_responses().is_empty());
+}
+
+#[test]
+fn dirty_cells() {
+    let mut term = TerminalEmulator::new(Vector2::new(4, 2));
+    term.take_dirty();
+
+    term.process(b"ab");
+    let mut dirty = term.take_dirty();
+    dirty.sort_by_key(|p| p.x);
+    assert_eq!(dirty, vec![ Point2::new(0, 0), Point2::new(1, 0) ]);
+
+    term.process(b"\x1b[2J");
+    assert_eq!(term.take_dirty().len(), 8);
+}
+
+#[test]
+fn resize_keeps_cursor_line() {
+    let mut term = TerminalEmulator::new(Vector2::new(10, 4));
+    term.process(b"1\r\n2\r\n3\r\n4");
+    term.resize(Vector2::new(5, 2));
+
+    assert_eq!(term.get_line(0).trim_end(), "3");
+    assert_eq!(term.get_line(1).trim_end(), "4");
+    assert_eq!(term.get_cursor_pos(), Point2::new(1, 1));
+    assert_eq!(term.get_scroll_region(), (0, 1));
+}
@@ -0,0 +1,119 @@
+//! Applies encoded sequence diffs to a `VecBuffer`.
+
+use {
+    nested::utils::diff_stream::{ApplyDiffStream, SequenceDiff, SerializeDiffStream},
+    r3vi::buffer::vec::VecBuffer,
+    std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// in-memory stream that stays readable while the writer owns it
+#[derive(Clone, Default)]
+struct SharedBytes(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBytes {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// accepts nothing
+struct BrokenPipe;
+
+impl Write for BrokenPipe {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn encode(diffs: &[SequenceDiff<u32>]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for diff in diffs.iter() {
+        bincode::serialize_into(&mut bytes, diff).unwrap();
+    }
+    bytes
+}
+
+fn contents(buf: &VecBuffer<u32>) -> Vec<u32> {
+    (0 .. buf.len()).map(|idx| buf.get(idx)).collect()
+}
+
+#[test]
+fn stream_is_applied() {
+    let mut buf = VecBuffer::new();
+    let stream = encode(&[
+        SequenceDiff::Clear,
+        SequenceDiff::Insert { idx: 0, val: 1 },
+        SequenceDiff::Insert { idx: 1, val: 3 },
+        SequenceDiff::Insert { idx: 1, val: 2 },
+        SequenceDiff::Update { idx: 2, val: 4 },
+        SequenceDiff::Remove { idx: 0 },
+    ]);
+
+    buf.apply_diff_stream(stream.as_slice()).unwrap();
+    assert_eq!(contents(&buf), vec![ 2, 4 ]);
+}
+
+#[test]
+fn malformed_stream_is_rejected() {
+    let malformed = [
+        SequenceDiff::Insert { idx: 2, val: 7 },
+        SequenceDiff::Remove { idx: 1 },
+        SequenceDiff::Update { idx: 1, val: 7 },
+    ];
+
+    for diff in malformed.iter() {
+        let mut buf = VecBuffer::new();
+        let stream = encode(&[ SequenceDiff::Insert { idx: 0, val: 1 }, diff.clone() ]);
+
+        let err = buf.apply_diff_stream(stream.as_slice()).unwrap_err();
+        match *err {
+            bincode::ErrorKind::Io(ref e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+            ref e => panic!("expected invalid data for {:?}, got {}", diff, e),
+        }
+        // diffs before the malformed one are kept
+        assert_eq!(contents(&buf), vec![ 1 ]);
+    }
+}
+
+#[test]
+fn serialized_stream_round_trips() {
+    let mut src = VecBuffer::with_data(vec![ 1, 2, 3 ]);
+    let bytes = SharedBytes::default();
+    let _writer = src.get_port().to_sequence().serialize_diff_stream(bytes.clone());
+
+    src.insert(1, 7);
+    src.push(9);
+    src.remove(0);
+    *src.get_mut(2) = 5;
+    src.insert(4, 8);
+    src.remove(2);
+
+    let mut dst = VecBuffer::new();
+    dst.apply_diff_stream(bytes.0.lock().unwrap().as_slice()).unwrap();
+    assert_eq!(contents(&dst), contents(&src));
+    assert_eq!(contents(&dst), vec![ 7, 2, 9, 8 ]);
+}
+
+#[test]
+fn write_errors_stop_the_stream() {
+    let mut src = VecBuffer::<u32>::new();
+    let writer = src.get_port().to_sequence().serialize_diff_stream(BrokenPipe);
+    src.push(1);
+
+    let writer = writer.read().unwrap();
+    match writer.error().map(|e| &**e) {
+        Some(bincode::ErrorKind::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::BrokenPipe),
+        other => panic!("expected the write error, got {:?}", other),
+    }
+}
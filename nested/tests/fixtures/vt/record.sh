@@ -0,0 +1,33 @@
+#!/bin/sh
+# Record the expected screen for an escape sequence fixture
+# by replaying it in tmux, which serves as reference terminal.
+#
+#   ./record.sh <name> <width> <height>
+#
+# reads <name>.in and writes <name>.expected:
+#   first line:  <width>x<height>
+#   second line: cursor <x> <y>
+#   then one line per row, trailing spaces removed
+#
+# tmux prints DEC line drawing chars as their ASCII codes,
+# those have to be replaced by hand.
+
+set -e
+cd "$(dirname "$0")"
+
+name=$1
+w=$2
+h=$3
+session=vt-fixture-$$
+
+tmux -f /dev/null new-session -d -s "$session" -x "$w" -y "$h" \
+     "stty raw -echo; cat '$PWD/$name.in'; sleep 60"
+sleep 1
+
+{
+    echo "${w}x${h}"
+    tmux display -p -t "$session" 'cursor #{cursor_x} #{cursor_y}'
+    tmux capture-pane -p -t "$session" | sed 's/ *$//'
+} > "$name.expected"
+
+tmux kill-session -t "$session"
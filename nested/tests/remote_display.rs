@@ -0,0 +1,144 @@
+//! Attaches remote clients to a `RemoteServer` over local sockets.
+
+use {
+    nested::terminal::{
+        protocol::{ProtocolError, MAGIC},
+        remote::{ClientMessage, RemoteClient, RemoteServer, ServerMessage, REMOTE_VERSION},
+        TerminalAtom, TerminalEvent,
+    },
+    r3vi::buffer::index_hashmap::IndexBuffer,
+    cgmath::{Point2, Vector2},
+    std::{
+        os::unix::net::UnixStream,
+        time::{Duration, Instant},
+    },
+    termion::event::{Event, Key},
+};
+
+/// poll until `cond` holds, the server works in background threads
+fn wait_for(cond: impl Fn() -> bool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(5) {
+        if cond() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+fn connect(server: &RemoteServer) -> RemoteClient {
+    let (server_stream, client_stream) = UnixStream::pair().unwrap();
+    server.attach(server_stream.try_clone().unwrap(), server_stream);
+    RemoteClient::connect(client_stream.try_clone().unwrap(), client_stream).unwrap()
+}
+
+#[test]
+fn diffs_reach_all_clients() {
+    let mut buf = IndexBuffer::<Point2<i16>, TerminalAtom>::new();
+    buf.insert(Point2::new(0, 0), TerminalAtom::from('a'));
+
+    let server = RemoteServer::new(buf.get_port());
+    let client1 = connect(&server);
+    let client2 = connect(&server);
+    assert_eq!(client1.get_version(), REMOTE_VERSION);
+
+    // initial content
+    assert!(wait_for(|| client1.get(&Point2::new(0, 0)) == Some(TerminalAtom::from('a'))));
+    assert!(wait_for(|| client2.get(&Point2::new(0, 0)) == Some(TerminalAtom::from('a'))));
+
+    // changes
+    buf.insert(Point2::new(3, 1), TerminalAtom::from('b'));
+    buf.remove(Point2::new(0, 0));
+
+    for client in [&client1, &client2].iter() {
+        assert!(wait_for(|| client.get(&Point2::new(3, 1)) == Some(TerminalAtom::from('b'))));
+        assert!(wait_for(|| client.get(&Point2::new(0, 0)).is_none()));
+    }
+}
+
+#[test]
+fn events_reach_server() {
+    let buf = IndexBuffer::<Point2<i16>, TerminalAtom>::new();
+    let mut server = RemoteServer::new(buf.get_port());
+    let client = connect(&server);
+
+    let resize = TerminalEvent::Resize(Vector2::new(100, 30));
+    let key = TerminalEvent::Input(Event::Key(Key::Ctrl('x')));
+    client.send_event(&resize).unwrap();
+    client.send_event(&key).unwrap();
+
+    async_std::task::block_on(async {
+        assert_eq!(server.next_event().await, resize);
+        assert_eq!(server.next_event().await, key);
+    });
+}
+
+#[test]
+fn unix_socket_listener() {
+    let path = std::env::temp_dir().join(format!("nested-remote-{}.sock", std::process::id()));
+    std::fs::remove_file(&path).ok();
+
+    let mut buf = IndexBuffer::<Point2<i16>, TerminalAtom>::new();
+    buf.insert(Point2::new(1, 2), TerminalAtom::from('x'));
+
+    let server = RemoteServer::new(buf.get_port());
+    server.listen_unix(&path).unwrap();
+
+    let client = RemoteClient::connect_unix(&path).unwrap();
+    assert!(wait_for(|| client.get(&Point2::new(1, 2)) == Some(TerminalAtom::from('x'))));
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn unsupported_version_is_rejected() {
+    let buf = IndexBuffer::<Point2<i16>, TerminalAtom>::new();
+    let server = RemoteServer::new(buf.get_port());
+
+    let (server_stream, mut client_stream) = UnixStream::pair().unwrap();
+    server.attach(server_stream.try_clone().unwrap(), server_stream);
+
+    bincode::serialize_into(&mut client_stream, &ClientMessage::Hello {
+        magic: MAGIC,
+        min_version: REMOTE_VERSION + 1,
+        max_version: REMOTE_VERSION + 5,
+    }).unwrap();
+
+    match bincode::deserialize_from(&mut client_stream).unwrap() {
+        ServerMessage::Reject(_) => {}
+        msg => panic!("expected rejection, got {:?}", msg),
+    }
+}
+
+#[test]
+fn bad_magic_is_rejected() {
+    let buf = IndexBuffer::<Point2<i16>, TerminalAtom>::new();
+    let server = RemoteServer::new(buf.get_port());
+
+    let (server_stream, client_stream) = UnixStream::pair().unwrap();
+    server.attach(server_stream.try_clone().unwrap(), server_stream);
+
+    let mut garbage = client_stream.try_clone().unwrap();
+    bincode::serialize_into(&mut garbage, &ClientMessage::Hello {
+        magic: *b"XXXX",
+        min_version: REMOTE_VERSION,
+        max_version: REMOTE_VERSION,
+    }).unwrap();
+
+    match bincode::deserialize_from(&mut &client_stream).unwrap() {
+        ServerMessage::Reject(_) => {}
+        msg => panic!("expected rejection, got {:?}", msg),
+    }
+
+    // a rejected handshake surfaces as error on the client side
+    let (server_stream, client_stream) = UnixStream::pair().unwrap();
+    std::thread::spawn(move || {
+        let mut s = server_stream;
+        bincode::serialize_into(&mut s, &ServerMessage::Reject("go away".into())).unwrap();
+    });
+    match RemoteClient::connect(client_stream.try_clone().unwrap(), client_stream) {
+        Err(ProtocolError::Rejected(reason)) => assert_eq!(reason, "go away"),
+        _ => panic!("expected rejection"),
+    }
+}
@@ -1,67 +1,78 @@
+//! Shows the atom streams (see `nested::terminal::protocol`)
+//! of any number of clients connecting to a unix socket.
+//! Each client gets its own layer, stacked in the order
+//! of connection; the layer is removed when the client disconnects.
+//!
+//! usage: `display_server [SOCKET]`, quit with `Ctrl-C`.
+
 use {
-    cgmath::{Point2, Vector2},
-    nested::terminal::{TerminalAtom, TerminalStyle},
-    std::io::{stdout, Read, Write},
-    termion::raw::IntoRawMode,
+    cgmath::Point2,
+    nested::terminal::{
+        protocol::{read_atom, read_header},
+        Terminal, TerminalAtom, TerminalCompositor, TerminalEvent,
+    },
+    r3vi::{buffer::index_hashmap::IndexBuffer, view::ViewPort},
+    std::{
+        os::unix::net::{UnixListener, UnixStream},
+        sync::{Arc, RwLock},
+    },
+    termion::event::{Event, Key},
 };
 
-fn main() {
-    let mut out = stdout().into_raw_mode().unwrap();
-    write!(
-        out,
-        "{}{}{}",
-        termion::cursor::Hide,
-        termion::cursor::Goto(1, 1),
-        termion::style::Reset
-    )
-    .unwrap();
+const DEFAULT_SOCKET: &str = "/tmp/nested-display.sock";
 
-    let mut cur_pos = Point2::<i16>::new(0, 0);
-    let mut cur_style = TerminalStyle::default();
+fn serve_client(compositor: Arc<RwLock<TerminalCompositor>>, mut stream: UnixStream) {
+    if let Err(err) = read_header(&mut stream) {
+        eprintln!("rejecting client: {}", err);
+        return;
+    }
 
-    let mut input = std::io::stdin();
+    let mut buf = IndexBuffer::<Point2<i16>, TerminalAtom>::new();
+    let layer = compositor.write().unwrap().push(buf.get_port());
 
-    loop {
-        match bincode::deserialize_from::<_, (Point2<i16>, Option<TerminalAtom>)>(input.by_ref()) {
-            Ok((pos, atom)) => {
-                if pos != cur_pos {
-                    write!(
-                        out,
-                        "{}",
-                        termion::cursor::Goto(pos.x as u16 + 1, pos.y as u16 + 1)
-                    )
-                    .unwrap();
-                }
+    while let Ok((pos, atom)) = read_atom(&mut stream) {
+        match atom {
+            Some(atom) => buf.insert(pos, atom),
+            None => buf.remove(pos),
+        }
+    }
+
+    compositor.write().unwrap().remove(layer);
+}
+
+#[async_std::main]
+async fn main() {
+    let socket = std::env::args().nth(1).unwrap_or(DEFAULT_SOCKET.into());
+
+    // a stale socket of a previous run would make bind() fail
+    std::fs::remove_file(&socket).ok();
+    let listener = UnixListener::bind(&socket).expect("could not bind socket");
 
-                if let Some(atom) = atom {
-                    if cur_style != atom.style {
-                        cur_style = atom.style;
-                        write!(out, "{}", atom.style).expect("");
-                    }
+    let port = ViewPort::new();
+    let compositor = TerminalCompositor::new(port.inner());
 
-                    write!(out, "{}", atom.c.unwrap_or(' ')).expect("");
-                } else {
-                    write!(out, "{} ", termion::style::Reset).expect("");
-                    cur_style = TerminalStyle::default();
-                }
+    let mut term = Terminal::new(port.outer());
+    let term_writer = term.get_writer();
 
-                cur_pos = pos + Vector2::new(1, 0);
+    async_std::task::spawn(async move {
+        term_writer.show().await.expect("output error!");
+    });
 
-                out.flush().unwrap();
-            }
-            Err(err) => {
-                match *err {
-                    bincode::ErrorKind::Io(_io_error) => break,
-                    err => {
-                        eprintln!("deserialization error\n{:?}", err);
-                    }
-                }
-                break;
-            }
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let compositor = compositor.clone();
+            std::thread::spawn(move || serve_client(compositor, stream));
+        }
+    });
+
+    loop {
+        match term.next_event().await {
+            // the writer repaints everything when the size changed
+            TerminalEvent::Resize(_) => term.redraw(),
+            TerminalEvent::Input(Event::Key(Key::Ctrl('c'))) => break,
+            _ => {}
         }
     }
 
-    // restore conventional terminal settings
-    write!(out, "{}", termion::cursor::Show).unwrap();
-    out.flush().unwrap();
+    std::fs::remove_file(&socket).ok();
 }
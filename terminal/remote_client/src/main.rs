@@ -0,0 +1,46 @@
+//! Thin client attaching to an editor that runs as
+//! `nested::terminal::remote::RemoteServer`.
+//!
+//! usage: `remote_client SOCKET` or `remote_client HOST:PORT`,
+//! detach with `Ctrl-\`.
+
+use {
+    nested::terminal::{remote::RemoteClient, Terminal, TerminalEvent},
+    termion::event::{Event, Key},
+};
+
+#[async_std::main]
+async fn main() {
+    let addr = std::env::args().nth(1).expect("usage: remote_client SOCKET|HOST:PORT");
+
+    let client = if std::path::Path::new(&addr).exists() {
+        RemoteClient::connect_unix(&addr)
+    } else {
+        RemoteClient::connect_tcp(&addr)
+    };
+    let client = match client {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("could not attach to {}: {}", addr, err);
+            std::process::exit(1);
+        }
+    };
+
+    let mut term = Terminal::new(client.get_view());
+    let term_writer = term.get_writer();
+
+    async_std::task::spawn(async move {
+        term_writer.show().await.expect("output error!");
+    });
+
+    loop {
+        let event = term.next_event().await;
+        if event == TerminalEvent::Input(Event::Key(Key::Ctrl('\\'))) {
+            break;
+        }
+        if client.send_event(&event).is_err() {
+            // server went away
+            break;
+        }
+    }
+}